@@ -0,0 +1,224 @@
+// Standard Crate
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// Chess Crate
+use chess::prelude::*;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+//==================================================
+//=== Benchmark
+//==================================================
+
+/// Runs `f` repeatedly for about a second, then gives back the average time of a run
+fn measure<T>(mut f: impl FnMut() -> T) -> (Duration, u32) {
+    let mut runs = 0;
+    let start = Instant::now();
+
+    while start.elapsed() < Duration::from_secs(1) {
+        black_box(f());
+        runs += 1;
+    }
+
+    (start.elapsed() / runs, runs)
+}
+
+/// Prints the average time of a run of `f`
+fn bench<T>(name: &str, f: impl FnMut() -> T) {
+    let (average, runs) = measure(f);
+    println!("{:<32} {:>12.3?} ({} runs)", name, average, runs);
+}
+
+/// Prints the average time of a run of the `squares` and the `bitboards` implementation side by side
+///
+/// Panics when the two implementations give back different results
+fn compare<T: PartialEq + fmt::Debug>(
+    name: &str,
+    mut squares: impl FnMut() -> T,
+    mut bitboards: impl FnMut() -> T,
+) {
+    assert_eq!(squares(), bitboards(), "{}", name);
+
+    let (squares, _) = measure(squares);
+    let (bitboards, _) = measure(bitboards);
+    println!(
+        "{:<32} {:>12.3?} -> {:>10.3?} (squares -> bitboards)",
+        name, squares, bitboards
+    );
+}
+
+fn main() {
+    let start = Board::new();
+    let kiwipete = Board::from_fen(KIWIPETE).unwrap();
+
+    bench("perft start depth 3", || start.perft(3));
+    bench("perft kiwipete depth 2", || kiwipete.perft(2));
+    bench("legal_moves kiwipete", || {
+        kiwipete.legal_moves(&kiwipete.get_current_turn())
+    });
+    bench("test_valid_step_available", || {
+        kiwipete.test_valid_step_available(&Side::White)
+    });
+
+    compare(
+        "test_checked_status kiwipete",
+        || squares::test_checked_status(&kiwipete, &Side::White),
+        || kiwipete.test_checked_status(&Side::White),
+    );
+    compare(
+        "test_attacked_status kiwipete",
+        || squares::test_attacked_status(&kiwipete, &Pos::from_notation("E5"), &Side::Black),
+        || kiwipete.test_attacked_status(&Pos::from_notation("E5"), &Side::Black),
+    );
+    compare(
+        "attacked squares kiwipete",
+        || squares::count_attacked(&kiwipete, &Side::Black),
+        || {
+            squares::all_pos()
+                .filter(|pos| kiwipete.test_attacked_status(pos, &Side::Black))
+                .count()
+        },
+    );
+}
+
+//==================================================
+//=== Benchmark: Squares
+//==================================================
+
+/// Attack detection of the [`Board`] before the bitboards, it walks the `squares` from the attacked [`Pos`]
+mod squares {
+    use chess::prelude::*;
+
+    const KNIGHT_OFFSETS: [Pos; 8] = [
+        Pos::new(1, 2),
+        Pos::new(2, 1),
+        Pos::new(2, -1),
+        Pos::new(1, -2),
+        Pos::new(-1, -2),
+        Pos::new(-2, -1),
+        Pos::new(-2, 1),
+        Pos::new(-1, 2),
+    ];
+
+    /// Gives back every [`Pos`] on the board
+    pub fn all_pos() -> impl Iterator<Item = Pos> {
+        (0..64).map(|square| Pos::new(square % 8, square / 8))
+    }
+
+    /// Returns true when the King of `side` is attacked
+    pub fn test_checked_status(board: &Board, side: &Side) -> bool {
+        let king = Unit::King(*side, true);
+        all_pos()
+            .find(|pos| matches!(board.get_unit(pos), Some(unit) if unit.get_id() == king.get_id()))
+            .is_some_and(|king_pos| test_attacked_status(board, &king_pos, &side.oppose()))
+    }
+
+    /// Gives back the number of squares attacked by `side`
+    pub fn count_attacked(board: &Board, side: &Side) -> usize {
+        all_pos()
+            .filter(|pos| test_attacked_status(board, pos, side))
+            .count()
+    }
+
+    /// Returns true when any [`Unit`] of `side` attacks the given `pos`
+    pub fn test_attacked_status(board: &Board, pos: &Pos, side: &Side) -> bool {
+        let mut positions = Vec::new();
+
+        // Find Units All Direction
+        for direction in Pos::ALL_DIRECTIONS {
+            if let Some(unit_pos) = find_unit_direction(board, *pos, direction) {
+                positions.push(unit_pos)
+            }
+        }
+
+        // Find Knights
+        positions.extend(find_knights(board, *pos));
+
+        // Filter Defending Units
+        positions.retain(|unit_pos| board.get_unit(unit_pos).unwrap().get_side() == *side);
+
+        for unit_pos in positions {
+            let unit = board.get_unit(&unit_pos).unwrap();
+
+            // Check if any Unit is able to take on the given Pos
+            if test_checking(&unit, &unit_pos, pos) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Searches for a [`Unit`] by repeatedly calling the `step_function` on the given [`Pos`]
+    fn find_unit_direction(
+        board: &Board,
+        pos: Pos,
+        step_function: &dyn Fn(&Pos) -> Pos,
+    ) -> Option<Pos> {
+        let mut check_pos = step_function(&pos);
+        for _ in 0..8 {
+            // No Unit found
+            if !check_pos.is_onboard() {
+                return None;
+            }
+
+            // Unit found
+            if board.get_unit(&check_pos).is_some() {
+                break;
+            }
+
+            check_pos = step_function(&check_pos);
+        }
+
+        Some(check_pos)
+    }
+
+    /// Searches for Knight [`Unit`]s around given [`Pos`]
+    fn find_knights(board: &Board, pos: Pos) -> Vec<Pos> {
+        let mut positions = Vec::new();
+
+        for offset_pos in KNIGHT_OFFSETS {
+            let check_pos = pos + offset_pos;
+
+            if !check_pos.is_onboard() {
+                continue;
+            }
+
+            if let Some(Unit::Knight(_)) = board.get_unit(&check_pos) {
+                positions.push(check_pos);
+            }
+        }
+
+        positions
+    }
+
+    /// Returns true if the `unit` at `unit_pos` attacks `king_pos`
+    fn test_checking(unit: &Unit, unit_pos: &Pos, king_pos: &Pos) -> bool {
+        let calc_pos = (*king_pos - *unit_pos).abs();
+
+        match unit {
+            Unit::Pawn(side, _) => {
+                // Validate Step Direction
+                match side {
+                    Side::Black if king_pos.y < unit_pos.y => return false,
+                    Side::White if king_pos.y > unit_pos.y => return false,
+                    _ => (),
+                };
+
+                calc_pos.x == 1 && calc_pos.y == 1
+            }
+            Unit::Bishop(_) => calc_pos.x == calc_pos.y && calc_pos.sum() != 0,
+            Unit::Knight(_) => {
+                (calc_pos.x == 1 && calc_pos.y == 2) || (calc_pos.x == 2 && calc_pos.y == 1)
+            }
+            Unit::Rook(_, _) => (calc_pos.x == 0 || calc_pos.y == 0) && calc_pos.sum() != 0,
+            Unit::Queen(_) => {
+                (calc_pos.x == 0 || calc_pos.y == 0 || calc_pos.x == calc_pos.y)
+                    && calc_pos.sum() != 0
+            }
+            Unit::King(_, _) => calc_pos.x <= 1 && calc_pos.y <= 1,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 // Standard
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{fs, path::Path};
 
 // Chess Crate
@@ -6,9 +7,26 @@ extern crate chess;
 use chess::prelude::*;
 
 // UI Crate
-use eframe::egui::{Color32, Image, Response, RichText};
+use eframe::egui::{Color32, Response, RichText};
 use eframe::{egui, App};
 
+// FILES
+const PGN_PATH: &str = "game.pgn";
+
+// CLOCK
+const CLOCK_TIME: Duration = Duration::from_secs(5 * 60);
+const CLOCK_INCREMENT: Duration = Duration::from_secs(3);
+
+// COMPUTER
+/// Depth and time limit of the search for each strength
+const STRENGTHS: [(u32, Duration); 5] = [
+    (1, Duration::from_millis(100)),
+    (2, Duration::from_millis(200)),
+    (3, Duration::from_millis(400)),
+    (5, Duration::from_millis(700)),
+    (64, Duration::from_millis(1000)),
+];
+
 // SIZES
 const WIDTH: f32 = 1024.0;
 const HEIGHT: f32 = 860.0;
@@ -23,14 +41,35 @@ fn main() {
     ChessEguiApp::run()
 }
 
-#[derive(Default)]
 pub struct ChessEguiApp {
     chess: Game,
+    engine: Engine,
+    computer: Option<Side>,
+    strength: usize,
+    thinking: bool,
+    chess960: bool,
+    crazyhouse: bool,
+    dragged: Option<Unit>,
+}
+
+impl Default for ChessEguiApp {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ChessEguiApp {
     pub fn new() -> Self {
-        Self { chess: Game::new() }
+        Self {
+            chess: new_game(Game::new()),
+            engine: Engine::new(),
+            computer: None,
+            strength: 3,
+            thinking: false,
+            chess960: false,
+            crazyhouse: false,
+            dragged: None,
+        }
     }
 
     pub fn run() {
@@ -51,16 +90,349 @@ impl ChessEguiApp {
             }),
         );
     }
+
+    /// Starts a new game, the [`Engine`] forgets the former one
+    fn restart(&mut self, chess: Game) {
+        self.chess = new_game(chess);
+        self.engine.clear();
+        self.thinking = false;
+    }
+
+    /// Gives back a new [`Game`] from the standard or a random Chess960 start position, or a Crazyhouse game
+    fn new_start(&self) -> Game {
+        if self.crazyhouse {
+            Game::with_variant(Crazyhouse)
+        } else if self.chess960 {
+            let seed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.subsec_nanos());
+            Game::new_chess960((seed % CHESS960_COUNT as u32) as u16).unwrap_or_default()
+        } else {
+            Game::new()
+        }
+    }
+
+    /// Returns true when the computer takes the next move
+    fn test_computer_turn(&self) -> bool {
+        self.computer == Some(self.chess.get_current_turn())
+            && self.chess.get_game_state() == GameState::Playing
+    }
+
+    /// Searches and plays the move of the computer
+    ///
+    /// The search starts on the frame after the computer's turn comes, so the last move shows up first
+    fn play_computer(&mut self, ctx: &egui::Context) {
+        if !self.test_computer_turn() {
+            self.thinking = false;
+            return;
+        }
+
+        if !self.thinking {
+            self.thinking = true;
+            ctx.request_repaint();
+            return;
+        }
+
+        let (depth, time) = STRENGTHS[self.strength - 1];
+        let limit = Limit {
+            depth,
+            time: Some(time),
+            nodes: None,
+        };
+
+        if let Some(result) = self.engine.search(&self.chess, limit) {
+            println!("Computer: {} ({})", result.best_move, result.score);
+            if self.chess.play_move(&result.best_move) {
+                report(&self.chess);
+            }
+        }
+        self.thinking = false;
+    }
+}
+
+/// Prints the outcome when the game ended, otherwise the side which moves next
+fn report(chess: &Game) {
+    match chess.get_outcome() {
+        Some(outcome) => println!("{}", outcome),
+        None => println!("{} moves next!", chess.get_current_turn()),
+    }
+}
+
+/// Attaches a blitz [`Clock`] to the `chess` game
+fn new_game(mut chess: Game) -> Game {
+    chess.set_clock(Clock::new(TimeControl::fischer(
+        CLOCK_TIME,
+        CLOCK_INCREMENT,
+    )));
+    chess
 }
+
 impl App for ChessEguiApp {
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
+        // Computer
+        self.play_computer(ctx);
+        if self.computer.is_some() && self.chess.get_draw_offer().is_some() {
+            self.chess.decline_draw();
+        }
+
+        // Clocks
+        if self.chess.test_flag_fall() {
+            report(&self.chess);
+        }
+        ctx.request_repaint_after(Duration::from_millis(100));
+
+        egui::Area::new("Clocks")
+            .anchor(egui::Align2::LEFT_TOP, [25.0, 80.0])
+            .show(ctx, |ui| {
+                if let Some(clock) = self.chess.get_clock() {
+                    for side in [Side::Black, Side::White] {
+                        let color = if clock.is_running() && clock.get_turn() == side {
+                            Color32::WHITE
+                        } else {
+                            Color32::GRAY
+                        };
+
+                        let text = format!("{} {}", side, clock.get_remaining_str(side));
+                        ui.label(RichText::new(text).color(color).size(28.0));
+                    }
+                }
+            });
+
+        // Play vs Computer
+        egui::Area::new("Computer")
+            .anchor(egui::Align2::LEFT_TOP, [25.0, 200.0])
+            .show(ctx, |ui| {
+                let mut play_computer = self.computer.is_some();
+                if ui
+                    .checkbox(&mut play_computer, "Play vs computer")
+                    .changed()
+                {
+                    self.computer = play_computer.then_some(Side::Black);
+                }
+
+                if let Some(computer) = self.computer {
+                    ui.horizontal(|ui| {
+                        ui.label("Play as");
+                        for side in [Side::White, Side::Black] {
+                            if ui
+                                .radio(computer == side.oppose(), side.to_string())
+                                .clicked()
+                            {
+                                self.computer = Some(side.oppose());
+                            }
+                        }
+                    });
+
+                    ui.add(
+                        egui::Slider::new(&mut self.strength, 1..=STRENGTHS.len()).text("Strength"),
+                    );
+                }
+            });
+
         egui::Area::new("Headline")
             .anchor(egui::Align2::CENTER_TOP, [0.0, 25.0])
             .show(ctx, |ui| {
-                let side = format!("{} move!", &self.chess.get_current_turn());
-                ui.label(RichText::new(side).color(Color32::WHITE).size(28.0));
+                let headline = if self.thinking {
+                    String::from("Computer thinks...")
+                } else {
+                    format!("{} move!", &self.chess.get_current_turn())
+                };
+                ui.label(RichText::new(headline).color(Color32::WHITE).size(28.0));
             });
 
+        // History + Archive
+        egui::Area::new("Controls")
+            .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -25.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("New Game").clicked() {
+                        self.restart(self.new_start());
+                    }
+                    ui.checkbox(&mut self.chess960, "Chess960");
+                    ui.checkbox(&mut self.crazyhouse, "Crazyhouse");
+
+                    if ui.button("Undo").clicked() && self.chess.undo() {
+                        // Takes back the move of the computer too
+                        if self.test_computer_turn() {
+                            self.chess.undo();
+                        }
+                        report(&self.chess);
+                    }
+
+                    if ui.button("Redo").clicked() && self.chess.redo() {
+                        report(&self.chess);
+                    }
+
+                    if ui.button("Offer Draw").clicked() {
+                        let side = self.chess.get_current_turn();
+                        if self.chess.offer_draw(side) {
+                            println!("{} offers a draw!", side);
+                        }
+                    }
+
+                    if ui.button("Resign").clicked() {
+                        let side = self.chess.get_current_turn();
+                        if self.chess.resign(side) {
+                            report(&self.chess);
+                        }
+                    }
+
+                    if let Some(end_reason) = self.chess.get_claimable_draw() {
+                        if ui.button(format!("Claim Draw: {}", end_reason)).clicked()
+                            && self.chess.claim_draw()
+                        {
+                            report(&self.chess);
+                        }
+                    }
+
+                    if ui.button("Save PGN").clicked() {
+                        match fs::write(PGN_PATH, self.chess.to_pgn()) {
+                            Ok(_) => println!("Game saved: {}", PGN_PATH),
+                            Err(error) => println!("Can't save the game: {}", error),
+                        }
+                    }
+
+                    if ui.button("Load PGN").clicked() {
+                        match fs::read_to_string(PGN_PATH) {
+                            Ok(pgn) => match Game::from_pgn(&pgn) {
+                                Ok(chess) => self.restart(chess),
+                                Err(error) => println!("Can't load the game: {}", error),
+                            },
+                            Err(error) => println!("Can't load the game: {}", error),
+                        }
+                    }
+                });
+            });
+
+        // Move List
+        egui::Area::new("Moves")
+            .anchor(egui::Align2::RIGHT_TOP, [-25.0, 80.0])
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(HEIGHT - 160.0)
+                    .show(ui, |ui| {
+                        let moves = self.chess.get_san_moves();
+                        for (idx, moves) in moves.chunks(2).enumerate() {
+                            let text = format!("{}. {}", idx + 1, moves.join(" "));
+                            ui.label(RichText::new(text).color(Color32::WHITE).size(18.0));
+                        }
+                    });
+            });
+
+        // Promotion PopUp
+        if self.chess.is_promotion_pending() {
+            egui::Window::new("Promotion")
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        for promotion in Unit::PROMOTIONS {
+                            if ui.button(promotion.get_name()).clicked() {
+                                self.chess.promotion = Some(promotion);
+                            }
+                        }
+
+                        if ui.button("Cancel").clicked() {
+                            self.chess.clear_selection();
+                        }
+                    });
+                });
+        }
+
+        // Draw Offer PopUp
+        if let Some(side) = self.chess.get_draw_offer() {
+            egui::Window::new("Draw Offer")
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let text = format!("{} offers a draw", side);
+                    ui.label(RichText::new(text).color(Color32::WHITE).size(28.0));
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Accept").clicked() && self.chess.accept_draw() {
+                            report(&self.chess);
+                        }
+
+                        if ui.button("Decline").clicked() {
+                            self.chess.decline_draw();
+                        }
+                    });
+                });
+        }
+
+        // Game Over PopUp
+        if let GameState::Ending(side) = self.chess.get_game_state() {
+            egui::Window::new("Game Over")
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let text = match side {
+                        Some(side) => format!("{} won!", side),
+                        None => String::from("Draw!"),
+                    };
+                    ui.label(RichText::new(text).color(Color32::WHITE).size(28.0));
+
+                    if let Some(end_reason) = self.chess.get_end_reason() {
+                        ui.label(RichText::new(end_reason.to_string()).color(Color32::WHITE));
+                    }
+
+                    if ui.button("New Game").clicked() {
+                        self.restart(self.new_start());
+                    }
+                });
+        }
+
+        // Pockets, the units of the side in turn can be dragged onto the board
+        if self.chess.board_state.is_crazyhouse() {
+            egui::Area::new("Pockets")
+                .anchor(egui::Align2::LEFT_TOP, [25.0, 420.0])
+                .show(ctx, |ui| {
+                    for side in [Side::Black, Side::White] {
+                        ui.label(RichText::new(format!("{} pocket", side)).color(Color32::WHITE));
+                        ui.horizontal(|ui| {
+                            let pocket = *self.chess.board_state.get_pocket(&side);
+                            for unit in Pocket::UNITS.map(|unit| unit.set_side(side)) {
+                                let count = pocket.get_count(&unit);
+                                if count == 0 {
+                                    continue;
+                                }
+
+                                let response = ui.add(
+                                    unit_image(&unit)
+                                        .fit_to_exact_size((SQUARE_SIZE, SQUARE_SIZE).into())
+                                        .sense(egui::Sense::drag()),
+                                );
+                                ui.label(RichText::new(count.to_string()).color(Color32::WHITE));
+
+                                if response.drag_started()
+                                    && side == self.chess.get_current_turn()
+                                    && !self.test_computer_turn()
+                                {
+                                    self.dragged = Some(unit);
+                                }
+                            }
+                        });
+                    }
+                });
+        }
+
+        // Dragged pocket unit follows the pointer
+        let pointer = ctx.input(|i| i.pointer.interact_pos());
+        let released = ctx.input(|i| i.pointer.any_released());
+        if let (Some(unit), Some(pointer)) = (self.dragged, pointer) {
+            egui::Area::new("Dragged")
+                .fixed_pos(pointer - egui::vec2(SQUARE_SIZE, SQUARE_SIZE) / 2.0)
+                .order(egui::Order::Tooltip)
+                .interactable(false)
+                .show(ctx, |ui| {
+                    ui.add(unit_image(&unit).fit_to_exact_size((SQUARE_SIZE, SQUARE_SIZE).into()));
+                });
+        }
+        let mut drop_pos: Option<Pos> = None;
+
         // Simple Button Grid
         egui::Area::new("Board")
             .anchor(egui::Align2::CENTER_CENTER, [0.0, 10.0])
@@ -84,14 +456,15 @@ impl App for ChessEguiApp {
                         ui.end_row();
 
                         let mut row_num = 8;
-                        for (y_pos, row) in self.chess.board_state.squares.into_iter().enumerate() {
+                        for (y_pos, row) in self.chess.board_state.get_squares().iter().enumerate()
+                        {
                             // Left Legend
                             let side = format!("   {}", row_num);
                             ui.label(RichText::new(side).color(Color32::WHITE).size(18.0));
                             row_num -= 1;
 
                             // Board
-                            for (x_pos, col) in row.into_iter().enumerate() {
+                            for (x_pos, col) in row.iter().enumerate() {
                                 let response: Response;
                                 if let Some(unit) = col {
                                     let uri = format!(
@@ -115,7 +488,15 @@ impl App for ChessEguiApp {
                                         ));
                                 }
 
-                                if response.clicked() {
+                                if let (true, Some(_), Some(pointer)) =
+                                    (released, self.dragged, pointer)
+                                {
+                                    if response.rect.contains(pointer) {
+                                        drop_pos = Some((x_pos as i8, y_pos as i8).into());
+                                    }
+                                }
+
+                                if response.clicked() && !self.test_computer_turn() {
                                     if self.chess.unit_pos.is_none() {
                                         self.chess.unit_pos =
                                             Some((x_pos as i8, y_pos as i8).into());
@@ -134,8 +515,33 @@ impl App for ChessEguiApp {
                     });
             });
 
-        self.chess.game_controller();
+        if released {
+            if let (Some(unit), Some(pos)) = (self.dragged.take(), drop_pos) {
+                match self.chess.make_drop(&unit, &pos) {
+                    Ok(_) => report(&self.chess),
+                    Err(error) => println!("{}", error),
+                }
+            }
+        }
+
+        match self.chess.game_controller() {
+            Ok(Some(_)) => report(&self.chess),
+            Ok(None) => (),
+            Err(error) => println!("{}", error),
+        }
 
         egui::CentralPanel::default().show(ctx, |_ui| {});
     }
 }
+
+/// Gives back the image of the `unit` from the svg resources
+fn unit_image(unit: &Unit) -> egui::Image<'static> {
+    let uri = format!("bytes://examples/res/svg/{}.svg", unit.get_id_str());
+    let bytes = fs::read(Path::new(&format!(
+        "examples/res/svg/{}.svg",
+        unit.get_id_str()
+    )))
+    .unwrap();
+
+    egui::Image::from_bytes(uri, bytes)
+}
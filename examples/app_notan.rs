@@ -1,3 +1,6 @@
+// Standard
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 // Chess Crate
 extern crate chess;
 use chess::prelude::*;
@@ -6,6 +9,30 @@ use chess::prelude::*;
 use notan::draw::*;
 use notan::prelude::*;
 
+// FILES
+const PGN_PATH: &str = "game.pgn";
+
+// CLOCK
+const CLOCK_TIME: Duration = Duration::from_secs(5 * 60);
+const CLOCK_INCREMENT: Duration = Duration::from_secs(3);
+
+// COMPUTER
+/// Depth and time limit of the search for each strength
+const STRENGTHS: [(u32, Duration); 5] = [
+    (1, Duration::from_millis(100)),
+    (2, Duration::from_millis(200)),
+    (3, Duration::from_millis(400)),
+    (5, Duration::from_millis(700)),
+    (64, Duration::from_millis(1000)),
+];
+const STRENGTH_KEYS: [KeyCode; 5] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+];
+
 // SIZES
 const SQUARE_SIZE: f32 = 84.0;
 
@@ -21,6 +48,12 @@ const RESTART_HEIGHT: f32 = 26.0;
 const QUIT_WIDTH: f32 = 42.0;
 const QUIT_HEIGHT: f32 = 26.0;
 
+const MOVE_LIST_LENGTH: usize = 30;
+const MOVE_LIST_SPACING: f32 = 22.0;
+
+const PICKER_WIDTH: f32 = 4.0 * SQUARE_SIZE;
+const PICKER_HEIGHT: f32 = SQUARE_SIZE;
+
 // POSITIONS
 const LEFT: f32 = (WIDTH - 8.0 * SQUARE_SIZE) / 2.0;
 const RIGHT: f32 = WIDTH - LEFT;
@@ -37,12 +70,16 @@ const RESTART_TOP: f32 = POPUP_TOP + 105.0 - RESTART_HEIGHT / 2.0;
 const QUIT_LEFT: f32 = WIDTH / 2.0 - QUIT_WIDTH / 2.0;
 const QUIT_TOP: f32 = POPUP_TOP + 145.0 - RESTART_HEIGHT / 2.0;
 
+const PICKER_LEFT: f32 = WIDTH / 2.0 - PICKER_WIDTH / 2.0;
+const PICKER_TOP: f32 = HEIGHT / 2.0 - PICKER_HEIGHT / 2.0;
+
 // COLORS
 const BACKGROUND: Color = Color::new(0.15, 0.15, 0.15, 1.0);
 
 const BOARD_BLACK: Color = Color::new(0.30, 0.30, 0.30, 1.0);
 const BOARD_WHITE: Color = Color::new(0.60, 0.60, 0.60, 1.0);
 const BOARD_SELECT: Color = Color::new(0.75, 0.65, 0.25, 1.0);
+const BOARD_TARGET: Color = Color::new(0.45, 0.55, 0.35, 1.0);
 
 const TEXT: Color = Color::new(0.50, 0.50, 0.50, 1.0);
 
@@ -58,6 +95,10 @@ fn main() -> Result<(), String> {
 #[derive(AppState)]
 pub struct ChessState {
     chess: Game,
+    engine: Engine,
+    computer: Option<Side>,
+    strength: usize,
+    thinking: bool,
     texture_buffer: Vec<Texture>,
     font: Font,
 }
@@ -68,7 +109,7 @@ impl ChessState {
             .set_title("Chess")
             .set_size(WIDTH as u32, HEIGHT as u32)
             .set_high_dpi(true)
-            .set_lazy_loop(true);
+            .set_lazy_loop(false);
 
         notan::init_with(init)
             .add_config(window_cfg)
@@ -119,17 +160,116 @@ fn init(gfx: &mut Graphics) -> ChessState {
         .create_font(include_bytes!("res/font/coolvetica_condensed_rg.otf"))
         .unwrap();
 
-    let chess = Game::new();
+    let chess = new_game(Game::new());
 
     ChessState {
         chess,
+        engine: Engine::new(),
+        computer: None,
+        strength: 3,
+        thinking: false,
         texture_buffer,
         font,
     }
 }
 
 fn update(app: &mut App, state: &mut ChessState) {
+    // Computer
+    play_computer(state);
+    if state.computer.is_some() && state.chess.get_draw_offer().is_some() {
+        state.chess.decline_draw();
+    }
+
+    if app.keyboard.was_pressed(KeyCode::C) {
+        // Off -> Computer plays Black -> Computer plays White -> Off
+        state.computer = match state.computer {
+            None => Some(Side::Black),
+            Some(Side::Black) => Some(Side::White),
+            Some(Side::White) => None,
+        };
+    }
+
+    for (idx, key) in STRENGTH_KEYS.iter().enumerate() {
+        if app.keyboard.was_pressed(*key) {
+            state.strength = idx + 1;
+        }
+    }
+
+    // Clocks
+    if state.chess.test_flag_fall() {
+        report(&state.chess);
+    }
+
+    // History
+    if app.keyboard.was_pressed(KeyCode::Z) && state.chess.undo() {
+        // Takes back the move of the computer too
+        if test_computer_turn(state) {
+            state.chess.undo();
+        }
+        report(&state.chess);
+    }
+
+    if app.keyboard.was_pressed(KeyCode::Y) && state.chess.redo() {
+        report(&state.chess);
+    }
+
+    // Draw Claim + Draw Offer + Resignation
+    if app.keyboard.was_pressed(KeyCode::D) && state.chess.claim_draw() {
+        report(&state.chess);
+    }
+
+    if app.keyboard.was_pressed(KeyCode::O) {
+        let side = state.chess.get_current_turn();
+        if state.chess.offer_draw(side) {
+            println!("{} offers a draw!", side);
+        }
+    }
+
+    if app.keyboard.was_pressed(KeyCode::A) && state.chess.accept_draw() {
+        report(&state.chess);
+    }
+
+    if app.keyboard.was_pressed(KeyCode::X) {
+        state.chess.decline_draw();
+    }
+
+    if app.keyboard.was_pressed(KeyCode::R) {
+        let side = state.chess.get_current_turn();
+        if state.chess.resign(side) {
+            report(&state.chess);
+        }
+    }
+
+    // Chess960
+    if app.keyboard.was_pressed(KeyCode::F) {
+        restart(state, new_chess960());
+    }
+
+    // Archive
+    if app.keyboard.was_pressed(KeyCode::S) {
+        save_pgn(&state.chess);
+    }
+
+    if app.keyboard.was_pressed(KeyCode::L) {
+        if let Some(chess) = load_pgn() {
+            restart(state, chess);
+        }
+    }
+
     match state.chess.get_game_state() {
+        GameState::Playing if state.chess.is_promotion_pending() => {
+            // Promotion Choice
+            if app.mouse.left_was_pressed() {
+                state.chess.promotion = mouse_to_promotion(app.mouse.position());
+                control(state);
+            }
+
+            // Cancel Step
+            if app.mouse.right_was_pressed() {
+                state.chess.clear_selection()
+            }
+        }
+        GameState::Playing if test_computer_turn(state) => (),
         GameState::Playing => {
             // Select
             if app.mouse.left_was_pressed() {
@@ -138,18 +278,18 @@ fn update(app: &mut App, state: &mut ChessState) {
                     _ => state.chess.target_pos = mouse_to_pos(app.mouse.position()),
                 }
 
-                state.chess.game_controller();
+                control(state);
             }
 
             // Deselect
             if app.mouse.right_was_pressed() {
-                state.chess.unit_pos = None
+                state.chess.clear_selection()
             }
         }
         GameState::Ending(_) => {
             if app.mouse.left_was_pressed() {
                 if check_mouse_at_restart(app.mouse.position()) {
-                    app.exit()
+                    restart(state, Game::new());
                 }
 
                 if check_mouse_at_quit(app.mouse.position()) {
@@ -169,8 +309,20 @@ fn draw(gfx: &mut Graphics, state: &mut ChessState) {
     let mut y = TOP;
     let mut tile_idx = 0;
 
-    for (board_y, row) in state.chess.board_state.squares.into_iter().enumerate() {
-        for (board_x, col) in row.into_iter().enumerate() {
+    // Legal Targets of the Selected Unit
+    let targets: Vec<Pos> = match state.chess.unit_pos {
+        Some(pos) => state
+            .chess
+            .board_state
+            .legal_moves_from(&pos)
+            .into_iter()
+            .map(|chess_move| chess_move.target_pos)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    for (board_y, row) in state.chess.board_state.get_squares().iter().enumerate() {
+        for (board_x, col) in row.iter().enumerate() {
             // Board Tile
             if tile_idx % 2 == 0 {
                 draw.rect((x, y), (SQUARE_SIZE, SQUARE_SIZE))
@@ -190,6 +342,13 @@ fn draw(gfx: &mut Graphics, state: &mut ChessState) {
                         .fill();
                 }
             }
+
+            // Legal Target Tile
+            if targets.contains(&(board_x as i8, board_y as i8).into()) {
+                draw.rect((x, y), (SQUARE_SIZE, SQUARE_SIZE))
+                    .fill_color(BOARD_TARGET)
+                    .fill();
+            }
             tile_idx += 1;
 
             // Unit
@@ -206,13 +365,70 @@ fn draw(gfx: &mut Graphics, state: &mut ChessState) {
         y += SQUARE_SIZE;
     }
 
+    // Move List
+    let lines: Vec<String> = state
+        .chess
+        .get_san_moves()
+        .chunks(2)
+        .enumerate()
+        .map(|(idx, moves)| format!("{}. {}", idx + 1, moves.join(" ")))
+        .collect();
+
+    let skip = lines.len().saturating_sub(MOVE_LIST_LENGTH);
+    for (idx, line) in lines.iter().skip(skip).enumerate() {
+        draw.text(&state.font, line)
+            .position(RIGHT + 20.0, TOP + idx as f32 * MOVE_LIST_SPACING)
+            .size(20.0)
+            .color(TEXT);
+    }
+
+    // Clocks
+    if let Some(clock) = state.chess.get_clock() {
+        for (side, y) in [(Side::Black, TOP + 20.0), (Side::White, BOTTOM - 20.0)] {
+            let color = if clock.is_running() && clock.get_turn() == side {
+                BOARD_WHITE
+            } else {
+                TEXT
+            };
+
+            draw.text(&state.font, &clock.get_remaining_str(side))
+                .position(LEFT - 20.0, y)
+                .size(35.0)
+                .color(color)
+                .h_align_right()
+                .v_align_middle();
+        }
+    }
+
+    // Computer
+    let text = match state.computer {
+        Some(computer) => format!(
+            "Computer: {}\nStrength: {}\n\nC - Switch\n1-5 - Strength",
+            computer, state.strength
+        ),
+        None => String::from("C - Computer"),
+    };
+
+    draw.text(&state.font, &text)
+        .position(LEFT - 20.0, HEIGHT / 2.0)
+        .size(25.0)
+        .color(TEXT)
+        .h_align_right()
+        .v_align_middle();
+
     // Text: Current Turn
     let turn_color = match state.chess.get_current_turn() {
         Side::Black => BOARD_BLACK,
         Side::White => BOARD_WHITE,
     };
 
-    draw.text(&state.font, state.chess.get_current_turn().into())
+    let headline = if state.thinking {
+        "Thinking..."
+    } else {
+        state.chess.get_current_turn().into()
+    };
+
+    draw.text(&state.font, headline)
         .position(WIDTH / 2.0, TOP - 50.0)
         .size(60.0)
         .color(turn_color)
@@ -227,12 +443,50 @@ fn draw(gfx: &mut Graphics, state: &mut ChessState) {
         .h_align_center()
         .v_align_middle();
 
-    draw.text(&state.font, "LMB - Select\nRMB - Cancel Selection")
-        .position(WIDTH / 2.0, BOTTOM + 67.0)
-        .size(25.0)
-        .color(TEXT)
-        .h_align_center()
-        .v_align_middle();
+    draw.text(
+        &state.font,
+        "LMB - Select, RMB - Cancel, Z/Y - Undo/Redo, S/L - Save/Load PGN\nR - Resign, O - Offer Draw, A/X - Accept/Decline Draw, D - Claim Draw",
+    )
+    .position(WIDTH / 2.0, BOTTOM + 67.0)
+    .size(25.0)
+    .color(TEXT)
+    .h_align_center()
+    .v_align_middle();
+
+    if state.chess.is_promotion_pending() {
+        // Promotion PopUp
+        draw.rect((PICKER_LEFT, PICKER_TOP), (PICKER_WIDTH, PICKER_HEIGHT))
+            .fill_color(BOARD_SELECT)
+            .fill();
+
+        let pawn = Unit::Pawn(state.chess.get_current_turn(), true);
+        for (idx, promotion) in Unit::PROMOTIONS.iter().enumerate() {
+            let unit = pawn.change_type(promotion);
+            draw.image(&state.texture_buffer[unit.get_id() as usize])
+                .position(PICKER_LEFT + idx as f32 * SQUARE_SIZE, PICKER_TOP);
+        }
+    }
+
+    if let Some(side) = state.chess.get_draw_offer() {
+        // Draw Offer PopUp
+        draw.rect((POPUP_LEFT, POPUP_TOP), (POPUP_WIDTH, POPUP_HEIGHT))
+            .fill_color(BACKGROUND)
+            .fill();
+
+        draw.text(&state.font, &format!("{} offers a draw", side))
+            .position(WIDTH / 2.0, POPUP_TOP + 35.0)
+            .size(35.0)
+            .color(TEXT)
+            .h_align_center()
+            .v_align_middle();
+
+        draw.text(&state.font, "A - Accept\nX - Decline")
+            .position(WIDTH / 2.0, POPUP_TOP + 125.0)
+            .size(35.0)
+            .color(TEXT)
+            .h_align_center()
+            .v_align_middle();
+    }
 
     if let GameState::Ending(side) = state.chess.get_game_state() {
         // Re-Play PopUp
@@ -251,6 +505,15 @@ fn draw(gfx: &mut Graphics, state: &mut ChessState) {
             .h_align_center()
             .v_align_middle();
 
+        if let Some(end_reason) = state.chess.get_end_reason() {
+            draw.text(&state.font, &end_reason.to_string())
+                .position(WIDTH / 2.0, POPUP_TOP + 68.0)
+                .size(25.0)
+                .color(TEXT)
+                .h_align_center()
+                .v_align_middle();
+        }
+
         draw.text(&state.font, "New Game")
             .position(WIDTH / 2.0, POPUP_TOP + 105.0)
             .size(35.0)
@@ -269,6 +532,103 @@ fn draw(gfx: &mut Graphics, state: &mut ChessState) {
     gfx.render(&draw);
 }
 
+/// Starts a new game, the [`Engine`] forgets the former one
+fn restart(state: &mut ChessState, chess: Game) {
+    state.chess = new_game(chess);
+    state.engine.clear();
+    state.thinking = false;
+}
+
+/// Returns true when the computer takes the next move
+fn test_computer_turn(state: &ChessState) -> bool {
+    state.computer == Some(state.chess.get_current_turn())
+        && state.chess.get_game_state() == GameState::Playing
+}
+
+/// Searches and plays the move of the computer
+///
+/// The search starts on the update after the computer's turn comes, so the last move shows up first
+fn play_computer(state: &mut ChessState) {
+    if !test_computer_turn(state) {
+        state.thinking = false;
+        return;
+    }
+
+    if !state.thinking {
+        state.thinking = true;
+        return;
+    }
+
+    let (depth, time) = STRENGTHS[state.strength - 1];
+    let limit = Limit {
+        depth,
+        time: Some(time),
+        nodes: None,
+    };
+
+    if let Some(result) = state.engine.search(&state.chess, limit) {
+        println!("Computer: {} ({})", result.best_move, result.score);
+        if state.chess.play_move(&result.best_move) {
+            report(&state.chess);
+        }
+    }
+    state.thinking = false;
+}
+
+/// Runs the `game_controller` after the selection changed and reports its result
+fn control(state: &mut ChessState) {
+    match state.chess.game_controller() {
+        Ok(Some(_)) => report(&state.chess),
+        Ok(None) => (),
+        Err(error) => println!("{}", error),
+    }
+}
+
+/// Prints the outcome when the game ended, otherwise the side which moves next
+fn report(chess: &Game) {
+    match chess.get_outcome() {
+        Some(outcome) => println!("{}", outcome),
+        None => println!("{} moves next!", chess.get_current_turn()),
+    }
+}
+
+/// Attaches a blitz [`Clock`] to the `chess` game
+fn new_game(mut chess: Game) -> Game {
+    chess.set_clock(Clock::new(TimeControl::fischer(
+        CLOCK_TIME,
+        CLOCK_INCREMENT,
+    )));
+    chess
+}
+
+/// Creates a new game from a random Chess960 start position
+fn new_chess960() -> Game {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.subsec_nanos());
+
+    Game::new_chess960((seed % CHESS960_COUNT as u32) as u16).unwrap_or_default()
+}
+
+/// Saves the game into the PGN archive
+fn save_pgn(chess: &Game) {
+    match std::fs::write(PGN_PATH, chess.to_pgn()) {
+        Ok(_) => println!("Game saved: {}", PGN_PATH),
+        Err(error) => println!("Can't save the game: {}", error),
+    }
+}
+
+/// Loads the first game of the PGN archive
+fn load_pgn() -> Option<Game> {
+    let pgn = std::fs::read_to_string(PGN_PATH)
+        .map_err(|error| println!("Can't load the game: {}", error))
+        .ok()?;
+
+    Game::from_pgn(&pgn)
+        .map_err(|error| println!("Can't load the game: {}", error))
+        .ok()
+}
+
 /// Calculate Mouse Position to Board Pos
 fn mouse_to_pos((x, y): (f32, f32)) -> Option<Pos> {
     if x > LEFT && x < RIGHT && y > TOP && y < BOTTOM {
@@ -284,6 +644,21 @@ fn mouse_to_pos((x, y): (f32, f32)) -> Option<Pos> {
     None
 }
 
+/// Calculate Mouse Position to Promotion Unit Type
+fn mouse_to_promotion((x, y): (f32, f32)) -> Option<Unit> {
+    if x > PICKER_LEFT
+        && x < PICKER_LEFT + PICKER_WIDTH
+        && y > PICKER_TOP
+        && y < PICKER_TOP + PICKER_HEIGHT
+    {
+        let idx = (x - PICKER_LEFT) / SQUARE_SIZE;
+
+        return Some(Unit::PROMOTIONS[idx as usize]);
+    }
+
+    None
+}
+
 /// Checks if Mouse Position is at the Restart Button
 fn check_mouse_at_restart((x, y): (f32, f32)) -> bool {
     if x > RESTART_LEFT
@@ -0,0 +1,56 @@
+// Standard Crate
+use std::env;
+use std::process::ExitCode;
+use std::time::Instant;
+
+// Chess Crate
+use chess::prelude::*;
+
+//==================================================
+//=== Perft
+//==================================================
+
+/// Counts the move paths of a position, E.g. `perft 4 "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"`
+///
+/// Prints the count of each [`Move`] first, the FEN defaults to the start position
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+
+    let depth = match args.next().map(|depth| depth.parse::<u32>()) {
+        Some(Ok(depth)) => depth,
+        _ => {
+            eprintln!("Usage: perft <depth> [fen]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // FEN fields may come as separate arguments
+    let fen = args.collect::<Vec<String>>().join(" ");
+    let board = if fen.is_empty() {
+        Board::new()
+    } else {
+        match Board::from_fen(&fen) {
+            Ok(board) => board,
+            Err(error) => {
+                eprintln!("{}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+
+    let start = Instant::now();
+    let divide = board.perft_divide(depth);
+
+    for (chess_move, count) in &divide {
+        println!("{}: {}", chess_move.to_lan(), count);
+    }
+
+    let nodes = match depth {
+        0 => 1,
+        _ => divide.iter().map(|(_, count)| count).sum(),
+    };
+    println!("\nNodes: {}", nodes);
+    println!("Time: {:.3}s", start.elapsed().as_secs_f64());
+
+    ExitCode::SUCCESS
+}
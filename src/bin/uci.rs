@@ -0,0 +1,27 @@
+// Standard Crate
+use std::io::{self, BufRead};
+
+// Chess Crate
+use chess::prelude::*;
+
+//==================================================
+//=== UCI
+//==================================================
+
+/// Runs the [`Engine`] behind the Universal Chess Interface, reads the commands from stdin
+///
+/// E.g. `printf "uci\nposition startpos moves e2e4\ngo depth 6\n" | uci`
+fn main() {
+    let mut uci = Uci::new(io::stdout());
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+
+        if !uci.execute(&line) {
+            return;
+        }
+    }
+
+    // The end of the input lets the last search finish
+    uci.wait();
+}
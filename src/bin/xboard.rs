@@ -0,0 +1,24 @@
+// Standard Crate
+use std::io::{self, BufRead};
+
+// Chess Crate
+use chess::prelude::*;
+
+//==================================================
+//=== XBoard
+//==================================================
+
+/// Runs the [`Engine`] behind the Chess Engine Communication Protocol, reads the commands from stdin
+///
+/// E.g. `printf "xboard\nprotover 2\nnew\nsd 5\nusermove e2e4\n" | xboard`
+fn main() {
+    let mut xboard = Xboard::new(io::stdout());
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+
+        if !xboard.execute(&line) {
+            break;
+        }
+    }
+}
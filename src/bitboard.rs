@@ -0,0 +1,429 @@
+// Standard Crate
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Not};
+
+// Chess Crate
+use super::pos::Pos;
+use super::unit::{Side, Unit};
+
+const BOARD_SIZE: i8 = 8;
+
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (-2, 1),
+    (-1, 2),
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+];
+
+const KING_OFFSETS: [(i8, i8); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+/// Directions of the rays, the first 4 are diagonal, the last 4 are horizontal or vertical
+const RAY_OFFSETS: [(i8, i8); 8] = [
+    (1, 1),
+    (1, -1),
+    (-1, -1),
+    (-1, 1),
+    (0, 1),
+    (0, -1),
+    (1, 0),
+    (-1, 0),
+];
+
+/// Squares attacked by a Knight, indexed by the square of the Knight
+const KNIGHT_ATTACKS: [u64; 64] = leaper_table(&KNIGHT_OFFSETS);
+
+/// Squares attacked by a King, indexed by the square of the King
+const KING_ATTACKS: [u64; 64] = leaper_table(&KING_OFFSETS);
+
+/// Squares attacked by a Pawn, indexed by the [`Side`] and the square of the Pawn
+///
+/// Black Pawns step down the board, White Pawns step up
+const PAWN_ATTACKS: [[u64; 64]; 2] = [
+    leaper_table(&[(-1, 1), (1, 1)]),
+    leaper_table(&[(-1, -1), (1, -1)]),
+];
+
+/// Squares of the ray until the edge of the board, indexed by the direction and the starting square
+const RAYS: [[u64; 64]; 8] = ray_table();
+
+//==================================================
+//=== Bitboard
+//==================================================
+
+/// Set of squares, the bit `y * 8 + x` stands for the [`Pos`] (x, y)
+///
+/// Iterates over the [`Pos`]itions in the set, from A8 to H1
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Self = Self(0);
+
+    /// Creates a new [`Bitboard`] holding only the given `pos`
+    pub fn from_pos(pos: &Pos) -> Self {
+        Self(1 << square(pos))
+    }
+
+    /// Checks if the set has no squares
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Gives back the number of squares in the set
+    pub fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+}
+
+impl Iterator for Bitboard {
+    type Item = Pos;
+
+    fn next(&mut self) -> Option<Pos> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let square = self.0.trailing_zeros() as i8;
+        self.0 &= self.0 - 1;
+
+        Some(Pos::new(square % BOARD_SIZE, square / BOARD_SIZE))
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self {
+        Self(self.0 ^ other.0)
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, other: Self) {
+        self.0 &= other.0;
+    }
+}
+
+//==================================================
+//=== Bitboards
+//==================================================
+
+/// [`Bitboard`]s of the [`Unit`]s on the board
+///
+/// * `units` squares of each [`Unit`] type and [`Side`], indexed by [`Unit::get_id`]
+/// * `sides` squares of all [`Unit`]s of each [`Side`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Bitboards {
+    units: [Bitboard; Unit::UNIT_COUNT],
+    sides: [Bitboard; 2],
+}
+
+impl Bitboards {
+    /// Adds the `unit` at `pos`, the square has to be empty
+    pub fn set(&mut self, unit: &Unit, pos: &Pos) {
+        let bitboard = Bitboard::from_pos(pos);
+        self.units[unit.get_id() as usize] |= bitboard;
+        self.sides[side_idx(&unit.get_side())] |= bitboard;
+    }
+
+    /// Removes the `unit` from `pos`
+    pub fn remove(&mut self, unit: &Unit, pos: &Pos) {
+        let bitboard = !Bitboard::from_pos(pos);
+        self.units[unit.get_id() as usize] &= bitboard;
+        self.sides[side_idx(&unit.get_side())] &= bitboard;
+    }
+
+    /// Gives back the squares of the [`Unit`]s with the type of `unit` and the given `side`
+    pub fn get_units(&self, unit: &Unit, side: &Side) -> Bitboard {
+        self.units[unit.get_id() as usize % 6 + 6 * side_idx(side)]
+    }
+
+    /// Gives back the squares of every [`Unit`] of `side`
+    pub fn get_side(&self, side: &Side) -> Bitboard {
+        self.sides[side_idx(side)]
+    }
+
+    /// Gives back the squares of every [`Unit`]
+    pub fn get_occupied(&self) -> Bitboard {
+        self.sides[0] | self.sides[1]
+    }
+
+    /// Returns true when any [`Unit`] of `side` attacks the given `pos`
+    pub fn test_attacked(&self, pos: &Pos, side: &Side) -> bool {
+        let square = square(pos);
+
+        // A Pawn of `side` attacks `pos` from where an enemy Pawn at `pos` would attack
+        let leapers = (Bitboard(PAWN_ATTACKS[side_idx(&side.oppose())][square])
+            & self.get_units(&Unit::PAWN, side))
+            | (Bitboard(KNIGHT_ATTACKS[square]) & self.get_units(&Unit::KNIGHT, side))
+            | (Bitboard(KING_ATTACKS[square]) & self.get_units(&Unit::KING, side));
+
+        if !leapers.is_empty() {
+            return true;
+        }
+
+        let occupied = self.get_occupied();
+        let queens = self.get_units(&Unit::QUEEN, side);
+        let sliders = (bishop_attacks(pos, occupied)
+            & (self.get_units(&Unit::BISHOP, side) | queens))
+            | (rook_attacks(pos, occupied) & (self.get_units(&Unit::ROOK, side) | queens));
+
+        !sliders.is_empty()
+    }
+}
+
+//==================================================
+//=== Attacks
+//==================================================
+
+/// Gives back the squares attacked by the `unit` at `pos`, `occupied` squares block the sliding [`Unit`]s
+///
+/// Pawns attack only diagonally, their steps forward are not included
+pub(crate) fn attacks(unit: &Unit, pos: &Pos, occupied: Bitboard) -> Bitboard {
+    match unit {
+        Unit::Pawn(side, _) => Bitboard(PAWN_ATTACKS[side_idx(side)][square(pos)]),
+        Unit::Bishop(_) => bishop_attacks(pos, occupied),
+        Unit::Knight(_) => Bitboard(KNIGHT_ATTACKS[square(pos)]),
+        Unit::Rook(..) => rook_attacks(pos, occupied),
+        Unit::Queen(_) => bishop_attacks(pos, occupied) | rook_attacks(pos, occupied),
+        Unit::King(..) => Bitboard(KING_ATTACKS[square(pos)]),
+    }
+}
+
+/// Gives back the squares attacked diagonally from `pos`
+fn bishop_attacks(pos: &Pos, occupied: Bitboard) -> Bitboard {
+    (0..4).fold(Bitboard::EMPTY, |attacks, direction| {
+        attacks | ray_attacks(direction, pos, occupied)
+    })
+}
+
+/// Gives back the squares attacked horizontally and vertically from `pos`
+fn rook_attacks(pos: &Pos, occupied: Bitboard) -> Bitboard {
+    (4..8).fold(Bitboard::EMPTY, |attacks, direction| {
+        attacks | ray_attacks(direction, pos, occupied)
+    })
+}
+
+/// Gives back the squares of the ray from `pos` until the first `occupied` square, including it
+fn ray_attacks(direction: usize, pos: &Pos, occupied: Bitboard) -> Bitboard {
+    let ray = RAYS[direction][square(pos)];
+    let blockers = ray & occupied.0;
+
+    if blockers == 0 {
+        return Bitboard(ray);
+    }
+
+    // The closest blocker has the lowest bit on rays going forward, the highest otherwise
+    let (dx, dy) = RAY_OFFSETS[direction];
+    let blocker = if dy * BOARD_SIZE + dx > 0 {
+        blockers.trailing_zeros()
+    } else {
+        63 - blockers.leading_zeros()
+    };
+
+    Bitboard(ray ^ RAYS[direction][blocker as usize])
+}
+
+/// Gives back the index of the `pos` in the [`Bitboard`]
+fn square(pos: &Pos) -> usize {
+    (pos.y * BOARD_SIZE + pos.x) as usize
+}
+
+/// Gives back the index of the `side` in the [`Bitboard`] arrays, the same order as [`Unit::get_id`]
+fn side_idx(side: &Side) -> usize {
+    match side {
+        Side::Black => 0,
+        Side::White => 1,
+    }
+}
+
+//==================================================
+//=== Tables
+//==================================================
+
+/// Produces the attacks of a non-sliding [`Unit`] for each square from its `offsets`
+const fn leaper_table(offsets: &[(i8, i8)]) -> [u64; 64] {
+    let mut table = [0; 64];
+
+    let mut square = 0;
+    while square < 64 {
+        let (x, y) = (square as i8 % BOARD_SIZE, square as i8 / BOARD_SIZE);
+
+        let mut idx = 0;
+        while idx < offsets.len() {
+            let (target_x, target_y) = (x + offsets[idx].0, y + offsets[idx].1);
+            if target_x >= 0 && target_x < BOARD_SIZE && target_y >= 0 && target_y < BOARD_SIZE {
+                table[square] |= 1 << (target_y * BOARD_SIZE + target_x);
+            }
+            idx += 1;
+        }
+
+        square += 1;
+    }
+
+    table
+}
+
+/// Produces the rays of each direction in [`RAY_OFFSETS`] for each square
+const fn ray_table() -> [[u64; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+
+    let mut direction = 0;
+    while direction < 8 {
+        let (dx, dy) = RAY_OFFSETS[direction];
+
+        let mut square = 0;
+        while square < 64 {
+            let mut x = square as i8 % BOARD_SIZE + dx;
+            let mut y = square as i8 / BOARD_SIZE + dy;
+
+            while x >= 0 && x < BOARD_SIZE && y >= 0 && y < BOARD_SIZE {
+                table[direction][square] |= 1 << (y * BOARD_SIZE + x);
+                x += dx;
+                y += dy;
+            }
+
+            square += 1;
+        }
+
+        direction += 1;
+    }
+
+    table
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_bitboard {
+    use super::*;
+
+    fn bitboard(positions: &[&str]) -> Bitboard {
+        positions.iter().fold(Bitboard::EMPTY, |bitboard, pos| {
+            bitboard | Bitboard::from_pos(&Pos::from_notation(pos))
+        })
+    }
+
+    #[test]
+    fn test_iterate() {
+        let positions: Vec<Pos> = bitboard(&["H1", "A8", "D4"]).collect();
+        assert_eq!(
+            positions,
+            vec![
+                Pos::from_notation("A8"),
+                Pos::from_notation("D4"),
+                Pos::from_notation("H1")
+            ]
+        );
+        assert_eq!(bitboard(&["H1", "A8", "D4"]).count(), 3);
+        assert!(Bitboard::EMPTY.is_empty());
+    }
+
+    #[test]
+    fn test_leaper_attacks() {
+        let occupied = Bitboard::EMPTY;
+
+        assert_eq!(
+            attacks(&Unit::KNIGHT, &Pos::from_notation("A1"), occupied),
+            bitboard(&["B3", "C2"])
+        );
+        assert_eq!(
+            attacks(&Unit::KING, &Pos::from_notation("H8"), occupied),
+            bitboard(&["G8", "G7", "H7"])
+        );
+        assert_eq!(
+            attacks(
+                &Unit::Pawn(Side::White, false),
+                &Pos::from_notation("E2"),
+                occupied
+            ),
+            bitboard(&["D3", "F3"])
+        );
+        assert_eq!(
+            attacks(
+                &Unit::Pawn(Side::Black, false),
+                &Pos::from_notation("A7"),
+                occupied
+            ),
+            bitboard(&["B6"])
+        );
+    }
+
+    #[test]
+    fn test_sliding_attacks() {
+        let occupied = bitboard(&["D6", "F4", "B2", "D1"]);
+
+        assert_eq!(
+            attacks(&Unit::ROOK, &Pos::from_notation("D4"), occupied),
+            bitboard(&["D5", "D6", "D3", "D2", "D1", "A4", "B4", "C4", "E4", "F4"])
+        );
+        assert_eq!(
+            attacks(&Unit::BISHOP, &Pos::from_notation("D4"), occupied),
+            bitboard(&["C3", "B2", "E3", "F2", "G1", "C5", "B6", "A7", "E5", "F6", "G7", "H8"])
+        );
+        assert_eq!(
+            attacks(&Unit::QUEEN, &Pos::from_notation("D4"), occupied),
+            attacks(&Unit::ROOK, &Pos::from_notation("D4"), occupied)
+                | attacks(&Unit::BISHOP, &Pos::from_notation("D4"), occupied)
+        );
+    }
+
+    #[test]
+    fn test_attacked() {
+        let mut bitboards = Bitboards::default();
+        bitboards.set(&Unit::Rook(Side::White, false), &Pos::from_notation("A1"));
+        bitboards.set(&Unit::Pawn(Side::Black, false), &Pos::from_notation("A4"));
+
+        assert!(bitboards.test_attacked(&Pos::from_notation("A3"), &Side::White));
+        assert!(bitboards.test_attacked(&Pos::from_notation("B3"), &Side::Black));
+        assert!(!bitboards.test_attacked(&Pos::from_notation("A5"), &Side::White));
+
+        bitboards.remove(&Unit::Pawn(Side::Black, false), &Pos::from_notation("A4"));
+        assert!(bitboards.test_attacked(&Pos::from_notation("A5"), &Side::White));
+        assert_eq!(bitboards.get_occupied(), bitboard(&["A1"]));
+    }
+}
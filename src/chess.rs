@@ -1,68 +1,47 @@
-use std::ops::Add;
-
 // Chess Crate
+use super::bitboard::{self, Bitboard, Bitboards};
+use super::crazyhouse::Pocket;
+use super::moves::{Move, MoveError, MoveKind};
 use super::pos::Pos;
 use super::unit::*;
+use super::zobrist;
 
 const BOARD_SIZE: usize = 8;
 
-const ALL_DIRECTION_OFFSETS: [Pos; 8] = [
-    Pos::new(0, 1),
-    Pos::new(1, 1),
-    Pos::new(1, 0),
-    Pos::new(1, -1),
-    Pos::new(0, -1),
-    Pos::new(-1, -1),
-    Pos::new(-1, 0),
-    Pos::new(-1, 1),
-];
-
-const DIAGONAL_OFFSETS: [Pos; 4] = [
-    Pos::new(1, 1),
-    Pos::new(1, -1),
-    Pos::new(-1, -1),
-    Pos::new(-1, 1),
-];
-
-const CROSS_OFFSETS: [Pos; 4] = [
-    Pos::new(0, 1),
-    Pos::new(0, -1),
-    Pos::new(1, 0),
-    Pos::new(-1, 0),
-];
-
-const UPWARD_OFFSETS: [Pos; 3] = [
-    Pos::new(0, 1),
-    Pos::new(1, 1),
-    Pos::new(-1, 1), //
-];
-
-const DOWNWARD_OFFSETS: [Pos; 3] = [
-    Pos::new(0, -1),
-    Pos::new(1, -1),
-    Pos::new(-1, -1), //
-];
-
-const KNIGHT_OFFSETS: [Pos; 8] = [
-    Pos::new(-2, 1),
-    Pos::new(-1, 2),
-    Pos::new(1, 2),
-    Pos::new(2, 1),
-    Pos::new(2, -1),
-    Pos::new(1, -2),
-    Pos::new(-1, -2),
-    Pos::new(-2, -1),
-];
-
 //==================================================
 //=== Board
 //==================================================
 
-#[derive(Clone)]
+/// * `squares` units on the board, indexed by `[y][x]` of [`Pos`], see [get_squares](#method.get_squares)
+/// * `bitboards` squares of the units by type and side, kept in sync with `squares`
+/// * `hash` Zobrist hash of the position, updated by each change, see [get_hash](#method.get_hash)
+/// * `current_turn` which side takes the next step
+/// * `en_passant_pos` position skipped by the last Pawn 2 Step
+/// * `halfmove_clock` number of steps since the last capture or Pawn step
+/// * `fullmove_number` number of full turns, starts at 1 and increases after each step of Black
+/// * `chess960` the King castles onto its own Rook, see [set_chess960](#method.set_chess960)
+/// * `crazyhouse` captured units can be dropped back onto the board, see [set_crazyhouse](#method.set_crazyhouse)
+/// * `pockets` captured units of Black and White, indexed by [`Side`]
+/// * `promoted` squares of the units promoted from a Pawn, they go into the [`Pocket`] as a Pawn
+/// * `three_check` the checks given by each side are counted, see [set_three_check](#method.set_three_check)
+/// * `checks` number of checks given by Black and White, indexed by [`Side`]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Board {
-    pub squares: [[Option<Unit>; BOARD_SIZE]; BOARD_SIZE],
+    squares: [[Option<Unit>; BOARD_SIZE]; BOARD_SIZE],
+    bitboards: Bitboards,
+    hash: u64,
     black_king_pos: Option<Pos>,
     white_king_pos: Option<Pos>,
+    current_turn: Side,
+    en_passant_pos: Option<Pos>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    chess960: bool,
+    crazyhouse: bool,
+    pockets: [Pocket; 2],
+    promoted: Bitboard,
+    three_check: bool,
+    checks: [u8; 2],
 }
 
 impl Board {
@@ -71,10 +50,31 @@ impl Board {
         Self::default()
     }
 
+    /// Creates a new [`Board`] without any [`Unit`]s on it
+    pub fn empty() -> Self {
+        Self {
+            squares: [[None; BOARD_SIZE]; BOARD_SIZE],
+            bitboards: Bitboards::default(),
+            hash: 0,
+            black_king_pos: None,
+            white_king_pos: None,
+            current_turn: Side::White,
+            en_passant_pos: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            chess960: false,
+            crazyhouse: false,
+            pockets: [Pocket::new(); 2],
+            promoted: Bitboard::EMPTY,
+            three_check: false,
+            checks: [0; 2],
+        }
+    }
+
     /// Returns the [`Pos`] of the King for the given `side`
     ///
     /// If there is no King for this `side`, then returns `None`
-    fn get_king_pos(&self, side: &Side) -> Option<Pos> {
+    pub(crate) fn get_king_pos(&self, side: &Side) -> Option<Pos> {
         match side {
             Side::Black => self.black_king_pos,
             Side::White => self.white_king_pos,
@@ -89,910 +89,1100 @@ impl Board {
         }
     }
 
-    /// Gives back the [`Unit`] on the given position
-    pub fn get_unit(&self, pos: &Pos) -> Option<Unit> {
-        if !pos.is_onboard() {
-            panic!("Cant get unit on Pos: {} - Pos Not On Board", pos);
-        }
+    /// Gives back the units on the board, indexed by `[y][x]` of [`Pos`]
+    pub fn get_squares(&self) -> &[[Option<Unit>; BOARD_SIZE]; BOARD_SIZE] {
+        &self.squares
+    }
 
-        self.squares[pos.y as usize][pos.x as usize]
+    /// Gives back which side takes the next step
+    pub fn get_current_turn(&self) -> Side {
+        self.current_turn
     }
 
-    /// Sets the [`Unit`] to the target position
-    pub fn set_unit(&mut self, unit: Unit, pos: Pos) {
-        if !pos.is_onboard() {
-            panic!("Cant set unit on Pos: {} - Pos Not On Board", pos);
+    /// Sets which side takes the next step
+    pub fn set_current_turn(&mut self, side: Side) {
+        let en_passant_key = self.get_en_passant_key();
+        self.put_turn(side);
+        self.hash ^= en_passant_key ^ self.get_en_passant_key();
+    }
+
+    /// Replaces the side in the current turn and its key in the `hash`
+    fn put_turn(&mut self, side: Side) {
+        if self.current_turn != side {
+            self.hash ^= zobrist::SIDE_KEY;
         }
 
-        self.squares[pos.y as usize][pos.x as usize] = Some(unit);
+        self.current_turn = side;
+    }
 
-        if let Unit::King(side, _) = unit {
-            self.set_king_pos(&side, pos);
-        }
+    /// Gives back the `bitboards` of the units
+    pub(crate) fn get_bitboards(&self) -> &Bitboards {
+        &self.bitboards
     }
 
-    /// Removes a [`Unit`] from the [`Board`]
-    pub fn remove_unit(&mut self, pos: &Pos) {
-        if !pos.is_onboard() {
-            panic!("Cant remove unit on Pos: {} - Pos Not On Board", pos);
-        }
+    /// Gives back the 64-bit Zobrist hash of the position
+    ///
+    /// Covers the [`Unit`]s, the side in the current turn, the castling rights and the En Passant column,
+    /// the clocks are not part of it
+    ///
+    /// On a Crazyhouse [`Board`] the pockets and the promoted [`Unit`]s are covered too,
+    /// on a Three-check [`Board`] the number of checks
+    ///
+    /// The same position gives back the same hash, whichever moves lead to it, see [compute_hash](#method.compute_hash)
+    pub fn get_hash(&self) -> u64 {
+        self.hash
+    }
 
-        self.squares[pos.y as usize][pos.x as usize] = None;
+    /// Gives back the number of steps since the last capture or Pawn step
+    pub fn get_halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
     }
 
-    /// Promotes [`Unit`] to `Queen`, when...
-    /// * The type of the [`Unit`] is `Pawn`
-    /// * [`Unit`] can be found in the first or the last row
-    fn promote(&mut self, pos: Pos) {
-        if !pos.is_onboard() {
-            panic!("Cant set unit on Pos: {} - Pos Not On Board", pos);
-        }
+    /// Sets the number of steps since the last capture or Pawn step
+    pub fn set_halfmove_clock(&mut self, halfmove_clock: u32) {
+        self.halfmove_clock = halfmove_clock;
+    }
 
-        if pos.y != 0 && pos.y != 7 {
-            return;
-        }
+    /// Gives back the number of the current full turn
+    pub fn get_fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
 
-        if let Some(unit) = self.squares[pos.y as usize][pos.x as usize] {
-            if eq_unit_type(&unit, &Unit::PAWN) {
-                self.squares[pos.y as usize][pos.x as usize] = Some(unit.change_type(&Unit::QUEEN));
-            }
-        }
+    /// Sets the number of the current full turn
+    pub fn set_fullmove_number(&mut self, fullmove_number: u32) {
+        self.fullmove_number = fullmove_number;
     }
 
-    /// Mutates [`Board`] when called with a viable step
-    pub fn test_step(&mut self, unit_pos: &Pos, target_pos: &Pos) -> bool {
-        // Any Valid Step Available -> Same Side
+    /// Returns true when the [`Board`] follows the Chess960 castling rules
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
+    }
 
-        // Check Step
-        let selected_unit = self.get_unit(unit_pos).unwrap();
-        let step = self.step_unit(&selected_unit, unit_pos, target_pos);
+    /// Sets the Chess960 castling rules
+    ///
+    /// The King castles by stepping onto its own unmoved Rook, E.g. B1 -> A1,
+    /// then the King and the Rook end on the same squares as in a standard Castle
+    pub fn set_chess960(&mut self, chess960: bool) {
+        let castling_key = self.get_castling_key();
+        self.chess960 = chess960;
+        self.hash ^= castling_key ^ self.get_castling_key();
+    }
 
-        // Evaluate
-        let step = step.evaluate(&self);
-        if !step.is_valid() {
-            return false;
-        }
+    /// Returns true when the [`Board`] follows the Crazyhouse rules
+    pub fn is_crazyhouse(&self) -> bool {
+        self.crazyhouse
+    }
 
-        // Execute Actions
-        let step = step.execute_actions(self);
+    /// Sets the Crazyhouse rules
+    ///
+    /// Captured [`Unit`]s go into the [`Pocket`] of the capturing side, which can drop them onto an empty square
+    /// instead of a step, Pawns can't be dropped onto the first or the last row
+    ///
+    /// A promoted [`Unit`] goes into the [`Pocket`] as a Pawn, see [is_promoted](#method.is_promoted)
+    pub fn set_crazyhouse(&mut self, crazyhouse: bool) {
+        let crazyhouse_key = self.get_crazyhouse_key();
+        self.crazyhouse = crazyhouse;
+        self.hash ^= crazyhouse_key ^ self.get_crazyhouse_key();
+    }
 
-        // Checked Status
-        if self.test_checked_status(&selected_unit.get_side()) {
-            // Reset Board State
-            step.reconstruct(self);
-            println!("Can't move into check! / Can't move when checeked!");
-            return false;
-        }
+    /// Gives back the captured [`Unit`]s `side` can drop onto the [`Board`]
+    pub fn get_pocket(&self, side: &Side) -> &Pocket {
+        &self.pockets[*side as usize]
+    }
 
-        // Any Valid Step Available -> Other Side
+    /// Sets the captured [`Unit`]s `side` can drop onto the [`Board`]
+    pub fn set_pocket(&mut self, side: &Side, pocket: Pocket) {
+        if self.crazyhouse {
+            self.hash ^= zobrist::pocket_key(side, self.get_pocket(side))
+                ^ zobrist::pocket_key(side, &pocket);
+        }
 
-        true
+        self.pockets[*side as usize] = pocket;
     }
 
-    fn step_unit(&self, unit: &Unit, unit_pos: &Pos, target_pos: &Pos) -> Step<ConditionState> {
-        match unit {
-            Unit::Pawn(side, moved) => self.step_pawn(&unit_pos, &target_pos, &side, &moved),
-            Unit::Bishop(side) => self.step_bishop(&unit_pos, &target_pos, &side),
-            Unit::Knight(side) => self.step_knight(&unit_pos, &target_pos, &side),
-            Unit::Rook(side, _) => self.step_rook(&unit_pos, &target_pos, &side),
-            Unit::Queen(side) => self.step_queen(&unit_pos, &target_pos, &side),
-            Unit::King(side, moved) => self.step_king(&unit_pos, &target_pos, &side, &moved),
-        }
+    /// Returns true when the [`Unit`] at `pos` was promoted from a Pawn on a Crazyhouse [`Board`]
+    pub fn is_promoted(&self, pos: &Pos) -> bool {
+        pos.is_onboard() && !(self.promoted & Bitboard::from_pos(pos)).is_empty()
     }
 
-    fn step_pawn(
-        &self,
-        unit_pos: &Pos,
-        target_pos: &Pos,
-        side: &Side,
-        moved: &Moved,
-    ) -> Step<ConditionState> {
-        let mut step = Step::new(false);
+    /// Marks the [`Unit`] at `pos` as promoted from a Pawn, does nothing when `pos` is not on the board
+    pub fn set_promoted(&mut self, pos: &Pos, promoted: bool) {
+        if !pos.is_onboard() {
+            return;
+        }
 
-        let mut calc_pos = *target_pos - *unit_pos;
-        let offset_pos;
+        if self.crazyhouse && self.is_promoted(pos) != promoted {
+            self.hash ^= zobrist::promoted_key(pos);
+        }
 
-        // Validate Step Direction
-        match side {
-            Side::Black if calc_pos.y > 0 => offset_pos = target_pos.bounded_up(),
-            Side::White if calc_pos.y < 0 => offset_pos = target_pos.bounded_down(),
-            _ => return step,
-        };
+        if promoted {
+            self.promoted |= Bitboard::from_pos(pos);
+        } else {
+            self.promoted &= !Bitboard::from_pos(pos);
+        }
+    }
 
-        // 1 Vertical Step E.g. D5 -> D4
-        // 2 Vertical Step E.g. D7 -> D6 OR D7 -> D5
-        calc_pos = calc_pos.abs();
-        if calc_pos.x == 0 {
-            // 1 Step
-            if calc_pos.y == 1 {
-                step.set(true);
+    /// Gives back the squares of the promoted [`Unit`]s
+    pub(crate) fn get_promoted(&self) -> Bitboard {
+        self.promoted
+    }
 
-                step.add_cond_pos_is_none(*target_pos);
+    /// Returns true when the [`Board`] counts the checks of Three-check
+    pub fn is_three_check(&self) -> bool {
+        self.three_check
+    }
 
-                step.add_action_move(*unit_pos, *target_pos);
-            }
-            // 2 Step
-            else if calc_pos.y == 2 && !moved {
-                step.set(true);
+    /// Sets the Three-check counting, each [`Move`] which puts the enemy King in check counts for its side
+    ///
+    /// The counts are part of the position, see [get_checks](#method.get_checks)
+    pub fn set_three_check(&mut self, three_check: bool) {
+        let three_check_key = self.get_three_check_key();
+        self.three_check = three_check;
+        self.hash ^= three_check_key ^ self.get_three_check_key();
+    }
 
-                step.add_cond_pos_is_none(offset_pos);
-                step.add_cond_pos_is_none(*target_pos);
+    /// Gives back the number of checks given by `side` on a Three-check [`Board`]
+    pub fn get_checks(&self, side: &Side) -> u8 {
+        self.checks[*side as usize]
+    }
 
-                step.add_action_move(*unit_pos, *target_pos);
-            }
+    /// Sets the number of checks given by `side`
+    pub fn set_checks(&mut self, side: &Side, checks: u8) {
+        if self.three_check {
+            self.hash ^=
+                zobrist::check_key(side, self.get_checks(side)) ^ zobrist::check_key(side, checks);
         }
-        // Capture E.g. D5 -> C4 OR D5 -> E4 [Condition Group ID: 0]
-        // En Passant E.g. D5 -> C4 OR D5 -> E4 [Condition Group ID: 1]
-        else if calc_pos.x == 1 && calc_pos.y == 1 {
-            step.set(true);
 
-            // Capture
-            step.add_cond_pos_is_enemy(*target_pos, side);
-            step.add_cond_pos_not_king(*target_pos);
+        self.checks[*side as usize] = checks;
+    }
 
-            step.add_action_move(*unit_pos, *target_pos);
-            step.add_action_promote(*target_pos);
+    /// Returns the [`Pos`] skipped by the last Pawn 2 Step, E.g. E2 -> E4 gives back E3
+    ///
+    /// Only available for one turn, then returns `None` again
+    pub fn get_en_passant_pos(&self) -> Option<Pos> {
+        self.en_passant_pos
+    }
 
-            // En Passant
-            step.next_group();
-            step.add_cond_pos_is_enemy(offset_pos, side);
-            step.add_cond_pos_not_king(offset_pos);
-            step.add_cond_pos_is_none(*target_pos);
+    /// Sets the [`Pos`] where an enemy Pawn can capture En Passant
+    pub fn set_en_passant_pos(&mut self, pos: Option<Pos>) {
+        let en_passant_key = self.get_en_passant_key();
+        self.en_passant_pos = pos;
+        self.hash ^= en_passant_key ^ self.get_en_passant_key();
+    }
 
-            step.add_action_remove(offset_pos);
-            step.add_action_move(*unit_pos, *target_pos);
+    /// Gives back the [`Unit`] on the given position
+    ///
+    /// Panics when `pos` is not on the board, see [try_get_unit](#method.try_get_unit)
+    pub fn get_unit(&self, pos: &Pos) -> Option<Unit> {
+        match self.try_get_unit(pos) {
+            Ok(unit) => unit,
+            Err(error) => panic!("Cant get unit: {}", error),
         }
+    }
 
-        step.add_action_promote(*target_pos);
+    /// Gives back the [`Unit`] on the given position
+    ///
+    /// Fails with [`MoveError::OffBoard`] when `pos` is not on the board
+    pub fn try_get_unit(&self, pos: &Pos) -> Result<Option<Unit>, MoveError> {
+        if !pos.is_onboard() {
+            return Err(MoveError::OffBoard(*pos));
+        }
 
-        step
+        Ok(self.squares[pos.y as usize][pos.x as usize])
     }
 
-    fn step_bishop(&self, unit_pos: &Pos, target_pos: &Pos, side: &Side) -> Step<ConditionState> {
-        let mut step = Step::new(false);
+    /// Sets the [`Unit`] to the target position
+    ///
+    /// Panics when `pos` is not on the board, see [try_set_unit](#method.try_set_unit)
+    pub fn set_unit(&mut self, unit: Unit, pos: Pos) {
+        if let Err(error) = self.try_set_unit(unit, pos) {
+            panic!("Cant set unit: {}", error);
+        }
+    }
 
-        let calc_pos = (*target_pos - *unit_pos).abs();
+    /// Sets the [`Unit`] to the target position
+    ///
+    /// Fails with [`MoveError::OffBoard`] when `pos` is not on the board
+    pub fn try_set_unit(&mut self, unit: Unit, pos: Pos) -> Result<(), MoveError> {
+        if !pos.is_onboard() {
+            return Err(MoveError::OffBoard(pos));
+        }
 
-        // Diagonal Step
-        if calc_pos.x == calc_pos.y && calc_pos.sum() != 0 {
-            step.set(true);
+        let square_key = self.get_square_key();
+        self.place_unit(unit, pos);
+        self.hash ^= square_key ^ self.get_square_key();
 
-            for pos in unit_pos.to(target_pos) {
-                step.add_cond_pos_is_none(pos);
-            }
-            step.add_cond_pos_not_king(*target_pos);
-            step.add_cond_pos_is_enemy_or_none(*target_pos, side);
+        Ok(())
+    }
 
-            step.add_action_move(*unit_pos, *target_pos);
+    /// Removes a [`Unit`] from the [`Board`]
+    ///
+    /// Panics when `pos` is not on the board, see [try_remove_unit](#method.try_remove_unit)
+    pub fn remove_unit(&mut self, pos: &Pos) {
+        if let Err(error) = self.try_remove_unit(pos) {
+            panic!("Cant remove unit: {}", error);
         }
-
-        step
     }
 
-    fn step_knight(&self, unit_pos: &Pos, target_pos: &Pos, side: &Side) -> Step<ConditionState> {
-        let mut step = Step::new(false);
+    /// Removes a [`Unit`] from the [`Board`]
+    ///
+    /// Fails with [`MoveError::OffBoard`] when `pos` is not on the board
+    pub fn try_remove_unit(&mut self, pos: &Pos) -> Result<(), MoveError> {
+        if !pos.is_onboard() {
+            return Err(MoveError::OffBoard(*pos));
+        }
 
-        let calc_pos = (*target_pos - *unit_pos).abs();
+        let square_key = self.get_square_key();
+        self.put_unit(None, pos);
+        self.hash ^= square_key ^ self.get_square_key();
 
-        // L Step
-        if (calc_pos.x == 1 && calc_pos.y == 2) || (calc_pos.x == 2 && calc_pos.y == 1) {
-            step.set(true);
+        Ok(())
+    }
 
-            step.add_cond_pos_not_king(*target_pos);
-            step.add_cond_pos_is_enemy_or_none(*target_pos, side);
+    /// Places the [`Unit`] at `pos` and keeps track of the King, `pos` has to be on the board
+    ///
+    /// Leaves the keys of the castling rights and the En Passant column in the `hash` to the caller
+    fn place_unit(&mut self, unit: Unit, pos: Pos) {
+        self.put_unit(Some(unit), &pos);
 
-            step.add_action_move(*unit_pos, *target_pos);
+        if let Unit::King(side, _) = unit {
+            self.set_king_pos(&side, pos);
         }
-
-        step
     }
 
-    fn step_rook(&self, unit_pos: &Pos, target_pos: &Pos, side: &Side) -> Step<ConditionState> {
-        let mut step = Step::new(false);
+    /// Replaces the [`Unit`] at `pos` on the `squares` and the `bitboards`, `pos` has to be on the board
+    fn put_unit(&mut self, unit: Option<Unit>, pos: &Pos) {
+        let square = &mut self.squares[pos.y as usize][pos.x as usize];
 
-        let calc_pos = (*target_pos - *unit_pos).abs();
+        if let Some(old_unit) = square {
+            self.bitboards.remove(old_unit, pos);
+            self.hash ^= zobrist::unit_key(old_unit, pos);
+        }
+        if let Some(unit) = &unit {
+            self.bitboards.set(unit, pos);
+            self.hash ^= zobrist::unit_key(unit, pos);
+        }
 
-        // Horizontal / Vertical Step
-        if (calc_pos.x == 0 || calc_pos.y == 0) && calc_pos.sum() != 0 {
-            step.set(true);
+        *square = unit;
+    }
 
-            for pos in unit_pos.to(target_pos) {
-                step.add_cond_pos_is_none(pos);
-            }
-            step.add_cond_pos_not_king(*target_pos);
-            step.add_cond_pos_is_enemy_or_none(*target_pos, side);
+    /// Mutates [`Board`] when called with a legal step of the side in the current turn
+    ///
+    /// Pawns reaching the last row promote to Queen, see [test_step_promote](#method.test_step_promote)
+    pub fn test_step(&mut self, unit_pos: &Pos, target_pos: &Pos) -> bool {
+        self.test_step_promote(unit_pos, target_pos, &Unit::QUEEN)
+    }
 
-            step.add_action_move(*unit_pos, *target_pos);
+    /// Mutates [`Board`] when called with a legal step of the side in the current turn
+    ///
+    /// Pawns reaching the last row promote to the type of `promotion`, which has to be one of [`Unit::PROMOTIONS`]
+    ///
+    /// See [try_find_move](#method.try_find_move) for the reason of a failed step
+    pub fn test_step_promote(
+        &mut self,
+        unit_pos: &Pos,
+        target_pos: &Pos,
+        promotion: &Unit,
+    ) -> bool {
+        match self.try_find_move(unit_pos, target_pos, promotion) {
+            Ok(chess_move) => self.make_move(&chess_move),
+            Err(_) => false,
         }
+    }
 
-        step
+    /// Mutates [`Board`] with a [`Move`] given by [legal_moves](#method.legal_moves)
+    ///
+    /// Returns false when the [`Move`] is not viable on the current [`Board`]
+    pub fn make_move(&mut self, chess_move: &Move) -> bool {
+        self.make_move_image(chess_move).is_some()
     }
 
-    fn step_queen(&self, unit_pos: &Pos, target_pos: &Pos, side: &Side) -> Step<ConditionState> {
-        let mut step = Step::new(false);
+    /// Mutates [`Board`] with a [`Move`] given by [legal_moves](#method.legal_moves)
+    ///
+    /// Returns the [`MoveImage`] which takes back the [`Move`], see [unmake_move](#method.unmake_move)
+    pub fn make_move_image(&mut self, chess_move: &Move) -> Option<MoveImage> {
+        if chess_move.is_drop() {
+            let legal_move = self
+                .find_drop(&chess_move.unit, &chess_move.target_pos)
+                .filter(|legal_move| !self.test_leaves_check(legal_move))?;
+
+            return Some(self.apply_move(&legal_move));
+        }
 
-        let calc_pos = (*target_pos - *unit_pos).abs();
+        let (unit_pos, target_pos) = (chess_move.unit_pos, chess_move.target_pos);
+        let promotion = chess_move.promotion.unwrap_or(Unit::QUEEN);
 
-        // Horizontal / Vertical / Diagonal Step
-        if (calc_pos.x == 0 || calc_pos.y == 0 || calc_pos.x == calc_pos.y) && calc_pos.sum() != 0 {
-            step.set(true);
+        if !Unit::PROMOTIONS
+            .iter()
+            .any(|unit| eq_unit_type(unit, &promotion))
+        {
+            return None;
+        }
 
-            for pos in unit_pos.to(target_pos) {
-                step.add_cond_pos_is_none(pos);
-            }
-            step.add_cond_pos_not_king(*target_pos);
-            step.add_cond_pos_is_enemy_or_none(*target_pos, side);
+        let unit = self.try_get_unit(&unit_pos).ok()??;
+        if !target_pos.is_onboard()
+            || (self.find_step_targets(&unit, &unit_pos) & Bitboard::from_pos(&target_pos))
+                .is_empty()
+        {
+            return None;
+        }
 
-            step.add_action_move(*unit_pos, *target_pos);
+        let mut legal_move = self
+            .find_pseudo_move(&unit, &unit_pos, &target_pos)
+            .filter(|legal_move| !self.test_leaves_check(legal_move))?;
+        if legal_move.is_promotion() {
+            legal_move.promotion = Some(unit.change_type(&promotion));
         }
 
-        step
+        Some(self.apply_move(&legal_move))
     }
 
-    fn step_king(
-        &self,
-        unit_pos: &Pos,
-        target_pos: &Pos,
-        side: &Side,
-        moved: &Moved,
-    ) -> Step<ConditionState> {
-        let mut step = Step::new(false);
+    /// Mutates [`Board`] with a legal [`Move`] without validating it
+    ///
+    /// Returns the [`MoveImage`] which takes back the [`Move`]
+    pub(crate) fn apply_move(&mut self, chess_move: &Move) -> MoveImage {
+        let mut move_image = MoveImage {
+            chess_move: *chess_move,
+            step_images: Vec::with_capacity(4),
+            current_turn: self.current_turn,
+            black_king_pos: self.black_king_pos,
+            white_king_pos: self.white_king_pos,
+            en_passant_pos: self.en_passant_pos,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            pockets: self.pockets,
+            promoted: self.promoted,
+            checks: self.checks,
+            hash: self.hash,
+        };
+        let step_images = &mut move_image.step_images;
+
+        // Only the steps of Kings and Rooks and the captures of Rooks change the castling rights
+        let castling_key = match eq_unit_type(&chess_move.unit, &Unit::KING)
+            || eq_unit_type(&chess_move.unit, &Unit::ROOK)
+            || chess_move
+                .captured
+                .is_some_and(|unit| eq_unit_type(&unit, &Unit::ROOK))
+        {
+            true => Some(self.get_castling_key()),
+            false => None,
+        };
+        let en_passant_key = self.get_en_passant_key();
 
-        let calc_pos = *target_pos - *unit_pos;
+        // Crazyhouse, the captured Unit goes into the Pocket, a promoted one as a Pawn
+        if self.crazyhouse {
+            let side = chess_move.unit.get_side();
 
-        // 1 Area Step
-        if calc_pos.x.abs() <= 1 && calc_pos.y.abs() <= 1 {
-            step.set(true);
+            if let Some(captured) = chess_move.captured {
+                let captured_pos = chess_move.get_captured_pos();
+                let unit = match self.is_promoted(&captured_pos) {
+                    true => Unit::PAWN,
+                    false => captured,
+                };
+                let mut pocket = self.pockets[side as usize];
+                pocket.add(&unit);
+                self.set_pocket(&side, pocket);
+                self.set_promoted(&captured_pos, false);
+            }
 
-            step.add_cond_pos_is_enemy_or_none(*target_pos, side);
-            step.add_cond_pos_not_king(*target_pos);
+            if chess_move.is_drop() {
+                let mut pocket = self.pockets[side as usize];
+                pocket.remove(&chess_move.unit);
+                self.set_pocket(&side, pocket);
+            } else if !chess_move.is_castle() {
+                let promoted = self.is_promoted(&chess_move.unit_pos) || chess_move.is_promotion();
+                self.set_promoted(&chess_move.unit_pos, false);
+                self.set_promoted(&chess_move.target_pos, promoted);
+            }
+        }
 
-            step.add_action_move(*unit_pos, *target_pos);
-        } else if calc_pos.y == 0 && !self.test_checked_status(side) {
-            // Castle Left E.g. E1 -> C1
-            if calc_pos.x == -2 && !moved {
-                step.set(true);
+        // En Passant Capture
+        if chess_move.is_en_passant() {
+            let captured_pos = chess_move.get_captured_pos();
+            step_images.push(StepImage::new(chess_move.captured, captured_pos));
+            self.put_unit(None, &captured_pos);
+        }
 
-                for pos in unit_pos.to(&target_pos.start_row()) {
-                    step.add_cond_pos_is_none(pos);
+        // Castle, the King and the Rook may step onto each other's squares on a Chess960 Board
+        if let Some((rook_pos, rook_target_pos)) = self.get_castle_rook(chess_move) {
+            let king_target_pos = chess_move.get_unit_target();
+            for pos in [
+                chess_move.unit_pos,
+                rook_pos,
+                king_target_pos,
+                rook_target_pos,
+            ] {
+                if !step_images.iter().any(|step_image| step_image.pos == pos) {
+                    step_images.push(StepImage::new(self.get_unit(&pos), pos));
                 }
-                step.add_cond_pos_not_moved(target_pos.start_row());
-
-                step.add_action_move(*unit_pos, *target_pos);
-                step.add_action_move(target_pos.start_row(), target_pos.bounded_right());
             }
-            // Castle Right E.g. E1 -> G1
-            else if calc_pos.x == 2 && !moved {
-                step.set(true);
 
-                for pos in unit_pos.to(&target_pos.end_row()) {
-                    step.add_cond_pos_is_none(pos);
-                }
-                step.add_cond_pos_not_moved(target_pos.end_row());
-
-                step.add_action_move(*unit_pos, *target_pos);
-                step.add_action_move(target_pos.end_row(), target_pos.bounded_left());
+            let rook = self.get_unit(&rook_pos);
+            self.put_unit(None, &chess_move.unit_pos);
+            self.put_unit(None, &rook_pos);
+            self.place_unit(chess_move.unit.set_moved(true), king_target_pos);
+            if let Some(rook) = rook {
+                self.place_unit(rook.set_moved(true), rook_target_pos);
             }
         }
-
-        step
-    }
-
-    /// Returns true when checked
-    fn test_checked_status(&self, side: &Side) -> bool {
-        let mut positions = Vec::new();
-
-        let king_pos = self.get_king_pos(side);
-        if king_pos.is_none() {
-            return false;
+        // Drop from the Pocket
+        else if chess_move.is_drop() {
+            step_images.push(StepImage::new(None, chess_move.target_pos));
+            self.place_unit(chess_move.unit, chess_move.target_pos);
         }
-        let king_pos = king_pos.unwrap();
-
-        // Find Units All Direction
-        for direction in Pos::ALL_DIRECTIONS {
-            if let Some(pos) = self.find_unit_direction(king_pos, direction) {
-                positions.push(pos)
-            }
+        // Step + Promotion
+        else {
+            let unit = chess_move.promotion.unwrap_or(chess_move.unit);
+            step_images.push(StepImage::new(Some(chess_move.unit), chess_move.unit_pos));
+            step_images.push(StepImage::new(
+                self.get_unit(&chess_move.target_pos),
+                chess_move.target_pos,
+            ));
+            self.put_unit(None, &chess_move.unit_pos);
+            self.place_unit(unit.set_moved(true), chess_move.target_pos);
         }
 
-        // Find Knights
-        positions.extend(self.find_knights(king_pos));
+        self.update_turn_status(
+            &chess_move.unit,
+            &chess_move.unit_pos,
+            &chess_move.target_pos,
+            chess_move.is_capture(),
+        );
+        self.hash ^= en_passant_key ^ self.get_en_passant_key();
+        if let Some(castling_key) = castling_key {
+            self.hash ^= castling_key ^ self.get_castling_key();
+        }
 
-        // Filter Friendly Units
-        positions = positions
-            .into_iter()
-            .filter(|pos| self.get_unit(pos).unwrap().get_side() != *side)
-            .collect();
+        // Three-check, the Move counts when it puts the enemy King in check
+        if self.three_check && self.test_checked_status(&self.current_turn) {
+            let side = chess_move.unit.get_side();
+            self.set_checks(&side, self.get_checks(&side).saturating_add(1));
+        }
 
-        for pos in positions {
-            let unit = self.get_unit(&pos).unwrap();
+        move_image
+    }
 
-            // Check if any Unit is able to take the King
-            if self.test_checking(&unit, &pos, &king_pos) {
-                return true;
-            }
+    /// Takes back the [`Move`] of the [`MoveImage`] given by [make_move_image](#method.make_move_image)
+    ///
+    /// Works only when the [`Move`] was the last one made on the [`Board`]
+    pub fn unmake_move(&mut self, move_image: &MoveImage) {
+        for step_image in &move_image.step_images {
+            step_image.reconstruct(self);
         }
 
-        false
+        self.current_turn = move_image.current_turn;
+        self.black_king_pos = move_image.black_king_pos;
+        self.white_king_pos = move_image.white_king_pos;
+        self.en_passant_pos = move_image.en_passant_pos;
+        self.halfmove_clock = move_image.halfmove_clock;
+        self.fullmove_number = move_image.fullmove_number;
+        self.pockets = move_image.pockets;
+        self.promoted = move_image.promoted;
+        self.checks = move_image.checks;
+        self.hash = move_image.hash;
     }
 
-    /// Searches for a [`Unit`] by repeatedly calling the `step_function` on the given [`Pos`]
-    ///
-    /// * Returns the [`Pos`] of the [`Unit`] when found
-    ///
-    /// * Returns `None` otherwise
-    fn find_unit_direction(&self, pos: Pos, step_function: &dyn Fn(&Pos) -> Pos) -> Option<Pos> {
-        let mut check_pos = step_function(&pos);
-        for _ in 0..BOARD_SIZE {
-            // No Unit found
-            if !check_pos.is_onboard() {
-                return None;
+    /// Updates the En Passant [`Pos`], the clocks and the current turn after the step of `unit`
+    fn update_turn_status(&mut self, unit: &Unit, unit_pos: &Pos, target_pos: &Pos, capture: bool) {
+        // En Passant Status
+        self.en_passant_pos = match unit {
+            Unit::Pawn(..) if (target_pos.y - unit_pos.y).abs() == 2 => {
+                Some(Pos::new(unit_pos.x, (unit_pos.y + target_pos.y) / 2))
             }
+            _ => None,
+        };
 
-            // Unit found
-            if self.get_unit(&check_pos).is_some() {
-                break;
-            }
+        // Turn Status
+        if capture || eq_unit_type(unit, &Unit::PAWN) {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
 
-            check_pos = step_function(&check_pos);
+        if unit.get_side() == Side::Black {
+            self.fullmove_number += 1;
         }
 
-        Some(check_pos)
+        self.put_turn(unit.get_side().oppose());
     }
 
-    /// Searches for Knight [`Unit`]s around given [`Pos`]
-    ///
-    /// Returns a vector, which contains all of the Knights found
-    fn find_knights(&self, pos: Pos) -> Vec<Pos> {
-        let mut positions = Vec::new();
-
-        for offset_pos in KNIGHT_OFFSETS {
-            let check_pos = pos + offset_pos;
-
-            if !check_pos.is_onboard() {
-                continue;
-            }
-
-            if let Some(unit) = self.get_unit(&check_pos) {
-                if let Unit::Knight(_) = unit {
-                    positions.push(check_pos);
-                }
-            }
+    /// Returns true when the King of `side` is in check
+    pub fn test_checked_status(&self, side: &Side) -> bool {
+        match self.get_king_pos(side) {
+            Some(king_pos) => self.test_attacked_status(&king_pos, &side.oppose()),
+            None => false,
         }
-
-        positions
     }
 
-    /// Returns true if the `unit` checks the King
-    fn test_checking(&self, unit: &Unit, unit_pos: &Pos, king_pos: &Pos) -> bool {
-        let calc_pos = (*king_pos - *unit_pos).abs();
-        let mut valid_check = false;
+    /// Returns true when neither side has enough [`Unit`]s to checkmate
+    ///
+    /// * King against King
+    /// * King and Bishop or Knight against King
+    /// * Kings and Bishops, when every Bishop stands on the same square color
+    ///
+    /// A [`Unit`] in a [`Pocket`] can always be dropped, so the material is sufficient
+    pub fn test_insufficient_material(&self) -> bool {
+        if !self.pockets.iter().all(Pocket::is_empty) {
+            return false;
+        }
 
-        match unit {
-            Unit::Pawn(side, _) => {
-                // Validate Step Direction
-                match side {
-                    Side::Black if king_pos.y < unit_pos.y => return false,
-                    Side::White if king_pos.y > unit_pos.y => return false,
-                    _ => (),
-                };
+        let mut units = Vec::new();
 
-                if calc_pos.x == 1 && calc_pos.y == 1 {
-                    valid_check = true;
-                }
-            }
-            Unit::Bishop(_) => {
-                if calc_pos.x == calc_pos.y && calc_pos.sum() != 0 {
-                    valid_check = true;
-                }
-            }
-            Unit::Knight(_) => {
-                if (calc_pos.x == 1 && calc_pos.y == 2) || (calc_pos.x == 2 && calc_pos.y == 1) {
-                    valid_check = true;
-                }
-            }
-            Unit::Rook(_, _) => {
-                if (calc_pos.x == 0 || calc_pos.y == 0) && calc_pos.sum() != 0 {
-                    valid_check = true;
-                }
-            }
-            Unit::Queen(_) => {
-                if (calc_pos.x == 0 || calc_pos.y == 0 || calc_pos.x == calc_pos.y)
-                    && calc_pos.sum() != 0
-                {
-                    valid_check = true;
-                }
-            }
-            Unit::King(_, _) => {
-                if calc_pos.x <= 1 && calc_pos.y <= 1 {
-                    valid_check = true;
+        for y in 0..BOARD_SIZE as i8 {
+            for x in 0..BOARD_SIZE as i8 {
+                match self.get_unit(&Pos::new(x, y)) {
+                    Some(Unit::King(..)) | None => {}
+                    Some(unit) => units.push((unit, (x + y) % 2)),
                 }
             }
         }
 
-        valid_check
+        match units.as_slice() {
+            [] => true,
+            [(Unit::Bishop(..) | Unit::Knight(..), _)] => true,
+            [(_, color), ..] => units.iter().all(|(unit, other_color)| {
+                matches!(unit, Unit::Bishop(..)) && other_color == color
+            }),
+        }
     }
 
+    /// Returns true when `side` has enough [`Unit`]s to checkmate, e.g. when the opponent runs out of time
     ///
-    fn test_valid_step_available(&self, side: &Side) -> bool {
-        let valid_step = false;
-        let check_rows: [i8; 8] = match side {
-            Side::Black => [1, 0, 2, 3, 4, 5, 6, 7],
-            Side::White => [6, 7, 1, 2, 3, 4, 5, 6],
-        };
-
-        for y in check_rows {
-            for x in 0..8i8 {
-                let unit_pos = Pos::new(x, y);
-                let unit = self.get_unit(&unit_pos);
-
-                if unit.is_none() {
-                    continue;
-                }
+    /// A lone King or a King with a single Bishop or Knight can't checkmate
+    pub fn test_mating_material(&self, side: &Side) -> bool {
+        let units = |unit: &Unit| self.bitboards.get_units(unit, side);
+        let minors = units(&Unit::BISHOP) | units(&Unit::KNIGHT);
+        let majors = units(&Unit::PAWN) | units(&Unit::ROOK) | units(&Unit::QUEEN);
 
-                let unit = unit.unwrap();
-                if unit.get_side() != *side {
-                    continue;
-                }
-
-                let offsets = match unit {
-                    Unit::Pawn(side, _) => match side {
-                        Side::Black => DOWNWARD_OFFSETS.as_ref(),
-                        Side::White => UPWARD_OFFSETS.as_ref(),
-                    },
-                    Unit::Bishop(..) => DIAGONAL_OFFSETS.as_ref(),
-                    Unit::Knight(..) => KNIGHT_OFFSETS.as_ref(),
-                    Unit::Rook(..) => CROSS_OFFSETS.as_ref(),
-                    _ => ALL_DIRECTION_OFFSETS.as_ref(),
-                };
-
-                for offset in offsets {
-                    let target_pos = unit_pos + *offset;
+        !majors.is_empty() || minors.count() > 1 || !self.get_pocket(side).is_empty()
+    }
 
-                    // Pos Not On Board -> Continue
-                    if !target_pos.is_onboard() {
-                        continue;
-                    }
+    /// Returns true when any [`Unit`] of `side` attacks the given `pos`
+    ///
+    /// The square at `pos` can be empty or occupied by any [`Unit`]
+    pub fn test_attacked_status(&self, pos: &Pos, side: &Side) -> bool {
+        self.bitboards.test_attacked(pos, side)
+    }
 
-                    let step = self.step_unit(&unit, &unit_pos, &target_pos);
+    /// Returns true when `side` has at least one legal step
+    ///
+    /// When it returns false the game is over:
+    /// * `side` is in check -> CheckMate
+    /// * `side` is not in check -> StaleMate
+    pub fn test_valid_step_available(&self, side: &Side) -> bool {
+        for unit_pos in self.bitboards.get_side(side) {
+            let unit = match self.get_unit(&unit_pos) {
+                Some(unit) => unit,
+                None => continue,
+            };
+
+            for target_pos in self.find_step_targets(&unit, &unit_pos) {
+                match self.find_pseudo_move(&unit, &unit_pos, &target_pos) {
+                    Some(chess_move) if !self.test_leaves_check(&chess_move) => return true,
+                    _ => {}
                 }
             }
         }
 
-        valid_step
+        self.legal_drops(side).next().is_some()
     }
-}
 
-impl Default for Board {
-    fn default() -> Self {
-        let mut board = Self {
-            squares: [[None; BOARD_SIZE]; BOARD_SIZE],
-            black_king_pos: None,
-            white_king_pos: None,
-        };
+    /// Gives back every legal [`Move`] of `side`, including the drops on a Crazyhouse [`Board`]
+    pub fn legal_moves(&self, side: &Side) -> Vec<Move> {
+        let mut moves = Vec::new();
 
-        // Black Back Row
-        board.set_unit(Unit::Rook(Side::Black, false), "A8".into());
-        board.set_unit(Unit::Knight(Side::Black), "B8".into());
-        board.set_unit(Unit::Bishop(Side::Black), "C8".into());
-        board.set_unit(Unit::Queen(Side::Black), "D8".into());
-        board.set_unit(Unit::King(Side::Black, false), "E8".into());
-        board.set_unit(Unit::Bishop(Side::Black), "F8".into());
-        board.set_unit(Unit::Knight(Side::Black), "G8".into());
-        board.set_unit(Unit::Rook(Side::Black, false), "H8".into());
-
-        // Black Front Row
-        board.set_unit(Unit::Pawn(Side::Black, false), "A7".into());
-        board.set_unit(Unit::Pawn(Side::Black, false), "B7".into());
-        board.set_unit(Unit::Pawn(Side::Black, false), "C7".into());
-        board.set_unit(Unit::Pawn(Side::Black, false), "D7".into());
-        board.set_unit(Unit::Pawn(Side::Black, false), "E7".into());
-        board.set_unit(Unit::Pawn(Side::Black, false), "F7".into());
-        board.set_unit(Unit::Pawn(Side::Black, false), "G7".into());
-        board.set_unit(Unit::Pawn(Side::Black, false), "H7".into());
-
-        // White Back Row
-        board.set_unit(Unit::Rook(Side::White, false), "A1".into());
-        board.set_unit(Unit::Knight(Side::White), "B1".into());
-        board.set_unit(Unit::Bishop(Side::White), "C1".into());
-        board.set_unit(Unit::Queen(Side::White), "D1".into());
-        board.set_unit(Unit::King(Side::White, false), "E1".into());
-        board.set_unit(Unit::Bishop(Side::White), "F1".into());
-        board.set_unit(Unit::Knight(Side::White), "G1".into());
-        board.set_unit(Unit::Rook(Side::White, false), "H1".into());
-
-        // Black Front Row
-        board.set_unit(Unit::Pawn(Side::White, false), "A2".into());
-        board.set_unit(Unit::Pawn(Side::White, false), "B2".into());
-        board.set_unit(Unit::Pawn(Side::White, false), "C2".into());
-        board.set_unit(Unit::Pawn(Side::White, false), "D2".into());
-        board.set_unit(Unit::Pawn(Side::White, false), "E2".into());
-        board.set_unit(Unit::Pawn(Side::White, false), "F2".into());
-        board.set_unit(Unit::Pawn(Side::White, false), "G2".into());
-        board.set_unit(Unit::Pawn(Side::White, false), "H2".into());
+        for unit_pos in self.bitboards.get_side(side) {
+            self.collect_legal_moves(&unit_pos, &mut moves);
+        }
+        moves.extend(self.legal_drops(side));
 
-        board
+        moves
     }
-}
 
-//==================================================
-//=== Step
-//================= =================================
-
-type GroupID = i8;
-type UnitPos = Pos;
-type TargetPos = Pos;
-
-trait State {}
-
-#[derive(Debug)]
-struct Step<S: State> {
-    groups: GroupID,
-    step_valid: bool,
-    condition_state: S,
-    actions: Vec<StepAction>,
-}
+    /// Gives back every legal drop of `side` from its [`Pocket`], see [set_crazyhouse](#method.set_crazyhouse)
+    fn legal_drops<'a>(&'a self, side: &Side) -> impl Iterator<Item = Move> + 'a {
+        let empty = !self.bitboards.get_occupied();
+        let units = match self.crazyhouse {
+            true => self.get_pocket(side).get_units(*side),
+            false => Vec::new(),
+        };
 
-//==================================================
-//=== Step: ConditionState
-//==================================================
+        units.into_iter().flat_map(move |unit| {
+            empty.filter_map(move |target_pos| {
+                self.find_drop(&unit, &target_pos)
+                    .filter(|chess_move| !self.test_leaves_check(chess_move))
+            })
+        })
+    }
 
-impl State for ConditionState {}
+    /// Finds the drop of the side in the current turn of a [`Unit`] with the type of `unit` onto `target_pos`
+    ///
+    /// Fails with the reason when there is no such legal drop, see [`MoveError`]
+    pub fn try_find_drop(&self, unit: &Unit, target_pos: &Pos) -> Result<Move, MoveError> {
+        self.try_get_unit(target_pos)?;
 
-impl Step<ConditionState> {
-    /// Creates a new [`Step`] with [`ConditionState`]
-    fn new(step_valid: bool) -> Self {
-        Self {
-            groups: 0,
-            step_valid,
-            condition_state: ConditionState::new(),
-            actions: Vec::new(),
+        let unit = unit.set_side(self.current_turn);
+        if self.get_pocket(&self.current_turn).get_count(&unit) == 0 {
+            return Err(MoveError::EmptyPocket(unit));
         }
-    }
 
-    /// Sets `step_valid` to the given value
-    fn set(&mut self, step_valid: bool) {
-        self.step_valid = step_valid;
+        self.find_drop(&unit, target_pos)
+            .filter(|chess_move| !self.test_leaves_check(chess_move))
+            .ok_or(MoveError::IllegalDrop(unit, *target_pos))
     }
 
-    /// Increases the number of groups in [`Step`]
-    fn next_group(&mut self) {
-        if self.groups < self.condition_state.step_conditions.len() as i8 {
-            self.groups += 1;
+    /// Produces the drop of `unit` from the [`Pocket`] of its side onto `target_pos`, the King may be left in check
+    ///
+    /// * The [`Board`] follows the Crazyhouse rules and the square is empty
+    /// * Pawns can't be dropped onto the first or the last row, they can take a 2 Step from their starting row
+    /// * Rooks are dropped as moved, they can't castle
+    fn find_drop(&self, unit: &Unit, target_pos: &Pos) -> Option<Move> {
+        let side = unit.get_side();
+        if !self.crazyhouse
+            || self.get_pocket(&side).get_count(unit) == 0
+            || self.try_get_unit(target_pos) != Ok(None)
+        {
+            return None;
         }
-    }
-
-    /// Adds an [`StepAction`] to [`Step`], which removes the unit at `pos`
-    fn add_action_remove(&mut self, pos: Pos) {
-        self.actions.push(StepAction {
-            group_id: self.groups,
-            command: Command::Remove(pos),
-        })
-    }
 
-    /// Adds an [`StepAction`] to [`Step`], which moves the unit from `unit_pos` to `target_pos`
-    fn add_action_move(&mut self, unit_pos: Pos, target_pos: Pos) {
-        self.actions.push(StepAction {
-            group_id: self.groups,
-            command: Command::Move(unit_pos, target_pos),
-        })
-    }
+        let unit = match unit {
+            Unit::Pawn(..) if target_pos.y == 0 || target_pos.y == 7 => return None,
+            Unit::Pawn(..) => {
+                let start_row = match side {
+                    Side::Black => 1,
+                    Side::White => 6,
+                };
+                Unit::Pawn(side, target_pos.y != start_row)
+            }
+            Unit::King(..) => return None,
+            unit => unit.set_moved(true),
+        };
 
-    /// Adds an [`StepAction`] to [`Step`], which moves the unit from `unit_pos` to `target_pos`
-    fn add_action_promote(&mut self, target_pos: Pos) {
-        self.actions.push(StepAction {
-            group_id: self.groups,
-            command: Command::Promote(target_pos),
+        Some(Move {
+            unit,
+            unit_pos: *target_pos,
+            target_pos: *target_pos,
+            captured: None,
+            promotion: None,
+            kind: MoveKind::Drop,
         })
     }
 
-    /// Adds a [`StepCondition`] to [`Step`]
+    /// Finds the legal [`Move`] of the side in the current turn from `unit_pos` to `target_pos`
     ///
-    /// Checks if the [`Pos`] is NOT occupied by any [`Unit`]
-    fn add_cond_pos_is_none(&mut self, pos: Pos) {
-        self.condition_state.step_conditions.push(StepCondition {
-            group_id: self.groups,
-            pos,
-            test: Test::None,
-        })
-    }
-
-    /// Adds a [`StepCondition`] to [`Step`]
+    /// Pawns reaching the last row promote to the type of `promotion`
     ///
-    /// Checks if the [`Unit`] at `pos` is an enemy to `side`
-    fn add_cond_pos_is_enemy(&mut self, pos: Pos, side: &Side) {
-        self.condition_state.step_conditions.push(StepCondition {
-            group_id: self.groups,
-            pos,
-            test: Test::Enemy(*side),
-        })
-    }
+    /// Fails with the reason when there is no such legal [`Move`], see [`MoveError`]
+    pub fn try_find_move(
+        &self,
+        unit_pos: &Pos,
+        target_pos: &Pos,
+        promotion: &Unit,
+    ) -> Result<Move, MoveError> {
+        let unit = self
+            .try_get_unit(unit_pos)?
+            .ok_or(MoveError::EmptySquare(*unit_pos))?;
+        self.try_get_unit(target_pos)?;
+
+        if unit.get_side() != self.current_turn {
+            return Err(MoveError::WrongSide(unit.get_side()));
+        }
 
-    /// Adds a [`StepCondition`] to [`Step`]
-    ///
-    /// Checks if the [`Unit`] at `pos` is EITHER an enemy to `side` OR NOT occupied by any [`Unit`]
-    fn add_cond_pos_is_enemy_or_none(&mut self, pos: Pos, side: &Side) {
-        self.condition_state.step_conditions.push(StepCondition {
-            group_id: self.groups,
-            pos,
-            test: Test::EnemyOrNone(*side),
-        })
-    }
+        if !Unit::PROMOTIONS
+            .iter()
+            .any(|unit| eq_unit_type(unit, promotion))
+        {
+            return Err(MoveError::IllegalMove(*unit_pos, *target_pos));
+        }
 
-    /// Adds a [`StepCondition`] to [`Step`]
-    ///
-    /// Checks if the [`Unit`] at `pos` is NOT the King Type
-    fn add_cond_pos_not_king(&mut self, pos: Pos) {
-        self.condition_state.step_conditions.push(StepCondition {
-            group_id: self.groups,
-            pos,
-            test: Test::NotKing,
-        })
+        let legal_move = self
+            .legal_moves_from(unit_pos)
+            .into_iter()
+            .find(|chess_move| {
+                chess_move.target_pos == *target_pos
+                    && match chess_move.promotion {
+                        Some(unit) => eq_unit_type(&unit, promotion),
+                        None => true,
+                    }
+            });
+
+        if let Some(chess_move) = legal_move {
+            return Ok(chess_move);
+        }
+
+        // Viable step, which fails only on the Checked Status
+        if target_pos.is_onboard()
+            && !(self.find_step_targets(&unit, unit_pos) & Bitboard::from_pos(target_pos))
+                .is_empty()
+            && self.find_pseudo_move(&unit, unit_pos, target_pos).is_some()
+        {
+            return Err(MoveError::LeavesKingInCheck(*unit_pos, *target_pos));
+        }
+
+        Err(MoveError::IllegalMove(*unit_pos, *target_pos))
     }
 
-    /// Adds a [`StepCondition`] to [`Step`]
+    /// Gives back every legal [`Move`] of the [`Unit`] at `pos`
     ///
-    /// Checks if the [`Unit`] at `pos` is NOT moved yet
-    fn add_cond_pos_not_moved(&mut self, pos: Pos) {
-        self.condition_state.step_conditions.push(StepCondition {
-            group_id: self.groups,
-            pos,
-            test: Test::NotMoved,
-        })
+    /// Returns an empty vector when there is no [`Unit`] at `pos`
+    pub fn legal_moves_from(&self, pos: &Pos) -> Vec<Move> {
+        let mut moves = Vec::new();
+        self.collect_legal_moves(pos, &mut moves);
+
+        moves
     }
 
-    /// Evaluates the [`StepCondition`]s on the given [`Board`]
-    ///
-    /// [`StepCondition`]s with the same `group_id` are connected with `AND`
-    ///
-    /// [`StepCondition`]s with the diferent `group_id` are connected with `OR`
-    fn evaluate(&self, board: &Board) -> Step<ResultState> {
-        let mut step = Step {
-            groups: self.groups,
-            step_valid: self.step_valid,
-            condition_state: ResultState::new(),
-            actions: self.actions.clone(),
+    /// Pushes every legal [`Move`] of the [`Unit`] at `pos` into `moves`
+    fn collect_legal_moves(&self, pos: &Pos, moves: &mut Vec<Move>) {
+        let unit = match self.get_unit(pos) {
+            Some(unit) => unit,
+            None => return,
         };
 
-        let mut group_valid = self.step_valid;
-        let mut group_id = 0;
-
-        for condition in self.condition_state.step_conditions.as_slice() {
-            // Valid Group Found
-            if condition.group_id > group_id && group_valid {
-                break;
+        for target_pos in self.find_step_targets(&unit, pos) {
+            let chess_move = match self.find_pseudo_move(&unit, pos, &target_pos) {
+                Some(chess_move) if !self.test_leaves_check(&chess_move) => chess_move,
+                _ => continue,
+            };
+
+            // Every Promotion Choice
+            if chess_move.is_promotion() {
+                for promotion in Unit::PROMOTIONS {
+                    moves.push(Move {
+                        promotion: Some(unit.change_type(&promotion)),
+                        ..chess_move
+                    });
+                }
+            } else {
+                moves.push(chess_move);
             }
+        }
+    }
 
-            // Next Group
-            if condition.group_id > group_id && !group_valid {
-                group_id += 1;
-                group_valid = true;
-            }
+    /// Produces the [`Move`] of `unit` from `unit_pos` to a `target_pos` given by [find_step_targets](#method.find_step_targets)
+    ///
+    /// The King may be left in check, see [test_leaves_check](#method.test_leaves_check)
+    ///
+    /// Promotions are produced with a Queen
+    fn find_pseudo_move(&self, unit: &Unit, unit_pos: &Pos, target_pos: &Pos) -> Option<Move> {
+        let captured = self.get_unit(target_pos);
+        if matches!(captured, Some(Unit::King(..))) {
+            return None;
+        }
 
-            // Skip Until Next Group
-            if condition.group_id == group_id && !group_valid {
-                continue;
-            }
+        let mut chess_move = Move {
+            unit: *unit,
+            unit_pos: *unit_pos,
+            target_pos: *target_pos,
+            captured,
+            promotion: None,
+            kind: MoveKind::Step,
+        };
+        let calc_pos = *target_pos - *unit_pos;
 
-            match condition.test {
-                Test::None => {
-                    if !board.get_unit(&condition.pos).is_none() {
-                        group_valid = false;
-                    }
-                }
-                Test::Enemy(side) => {
-                    if !matches!(board.get_unit(&condition.pos), Some(unit) if unit.get_side() != side)
-                    {
-                        group_valid = false;
+        match unit {
+            Unit::Pawn(side, moved) => {
+                // 1 Step / 2 Step
+                if calc_pos.x == 0 {
+                    if captured.is_some() {
+                        return None;
                     }
-                }
-                Test::EnemyOrNone(side) => {
-                    if !board.get_unit(&condition.pos).is_none() {
-                        if !matches!(board.get_unit(&condition.pos), Some(unit) if unit.get_side() != side)
-                        {
-                            group_valid = false;
+
+                    if calc_pos.y.abs() == 2 {
+                        let offset_pos = Pos::new(unit_pos.x, (unit_pos.y + target_pos.y) / 2);
+                        if *moved || self.get_unit(&offset_pos).is_some() {
+                            return None;
                         }
+                        chess_move.kind = MoveKind::DoubleStep;
                     }
                 }
-                Test::NotKing => {
-                    if !board.get_unit(&condition.pos).is_none() {
-                        if !matches!(board.get_unit(&condition.pos), Some(unit) if !eq_unit_type(&Unit::KING, &unit))
+                // En Passant
+                else if captured.is_none() {
+                    let offset_pos = Pos::new(target_pos.x, unit_pos.y);
+
+                    match self.get_unit(&offset_pos) {
+                        Some(pawn @ Unit::Pawn(pawn_side, _))
+                            if pawn_side != *side && self.en_passant_pos == Some(*target_pos) =>
                         {
-                            group_valid = false;
+                            chess_move.captured = Some(pawn);
+                            chess_move.kind = MoveKind::EnPassant;
                         }
+                        _ => return None,
                     }
                 }
-                Test::NotMoved => {
-                    if !matches!(board.get_unit(&condition.pos), Some(unit) if !unit.is_moved()) {
-                        group_valid = false;
-                    }
+
+                if target_pos.y == 0 || target_pos.y == 7 {
+                    chess_move.promotion = Some(unit.change_type(&Unit::QUEEN));
                 }
             }
-        }
+            // Castle
+            Unit::King(side, _)
+                if calc_pos.x.abs() > 1
+                    || captured.is_some_and(|unit| unit.get_side() == *side) =>
+            {
+                let (kind, rook_pos) = self.find_castle_rook(unit_pos, target_pos)?;
+                if !self.test_castle(unit_pos, &rook_pos, kind, side) {
+                    return None;
+                }
 
-        if group_valid == true {
-            step.set(true, group_id);
+                chess_move.captured = None;
+                chess_move.kind = kind;
+            }
+            _ => {}
         }
 
-        step
+        Some(chess_move)
     }
-}
 
-struct ConditionState {
-    step_conditions: Vec<StepCondition>,
-}
+    /// Returns true when the [`Move`] leaves the King of its side in check
+    ///
+    /// Tested on a copy of the `bitboards`, the [`Board`] does not change
+    fn test_leaves_check(&self, chess_move: &Move) -> bool {
+        let side = chess_move.unit.get_side();
+        let bitboards = self.get_bitboards_after(chess_move);
+
+        let king_pos = match chess_move.unit {
+            Unit::King(..) => Some(chess_move.get_unit_target()),
+            _ => self.get_king_pos(&side),
+        };
 
-impl ConditionState {
-    /// Creates a new [`ConditionState`] with empty `step_conditions`
-    fn new() -> Self {
-        Self {
-            step_conditions: Vec::new(),
+        match king_pos {
+            Some(king_pos) => bitboards.test_attacked(&king_pos, &side.oppose()),
+            None => false,
         }
     }
-}
 
-#[derive(Clone, Copy, Debug)]
-struct StepCondition {
-    group_id: GroupID,
-    pos: Pos,
-    test: Test,
-}
+    /// Returns true when the [`Move`] puts the King of the other side in check
+    ///
+    /// Tested on a copy of the `bitboards`, the [`Board`] does not change
+    pub fn test_gives_check(&self, chess_move: &Move) -> bool {
+        let side = chess_move.unit.get_side();
+
+        match self.get_king_pos(&side.oppose()) {
+            Some(king_pos) => self
+                .get_bitboards_after(chess_move)
+                .test_attacked(&king_pos, &side),
+            None => false,
+        }
+    }
 
-#[derive(Clone, Copy, Debug)]
-enum Test {
-    None,
-    Enemy(Side),
-    EnemyOrNone(Side),
-    NotKing,
-    NotMoved,
-}
+    /// Gives back a copy of the `bitboards` with the [`Move`] made on them
+    fn get_bitboards_after(&self, chess_move: &Move) -> Bitboards {
+        let mut bitboards = self.bitboards;
 
-//==================================================
-//=== Step: ResultState
-//==================================================
+        if let Some(captured) = chess_move.captured {
+            bitboards.remove(&captured, &chess_move.get_captured_pos());
+        }
+        bitboards.remove(&chess_move.unit, &chess_move.unit_pos);
 
-impl State for ResultState {}
+        if let Some((rook_pos, rook_target_pos)) = self.get_castle_rook(chess_move) {
+            let rook = Unit::Rook(chess_move.unit.get_side(), false);
+            bitboards.remove(&rook, &rook_pos);
+            bitboards.set(&rook, &rook_target_pos);
+        }
+        bitboards.set(
+            &chess_move.promotion.unwrap_or(chess_move.unit),
+            &chess_move.get_unit_target(),
+        );
 
-impl Step<ResultState> {
-    /// Sets [`StepResult`]'s `valid` and `group_id` values
-    fn set(&mut self, result_valid: bool, group_id: GroupID) {
-        self.condition_state.step_result.valid = result_valid;
-        self.condition_state.step_result.group_id = Some(group_id);
+        bitboards
     }
 
-    /// Checks if [`StepResult`] is valid
-    fn is_valid(&self) -> bool {
-        self.condition_state.step_result.valid
+    /// Collects the target [`Pos`]itions the `unit` could possibly step to
+    ///
+    /// The targets still have to be validated, see [find_pseudo_move](#method.find_pseudo_move)
+    fn find_step_targets(&self, unit: &Unit, unit_pos: &Pos) -> Bitboard {
+        let mut targets = bitboard::attacks(unit, unit_pos, self.bitboards.get_occupied());
+
+        // 1 Step, 2 Step / Castling
+        let offsets = match unit {
+            Unit::Pawn(Side::Black, _) => [Pos::new(0, 1), Pos::new(0, 2)],
+            Unit::Pawn(Side::White, _) => [Pos::new(0, -1), Pos::new(0, -2)],
+            // Chess960 Castle onto the own Rooks of the row
+            Unit::King(side, false) if self.chess960 => {
+                let row = Bitboard(0xFF << (unit_pos.y * 8));
+                return (targets & !self.bitboards.get_side(side))
+                    | (self.bitboards.get_units(&Unit::ROOK, side) & row);
+            }
+            Unit::King(..) => [Pos::new(-2, 0), Pos::new(2, 0)],
+            _ => return targets & !self.bitboards.get_side(&unit.get_side()),
+        };
+
+        for offset in offsets {
+            let target_pos = *unit_pos + offset;
+
+            if target_pos.is_onboard() {
+                targets |= Bitboard::from_pos(&target_pos);
+            }
+        }
+
+        targets & !self.bitboards.get_side(&unit.get_side())
     }
 
-    /// Executes the [`StepAction`]s on the given board based on the [`StepResult`]
-    fn execute_actions(&self, board: &mut Board) -> Step<ImageState> {
-        let mut step = Step {
-            groups: self.groups,
-            step_valid: self.step_valid,
-            condition_state: ImageState::new(),
-            actions: self.actions.clone(),
+    /// Gives back where the Rook steps from and to on a Castle, E.g. E1 -> G1 moves the Rook H1 -> F1
+    ///
+    /// On a Chess960 [`Board`] the Rook steps from the `target_pos` of the [`Move`], E.g. B1 -> A1 moves the Rook A1 -> D1
+    pub fn get_castle_rook(&self, chess_move: &Move) -> Option<(Pos, Pos)> {
+        if !chess_move.is_castle() {
+            return None;
+        }
+
+        let rook_pos = match chess_move.kind {
+            _ if self.chess960 => chess_move.target_pos,
+            MoveKind::CastleLeft => chess_move.target_pos.start_row(),
+            _ => chess_move.target_pos.end_row(),
         };
 
-        // Empty GroupID
-        if self.condition_state.step_result.group_id.is_none() {
-            return step;
+        Some((rook_pos, castle_targets(chess_move.kind, rook_pos.y).1))
+    }
+
+    /// Gives back the kind of the Castle and the [`Pos`] of the Rook when the King at `unit_pos` castles to `target_pos`
+    ///
+    /// Only the squares are checked, the rules of the Castle are checked by [test_castle](#method.test_castle)
+    fn find_castle_rook(&self, unit_pos: &Pos, target_pos: &Pos) -> Option<(MoveKind, Pos)> {
+        if unit_pos.y != target_pos.y {
+            return None;
         }
 
-        // Invalid StepResult
-        if !self.condition_state.step_result.valid {
-            return step;
+        let kind = match target_pos.x - unit_pos.x {
+            -2 if !self.chess960 => MoveKind::CastleLeft,
+            2 if !self.chess960 => MoveKind::CastleRight,
+            x if x < 0 && self.chess960 => MoveKind::CastleLeft,
+            x if x > 0 && self.chess960 => MoveKind::CastleRight,
+            _ => return None,
+        };
+
+        if !self.chess960 {
+            let rook_pos = match kind {
+                MoveKind::CastleLeft => target_pos.start_row(),
+                _ => target_pos.end_row(),
+            };
+            return Some((kind, rook_pos));
         }
 
-        for action in &self.actions {
-            if self.condition_state.step_result.group_id.unwrap() == action.group_id {
-                step.condition_state
-                    .step_images
-                    .extend(action.command.execute(board));
+        match (self.get_unit(unit_pos), self.get_unit(target_pos)) {
+            (Some(king), Some(Unit::Rook(side, false))) if king.get_side() == side => {
+                Some((kind, *target_pos))
             }
+            _ => None,
         }
-
-        step
     }
-}
 
-struct ResultState {
-    step_result: StepResult,
-}
-
-impl ResultState {
-    /// Creates a new [`ResultState`] with an invalid `step_result`
-    fn new() -> Self {
-        Self {
-            step_result: StepResult {
-                group_id: None,
-                valid: false,
-            },
+    /// Returns true when the King of `side` at `unit_pos` can castle with the Rook at `rook_pos`
+    ///
+    /// * Neither the King nor the Rook has moved yet and the King is not in check
+    /// * The squares the King and the Rook step over or to are empty, apart from the King and the Rook
+    /// * The King can't pass through or land on an attacked square
+    fn test_castle(&self, unit_pos: &Pos, rook_pos: &Pos, kind: MoveKind, side: &Side) -> bool {
+        if !matches!(self.get_unit(unit_pos), Some(Unit::King(king_side, false)) if king_side == *side)
+            || !matches!(self.get_unit(rook_pos), Some(Unit::Rook(rook_side, false)) if rook_side == *side)
+        {
+            return false;
         }
+
+        let (king_target_pos, rook_target_pos) = castle_targets(kind, unit_pos.y);
+
+        row_span(unit_pos, &king_target_pos)
+            .chain(row_span(rook_pos, &rook_target_pos))
+            .all(|pos| pos == *unit_pos || pos == *rook_pos || self.get_unit(&pos).is_none())
+            && row_span(unit_pos, &king_target_pos)
+                .all(|pos| !self.test_attacked_status(&pos, &side.oppose()))
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-struct StepResult {
-    group_id: Option<GroupID>,
-    valid: bool,
+/// Gives back where the King and the Rook end on the row `y` after a Castle of the `kind`
+fn castle_targets(kind: MoveKind, y: i8) -> (Pos, Pos) {
+    match kind {
+        MoveKind::CastleLeft => (Pos::new(2, y), Pos::new(3, y)),
+        _ => (Pos::new(6, y), Pos::new(5, y)),
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
-struct StepAction {
-    group_id: GroupID,
-    command: Command,
+/// Gives back every [`Pos`] of the row between `from` and `to`, both included
+fn row_span(from: &Pos, to: &Pos) -> impl Iterator<Item = Pos> {
+    let y = from.y;
+    (from.x.min(to.x)..=from.x.max(to.x)).map(move |x| Pos::new(x, y))
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Command {
-    Remove(UnitPos),
-    Move(UnitPos, TargetPos),
-    Promote(TargetPos),
-}
+impl Default for Board {
+    fn default() -> Self {
+        let mut board = Self::empty();
 
-impl Command {
-    /// Mutates [`Board`] using the [`StepAction`]s
-    ///
-    /// Returns a vector of [`StepImage`] which contains the former
-    fn execute(&self, board: &mut Board) -> Vec<StepImage> {
-        let mut step_image = Vec::new();
+        // Black Back Row
+        board.set_unit(Unit::Rook(Side::Black, false), Pos::from_notation("A8"));
+        board.set_unit(Unit::Knight(Side::Black), Pos::from_notation("B8"));
+        board.set_unit(Unit::Bishop(Side::Black), Pos::from_notation("C8"));
+        board.set_unit(Unit::Queen(Side::Black), Pos::from_notation("D8"));
+        board.set_unit(Unit::King(Side::Black, false), Pos::from_notation("E8"));
+        board.set_unit(Unit::Bishop(Side::Black), Pos::from_notation("F8"));
+        board.set_unit(Unit::Knight(Side::Black), Pos::from_notation("G8"));
+        board.set_unit(Unit::Rook(Side::Black, false), Pos::from_notation("H8"));
 
-        match self {
-            Self::Remove(pos) => {
-                step_image.push(StepImage::new(board.get_unit(pos), *pos));
+        // Black Front Row
+        board.set_unit(Unit::Pawn(Side::Black, false), Pos::from_notation("A7"));
+        board.set_unit(Unit::Pawn(Side::Black, false), Pos::from_notation("B7"));
+        board.set_unit(Unit::Pawn(Side::Black, false), Pos::from_notation("C7"));
+        board.set_unit(Unit::Pawn(Side::Black, false), Pos::from_notation("D7"));
+        board.set_unit(Unit::Pawn(Side::Black, false), Pos::from_notation("E7"));
+        board.set_unit(Unit::Pawn(Side::Black, false), Pos::from_notation("F7"));
+        board.set_unit(Unit::Pawn(Side::Black, false), Pos::from_notation("G7"));
+        board.set_unit(Unit::Pawn(Side::Black, false), Pos::from_notation("H7"));
 
-                board.remove_unit(pos);
-            }
-            Self::Move(unit_pos, target_pos) => {
-                if let Some(unit) = board.get_unit(unit_pos) {
-                    step_image.push(StepImage::new(Some(unit), *unit_pos));
-                    step_image.push(StepImage::new(board.get_unit(target_pos), *target_pos));
+        // White Back Row
+        board.set_unit(Unit::Rook(Side::White, false), Pos::from_notation("A1"));
+        board.set_unit(Unit::Knight(Side::White), Pos::from_notation("B1"));
+        board.set_unit(Unit::Bishop(Side::White), Pos::from_notation("C1"));
+        board.set_unit(Unit::Queen(Side::White), Pos::from_notation("D1"));
+        board.set_unit(Unit::King(Side::White, false), Pos::from_notation("E1"));
+        board.set_unit(Unit::Bishop(Side::White), Pos::from_notation("F1"));
+        board.set_unit(Unit::Knight(Side::White), Pos::from_notation("G1"));
+        board.set_unit(Unit::Rook(Side::White, false), Pos::from_notation("H1"));
 
-                    board.remove_unit(unit_pos);
-                    board.set_unit(unit.set_moved(true), *target_pos);
-                }
-            }
-            Self::Promote(pos) => board.promote(*pos),
-        }
+        // Black Front Row
+        board.set_unit(Unit::Pawn(Side::White, false), Pos::from_notation("A2"));
+        board.set_unit(Unit::Pawn(Side::White, false), Pos::from_notation("B2"));
+        board.set_unit(Unit::Pawn(Side::White, false), Pos::from_notation("C2"));
+        board.set_unit(Unit::Pawn(Side::White, false), Pos::from_notation("D2"));
+        board.set_unit(Unit::Pawn(Side::White, false), Pos::from_notation("E2"));
+        board.set_unit(Unit::Pawn(Side::White, false), Pos::from_notation("F2"));
+        board.set_unit(Unit::Pawn(Side::White, false), Pos::from_notation("G2"));
+        board.set_unit(Unit::Pawn(Side::White, false), Pos::from_notation("H2"));
 
-        step_image
+        board
     }
 }
 
 //==================================================
-//=== Step: ImageState
+//=== Move Image
 //==================================================
 
-impl State for ImageState {}
-
-impl Step<ImageState> {
-    /// Reconstruct the former state of a `square` in the [`Board`] from the [`StepImage`]
-    fn reconstruct(&self, board: &mut Board) -> () {
-        for step_image in &self.condition_state.step_images {
-            if let Some(unit) = step_image.unit {
-                board.set_unit(unit, step_image.pos)
-            } else {
-                board.remove_unit(&step_image.pos)
-            }
-        }
-    }
-}
-
-struct ImageState {
+/// The former state of the [`Board`] before a [`Move`], see [`Board::make_move_image`]
+///
+/// * `chess_move` the [`Move`] made on the [`Board`], including the captured [`Unit`]
+/// * `step_images` former state of the mutated squares, including the `Moved` status which holds the castling rights
+/// * `current_turn`, `en_passant_pos`, `halfmove_clock`, `fullmove_number` former turn status
+/// * `black_king_pos`, `white_king_pos` former position of the Kings
+/// * `pockets`, `promoted` former Crazyhouse status, see [`Board::set_crazyhouse`]
+/// * `checks` former Three-check status, see [`Board::set_three_check`]
+/// * `hash` former Zobrist hash, see [`Board::get_hash`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveImage {
+    pub chess_move: Move,
     step_images: Vec<StepImage>,
+    pub current_turn: Side,
+    pub black_king_pos: Option<Pos>,
+    pub white_king_pos: Option<Pos>,
+    pub en_passant_pos: Option<Pos>,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+    pockets: [Pocket; 2],
+    promoted: Bitboard,
+    checks: [u8; 2],
+    hash: u64,
 }
 
-impl ImageState {
-    /// Creates a new [`ImageState`] with empty `step_images`
-    fn new() -> Self {
-        Self {
-            step_images: Vec::new(),
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
+/// The former state of a square mutated by a [`Move`], see [`MoveImage`]
+#[derive(Clone, Copy, Debug, PartialEq)]
 struct StepImage {
     unit: Option<Unit>,
     pos: Pos,
@@ -1002,6 +1192,11 @@ impl StepImage {
     fn new(unit: Option<Unit>, pos: Pos) -> Self {
         Self { unit, pos }
     }
+
+    /// Reconstruct the former state of the `square` in the [`Board`]
+    fn reconstruct(&self, board: &mut Board) {
+        board.put_unit(self.unit, &self.pos);
+    }
 }
 
 //==================================================
@@ -1009,174 +1204,531 @@ impl StepImage {
 //==================================================
 
 #[cfg(test)]
-mod tests_step {
+mod tests_board {
     use super::*;
 
-    #[test]
-    fn test_no_condition() {
-        let step = Step::new(true);
+    /// Creates a [`Board`] from the given `units`
+    fn setup(units: &[(Unit, &str)]) -> Board {
+        let mut board = Board::empty();
+        for (unit, pos) in units {
+            board.set_unit(*unit, Pos::from_notation(pos));
+        }
 
-        let step = step.evaluate(&Board::default());
+        board
+    }
 
-        assert!(step.is_valid());
-        assert_eq!(step.condition_state.step_result.group_id, Some(0));
+    /// Finds the [`Move`] from `unit_pos` to `target_pos` in `moves`
+    fn find_move(moves: &[Move], unit_pos: &str, target_pos: &str) -> Option<Move> {
+        moves
+            .iter()
+            .find(|m| {
+                m.unit_pos == Pos::from_notation(unit_pos)
+                    && m.target_pos == Pos::from_notation(target_pos)
+            })
+            .copied()
     }
 
     #[test]
-    fn test_invalid() {
-        let mut step = Step::new(false);
+    fn test_legal_moves_default() {
+        let board = Board::default();
 
-        // [Group 0:] H2 -> Pawn
-        step.add_cond_pos_is_none("H2".into());
-
-        let step = step.evaluate(&Board::default());
+        assert_eq!(board.legal_moves(&Side::White).len(), 20);
+        assert_eq!(board.legal_moves(&Side::Black).len(), 20);
+    }
 
-        assert!(!step.is_valid());
-        assert_eq!(step.condition_state.step_result.group_id, None);
+    #[test]
+    fn test_step_follows_legal_moves() {
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let moves = board.legal_moves(&Side::White);
+
+        let all_pos = || (0..64).map(|square| Pos::new(square % 8, square / 8));
+        for unit_pos in all_pos() {
+            for target_pos in all_pos() {
+                let legal = moves.iter().any(|chess_move| {
+                    chess_move.unit_pos == unit_pos && chess_move.target_pos == target_pos
+                });
+                assert_eq!(
+                    board.clone().test_step(&unit_pos, &target_pos),
+                    legal,
+                    "{} -> {}",
+                    unit_pos,
+                    target_pos
+                );
+            }
+        }
     }
 
     #[test]
-    fn test_empty1() {
-        let mut step = Step::new(true);
+    fn test_legal_moves_from() {
+        let board = Board::default();
+        let moves = board.legal_moves_from(&Pos::from_notation("B1"));
+
+        assert_eq!(moves.len(), 2);
+        assert!(find_move(&moves, "B1", "A3").is_some());
+        assert!(find_move(&moves, "B1", "C3").is_some());
+        assert!(board.legal_moves_from(&Pos::from_notation("D4")).is_empty());
+    }
 
-        // [Group 0:] H2 -> Empty
-        step.add_cond_pos_is_none("D4".into());
+    #[test]
+    fn test_legal_moves_double_step() {
+        let board = Board::default();
+        let moves = board.legal_moves_from(&Pos::from_notation("E2"));
+
+        assert_eq!(find_move(&moves, "E2", "E3").unwrap().kind, MoveKind::Step);
+        assert_eq!(
+            find_move(&moves, "E2", "E4").unwrap().kind,
+            MoveKind::DoubleStep
+        );
+    }
 
-        // [Group 1:] H2 -> Pawn
-        step.next_group();
-        step.add_cond_pos_is_none("H2".into());
+    #[test]
+    fn test_legal_moves_capture() {
+        let board = setup(&[
+            (Unit::King(Side::White, true), "E1"),
+            (Unit::Rook(Side::White, true), "D4"),
+            (Unit::King(Side::Black, true), "E8"),
+            (Unit::Knight(Side::Black), "D7"),
+        ]);
+        let moves = board.legal_moves_from(&Pos::from_notation("D4"));
+
+        let capture = find_move(&moves, "D4", "D7").unwrap();
+        assert!(capture.is_capture());
+        assert_eq!(capture.captured, Some(Unit::Knight(Side::Black)));
+        assert!(find_move(&moves, "D4", "D8").is_none());
+        assert!(!find_move(&moves, "D4", "D6").unwrap().is_capture());
+    }
 
-        // [Group 2:] D8 -> Queen
-        step.next_group();
-        step.add_cond_pos_is_none("D8".into());
+    #[test]
+    fn test_legal_moves_castle() {
+        let board = setup(&[
+            (Unit::King(Side::White, false), "E1"),
+            (Unit::Rook(Side::White, false), "A1"),
+            (Unit::Rook(Side::White, false), "H1"),
+            (Unit::King(Side::Black, true), "E8"),
+        ]);
+        let moves = board.legal_moves_from(&Pos::from_notation("E1"));
+
+        let castle = find_move(&moves, "E1", "C1").unwrap();
+        assert!(castle.is_castle());
+        assert_eq!(castle.kind, MoveKind::CastleLeft);
+
+        let castle = find_move(&moves, "E1", "G1").unwrap();
+        assert_eq!(castle.kind, MoveKind::CastleRight);
+    }
 
-        let step = step.evaluate(&Board::default());
+    /// Creates a [`Board`] where both sides can castle on both wings, plus the given `units`
+    fn setup_castle(units: &[(Unit, &str)]) -> Board {
+        let mut board = setup(&[
+            (Unit::King(Side::White, false), "E1"),
+            (Unit::Rook(Side::White, false), "A1"),
+            (Unit::Rook(Side::White, false), "H1"),
+            (Unit::King(Side::Black, false), "E8"),
+            (Unit::Rook(Side::Black, false), "A8"),
+            (Unit::Rook(Side::Black, false), "H8"),
+        ]);
+        for (unit, pos) in units {
+            board.set_unit(*unit, Pos::from_notation(pos));
+        }
 
-        assert!(step.is_valid());
-        assert_eq!(step.condition_state.step_result.group_id, Some(0));
+        board
     }
 
     #[test]
-    fn test_empty2() {
-        let mut step = Step::new(true);
+    fn test_attacked_status() {
+        let board = setup(&[
+            (Unit::King(Side::White, true), "E1"),
+            (Unit::Pawn(Side::White, true), "D4"),
+            (Unit::King(Side::Black, true), "E8"),
+            (Unit::Bishop(Side::Black), "A6"),
+        ]);
+
+        // Pawn
+        assert!(board.test_attacked_status(&Pos::from_notation("E5"), &Side::White));
+        assert!(board.test_attacked_status(&Pos::from_notation("C5"), &Side::White));
+        assert!(!board.test_attacked_status(&Pos::from_notation("D5"), &Side::White));
+        assert!(!board.test_attacked_status(&Pos::from_notation("E3"), &Side::White));
+
+        // Bishop, the empty F1 is attacked, E3 is not
+        assert!(board.test_attacked_status(&Pos::from_notation("F1"), &Side::Black));
+        assert!(!board.test_attacked_status(&Pos::from_notation("E3"), &Side::Black));
+
+        // King
+        assert!(board.test_attacked_status(&Pos::from_notation("F1"), &Side::White));
+    }
 
-        // [Group 0:] H2 -> Pawn
-        step.add_cond_pos_is_none("H2".into());
+    #[test]
+    fn test_castle_white() {
+        let mut board = setup_castle(&[]);
+        assert!(board.test_step(&Pos::from_notation("E1"), &Pos::from_notation("G1")));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("F1")),
+            Some(Unit::Rook(Side::White, true))
+        );
+
+        let mut board = setup_castle(&[]);
+        assert!(board.test_step(&Pos::from_notation("E1"), &Pos::from_notation("C1")));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("D1")),
+            Some(Unit::Rook(Side::White, true))
+        );
+    }
 
-        // [Group 1:] E1 -> King
-        step.next_group();
-        step.add_cond_pos_is_none("E1".into());
+    #[test]
+    fn test_castle_black() {
+        let mut board = setup_castle(&[]);
+        board.set_current_turn(Side::Black);
+        assert!(board.test_step(&Pos::from_notation("E8"), &Pos::from_notation("G8")));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("F8")),
+            Some(Unit::Rook(Side::Black, true))
+        );
+
+        let mut board = setup_castle(&[]);
+        board.set_current_turn(Side::Black);
+        assert!(board.test_step(&Pos::from_notation("E8"), &Pos::from_notation("C8")));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("D8")),
+            Some(Unit::Rook(Side::Black, true))
+        );
+    }
 
-        // [Group 2:] D8 -> Queen
-        step.next_group();
-        step.add_cond_pos_is_none("D8".into());
+    #[test]
+    fn test_castle_through_attacked_white() {
+        // F1 and D1 attacked by Black Rooks
+        let mut board = setup_castle(&[
+            (Unit::Rook(Side::Black, true), "F5"),
+            (Unit::Rook(Side::Black, true), "D5"),
+        ]);
+
+        assert!(!board.test_step(&Pos::from_notation("E1"), &Pos::from_notation("G1")));
+        assert!(!board.test_step(&Pos::from_notation("E1"), &Pos::from_notation("C1")));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("E1")),
+            Some(Unit::King(Side::White, false))
+        );
+    }
 
-        let step = step.evaluate(&Board::default());
+    #[test]
+    fn test_castle_through_attacked_black() {
+        // F8 and D8 attacked by White Bishops
+        let mut board = setup_castle(&[
+            (Unit::Bishop(Side::White), "H6"),
+            (Unit::Bishop(Side::White), "B6"),
+        ]);
+
+        assert!(!board.test_step(&Pos::from_notation("E8"), &Pos::from_notation("G8")));
+        assert!(!board.test_step(&Pos::from_notation("E8"), &Pos::from_notation("C8")));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("E8")),
+            Some(Unit::King(Side::Black, false))
+        );
+    }
 
-        assert!(!step.is_valid());
-        assert_eq!(step.condition_state.step_result.group_id, None);
+    #[test]
+    fn test_castle_into_attacked() {
+        // G1 and C1 attacked by Black Knights, G8 and C8 attacked by White Knights
+        let board = setup_castle(&[
+            (Unit::Knight(Side::Black), "H3"),
+            (Unit::Knight(Side::Black), "B3"),
+            (Unit::Knight(Side::White), "H6"),
+            (Unit::Knight(Side::White), "B6"),
+        ]);
+
+        assert!(!board
+            .legal_moves(&Side::White)
+            .iter()
+            .any(|m| m.is_castle()));
+        assert!(!board
+            .legal_moves(&Side::Black)
+            .iter()
+            .any(|m| m.is_castle()));
     }
 
     #[test]
-    fn test_empty3() {
-        let mut step = Step::new(true);
+    fn test_castle_rook_attacked() {
+        // Only B1 / B8 are attacked, the King does not cross them, which does not prevent castling
+        let board = setup_castle(&[(Unit::Rook(Side::Black, true), "B5")]);
+        assert!(board.test_attacked_status(&Pos::from_notation("B1"), &Side::Black));
 
-        // [Group 0:] H2 -> Pawn | A2 -> Pawn
-        step.add_cond_pos_is_none("H2".into());
-        step.add_cond_pos_is_none("A2".into());
+        let moves = board.legal_moves_from(&Pos::from_notation("E1"));
+        assert!(find_move(&moves, "E1", "C1").is_some());
 
-        // [Group 1:] D4 -> Empty | H2 -> Empty | D8 -> Empty
-        step.next_group();
-        step.add_cond_pos_is_none("D4".into());
-        step.add_cond_pos_is_none("H4".into());
-        step.add_cond_pos_is_none("D4".into());
+        let board = setup_castle(&[(Unit::Rook(Side::White, true), "B4")]);
+        assert!(board.test_attacked_status(&Pos::from_notation("B8"), &Side::White));
 
-        // [Group 2:] D4 -> Empty | H4 -> Empty | A2 -> Pawn
-        step.next_group();
-        step.add_cond_pos_is_none("D4".into());
-        step.add_cond_pos_is_none("H4".into());
-        step.add_cond_pos_is_none("A2".into());
+        let moves = board.legal_moves_from(&Pos::from_notation("E8"));
+        assert!(find_move(&moves, "E8", "C8").is_some());
+    }
 
-        let step = step.evaluate(&Board::default());
+    #[test]
+    fn test_castle_out_of_check() {
+        let mut board = setup_castle(&[(Unit::Rook(Side::Black, true), "E5")]);
 
-        assert!(step.is_valid());
-        assert_eq!(step.condition_state.step_result.group_id, Some(1));
+        assert!(!board.test_step(&Pos::from_notation("E1"), &Pos::from_notation("G1")));
+        assert!(!board.test_step(&Pos::from_notation("E1"), &Pos::from_notation("C1")));
     }
 
     #[test]
-    fn test_empty_group1() {
-        let mut step = Step::new(true);
+    fn test_castle_without_rook() {
+        let mut board = setup_castle(&[(Unit::Knight(Side::Black), "H1")]);
 
-        // Add group should do nothing here!
-        step.next_group();
-
-        // [Group 0:] D4 -> Empty | H2 -> Pawn | E4 -> Empty
-        step.add_cond_pos_is_none("D4".into());
-        step.add_cond_pos_is_none("H2".into());
-        step.add_cond_pos_is_none("E4".into());
+        assert!(!board.test_step(&Pos::from_notation("E1"), &Pos::from_notation("G1")));
+    }
 
-        // [Group 1:] D4 -> Empty | H4 -> Empty | A4 -> Empty
-        step.next_group();
-        step.add_cond_pos_is_none("D4".into());
-        step.add_cond_pos_is_none("H4".into());
-        step.add_cond_pos_is_none("A4".into());
+    #[test]
+    fn test_legal_moves_en_passant() {
+        let mut board = setup(&[
+            (Unit::King(Side::White, true), "E1"),
+            (Unit::Pawn(Side::White, true), "E5"),
+            (Unit::King(Side::Black, true), "E8"),
+            (Unit::Pawn(Side::Black, true), "D5"),
+        ]);
+        board.set_en_passant_pos(Some(Pos::from_notation("D6")));
+        let moves = board.legal_moves_from(&Pos::from_notation("E5"));
+
+        let en_passant = find_move(&moves, "E5", "D6").unwrap();
+        assert!(en_passant.is_en_passant());
+        assert_eq!(en_passant.captured, Some(Unit::Pawn(Side::Black, true)));
+    }
 
-        let step = step.evaluate(&Board::default());
+    #[test]
+    fn test_en_passant_not_pawn() {
+        let mut board = setup(&[
+            (Unit::King(Side::White, true), "E1"),
+            (Unit::Pawn(Side::White, true), "E5"),
+            (Unit::King(Side::Black, true), "E8"),
+            (Unit::Bishop(Side::Black), "D5"),
+            (Unit::Knight(Side::Black), "F5"),
+        ]);
+        board.set_en_passant_pos(Some(Pos::from_notation("D6")));
+
+        assert!(!board.test_step(&Pos::from_notation("E5"), &Pos::from_notation("D6")));
+        assert!(!board.test_step(&Pos::from_notation("E5"), &Pos::from_notation("F6")));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("D5")),
+            Some(Unit::Bishop(Side::Black))
+        );
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("F5")),
+            Some(Unit::Knight(Side::Black))
+        );
+    }
 
-        assert!(step.is_valid());
-        assert_eq!(step.condition_state.step_result.group_id, Some(1));
+    #[test]
+    fn test_en_passant_without_double_step() {
+        let board = setup(&[
+            (Unit::King(Side::White, true), "E1"),
+            (Unit::Pawn(Side::White, true), "E5"),
+            (Unit::King(Side::Black, true), "E8"),
+            (Unit::Pawn(Side::Black, true), "D5"),
+        ]);
+
+        assert!(find_move(
+            &board.legal_moves_from(&Pos::from_notation("E5")),
+            "E5",
+            "D6"
+        )
+        .is_none());
     }
 
     #[test]
-    fn test_empty_group2() {
-        let mut step = Step::new(true);
+    fn test_en_passant_pos() {
+        let mut board = Board::default();
 
-        // [Group 0:] D4 -> Empty | H2 -> Pawn | e4 -> Empty
-        step.next_group();
-        step.add_cond_pos_is_none("D4".into());
-        step.add_cond_pos_is_none("H2".into());
-        step.add_cond_pos_is_none("E4".into());
+        assert!(board.test_step(&Pos::from_notation("E2"), &Pos::from_notation("E4")));
+        assert_eq!(board.get_en_passant_pos(), Some(Pos::from_notation("E3")));
 
-        // [Group 1:] D4 -> Empty | E2 -> Pawn
-        step.next_group();
-        step.add_cond_pos_is_none("D4".into());
-        step.add_cond_pos_is_none("E2".into());
+        assert!(board.test_step(&Pos::from_notation("G8"), &Pos::from_notation("F6")));
+        assert_eq!(board.get_en_passant_pos(), None);
 
-        // [Group 2:] Empty Group
-        step.next_group();
+        assert!(board.test_step(&Pos::from_notation("E4"), &Pos::from_notation("E5")));
+        assert!(board.test_step(&Pos::from_notation("D7"), &Pos::from_notation("D5")));
+        assert_eq!(board.get_en_passant_pos(), Some(Pos::from_notation("D6")));
 
-        let step = step.evaluate(&Board::default());
+        assert!(board.test_step(&Pos::from_notation("E5"), &Pos::from_notation("D6")));
+        assert_eq!(board.get_unit(&Pos::from_notation("D5")), None);
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("D6")),
+            Some(Unit::Pawn(Side::White, true))
+        );
+    }
 
-        assert!(!step.is_valid());
-        assert_eq!(step.condition_state.step_result.group_id, None);
+    #[test]
+    fn test_en_passant_expires() {
+        let mut board = Board::default();
+
+        assert!(board.test_step(&Pos::from_notation("E2"), &Pos::from_notation("E4")));
+        assert!(board.test_step(&Pos::from_notation("A7"), &Pos::from_notation("A6")));
+        assert!(board.test_step(&Pos::from_notation("E4"), &Pos::from_notation("E5")));
+        assert!(board.test_step(&Pos::from_notation("D7"), &Pos::from_notation("D5")));
+        assert!(board.test_step(&Pos::from_notation("H2"), &Pos::from_notation("H3")));
+        assert!(board.test_step(&Pos::from_notation("H7"), &Pos::from_notation("H6")));
+
+        assert!(!board.test_step(&Pos::from_notation("E5"), &Pos::from_notation("D6")));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("D5")),
+            Some(Unit::Pawn(Side::Black, true))
+        );
     }
 
     #[test]
-    fn test_last_condition_valid() {
-        let mut step = Step::new(true);
+    fn test_legal_moves_promotion() {
+        let board = setup(&[
+            (Unit::King(Side::White, true), "E1"),
+            (Unit::Pawn(Side::White, true), "A7"),
+            (Unit::King(Side::Black, true), "E8"),
+            (Unit::Rook(Side::Black, true), "B8"),
+        ]);
+        let moves = board.legal_moves_from(&Pos::from_notation("A7"));
+
+        // 2 Steps x 4 Promotions
+        assert_eq!(moves.len(), 8);
+        assert!(moves.iter().all(|m| m.is_promotion()));
+        assert_eq!(
+            find_move(&moves, "A7", "B8").unwrap().promotion,
+            Some(Unit::Queen(Side::White))
+        );
+        assert!(moves
+            .iter()
+            .any(|m| m.target_pos == Pos::from_notation("A8")
+                && m.promotion == Some(Unit::Knight(Side::White))));
+    }
 
-        // [Group 0:] B8 -> Black Knight
-        step.add_cond_pos_is_enemy("B8".into(), &Side::Black);
+    #[test]
+    fn test_under_promotion() {
+        let units = [
+            (Unit::King(Side::White, true), "E1"),
+            (Unit::Pawn(Side::White, true), "A7"),
+            (Unit::King(Side::Black, true), "E8"),
+        ];
+
+        let mut board = setup(&units);
+        assert!(board.test_step(&Pos::from_notation("A7"), &Pos::from_notation("A8")));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("A8")),
+            Some(Unit::Queen(Side::White))
+        );
+
+        for promotion in Unit::PROMOTIONS {
+            let mut board = setup(&units);
+            assert!(board.test_step_promote(
+                &Pos::from_notation("A7"),
+                &Pos::from_notation("A8"),
+                &promotion
+            ));
+            assert!(eq_unit_type(
+                &board.get_unit(&Pos::from_notation("A8")).unwrap(),
+                &promotion
+            ));
+        }
 
-        // [Group 1:] B1 -> White Knight
-        step.next_group();
-        step.add_cond_pos_is_enemy_or_none("B1".into(), &Side::White);
+        let mut board = setup(&units);
+        assert!(!board.test_step_promote(
+            &Pos::from_notation("A7"),
+            &Pos::from_notation("A8"),
+            &Unit::KING
+        ));
+        assert!(!board.test_step_promote(
+            &Pos::from_notation("A7"),
+            &Pos::from_notation("A8"),
+            &Unit::PAWN
+        ));
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("A7")),
+            Some(Unit::Pawn(Side::White, true))
+        );
+    }
 
-        // [Group 2:] F7 -> Pawn
-        step.next_group();
-        step.add_cond_pos_is_none("F7".into());
+    #[test]
+    fn test_legal_moves_pinned() {
+        let board = setup(&[
+            (Unit::King(Side::White, true), "E1"),
+            (Unit::Knight(Side::White), "E2"),
+            (Unit::King(Side::Black, true), "A8"),
+            (Unit::Rook(Side::Black, true), "E8"),
+        ]);
+
+        assert!(board.legal_moves_from(&Pos::from_notation("E2")).is_empty());
+        assert!(board
+            .legal_moves(&Side::White)
+            .iter()
+            .all(|m| m.unit_pos == Pos::from_notation("E1")));
+    }
 
-        // [Group 3:] E8 -> King
-        step.next_group();
-        step.add_cond_pos_not_king("E8".into());
+    #[test]
+    fn test_unmake_move() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+
+            for chess_move in board.legal_moves(&board.get_current_turn()) {
+                let mut board_state = board.clone();
+                let move_image = board_state.make_move_image(&chess_move).unwrap();
+                assert_eq!(move_image.chess_move, chess_move);
+                assert_ne!(board_state, board);
+
+                board_state.unmake_move(&move_image);
+                assert_eq!(board_state, board, "{}", chess_move);
+            }
+        }
+    }
 
-        // [Group 4:] H8 -> Rook (Moved = false)
-        step.next_group();
-        step.add_cond_pos_not_moved("H8".into());
+    #[test]
+    fn test_try_unit() {
+        let mut board = Board::default();
+        let pos = Pos::new(8, 0);
+
+        assert_eq!(board.try_get_unit(&pos), Err(MoveError::OffBoard(pos)));
+        assert_eq!(
+            board.try_set_unit(Unit::QUEEN, pos),
+            Err(MoveError::OffBoard(pos))
+        );
+        assert_eq!(board.try_remove_unit(&pos), Err(MoveError::OffBoard(pos)));
+
+        assert_eq!(board.try_remove_unit(&Pos::from_notation("D8")), Ok(()));
+        assert_eq!(board.try_get_unit(&Pos::from_notation("D8")), Ok(None));
+    }
 
-        let step = step.evaluate(&Board::default());
+    #[test]
+    #[should_panic]
+    fn test_get_unit_panics() {
+        Board::default().get_unit(&Pos::new(-1, 0));
+    }
 
-        assert!(step.is_valid());
-        assert_eq!(step.condition_state.step_result.group_id, Some(4));
+    #[test]
+    fn test_insufficient_material() {
+        for (fen, insufficient) in [
+            ("4k3/8/8/8/8/8/8/4K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/4KB2 w - - 0 1", true),
+            ("4kn2/8/8/8/8/8/8/4K3 w - - 0 1", true),
+            ("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1", true),
+            ("4k1b1/8/8/8/8/8/8/2B1K3 w - - 0 1", false),
+            ("4k3/8/8/8/8/8/8/3NKN2 w - - 0 1", false),
+            ("4kn2/8/8/8/8/8/8/4KB2 w - - 0 1", false),
+            ("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1", false),
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", false),
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+            assert_eq!(board.test_insufficient_material(), insufficient, "{}", fen);
+        }
+    }
+    #[test]
+    fn test_mating_material() {
+        for (fen, white, black) in [
+            ("4k3/8/8/8/8/8/8/4K3 w - - 0 1", false, false),
+            ("4kn2/8/8/8/8/8/8/4KB2 w - - 0 1", false, false),
+            ("4k3/8/8/8/8/8/8/3NKN2 w - - 0 1", true, false),
+            ("4k3/4p3/8/8/8/8/8/4K3 w - - 0 1", false, true),
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", true, false),
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+            assert_eq!(board.test_mating_material(&Side::White), white, "{}", fen);
+            assert_eq!(board.test_mating_material(&Side::Black), black, "{}", fen);
+        }
     }
 }
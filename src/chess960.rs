@@ -0,0 +1,250 @@
+// Chess Crate
+use super::chess::Board;
+use super::game::Game;
+
+/// Number of the Chess960 start positions
+pub const CHESS960_COUNT: u16 = 960;
+
+/// Index of the standard start position among the Chess960 start positions
+pub const CHESS960_STANDARD: u16 = 518;
+
+/// Columns of the two Knights among the 5 squares left after the Bishops and the Queen
+const KNIGHTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+//==================================================
+//=== Chess960: Board
+//==================================================
+
+impl Board {
+    /// Creates the Chess960 start position with the given `index`, see [`CHESS960_COUNT`]
+    ///
+    /// Follows the Scharnagl numbering, E.g. 518 is the standard start position
+    ///
+    /// The [`Board`] follows the Chess960 castling rules, see [set_chess960](#method.set_chess960)
+    ///
+    /// Returns `None` when the `index` is out of range
+    pub fn from_chess960(index: u16) -> Option<Self> {
+        let back_row = chess960_back_row(index)?;
+
+        let mut board = Board::from_fen(&format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1",
+            back_row.to_ascii_lowercase(),
+            back_row
+        ))
+        .ok()?;
+        board.set_chess960(true);
+
+        Some(board)
+    }
+}
+
+/// Gives back the White back row of the Chess960 start position with the given `index` as FEN, E.g. "RNBQKBNR"
+fn chess960_back_row(index: u16) -> Option<String> {
+    if index >= CHESS960_COUNT {
+        return None;
+    }
+
+    let mut row = [None; 8];
+    let mut n = index as usize;
+
+    // Bishops on opposite square colors
+    row[n % 4 * 2 + 1] = Some('B');
+    n /= 4;
+    row[n % 4 * 2] = Some('B');
+    n /= 4;
+
+    // Queen on one of the 6 empty squares
+    let queen = n % 6;
+    n /= 6;
+    place_nth(&mut row, queen, 'Q');
+
+    // Knights on 2 of the 5 empty squares, the later one first to keep the order of the empty squares
+    let (first, second) = KNIGHTS[n];
+    place_nth(&mut row, second, 'N');
+    place_nth(&mut row, first, 'N');
+
+    // King between the Rooks
+    for unit in ['R', 'K', 'R'] {
+        place_nth(&mut row, 0, unit);
+    }
+
+    Some(row.iter().flatten().collect())
+}
+
+/// Places the `unit` on the `n`-th empty square of the `row`
+fn place_nth(row: &mut [Option<char>; 8], n: usize, unit: char) {
+    if let Some(square) = row.iter_mut().filter(|square| square.is_none()).nth(n) {
+        *square = Some(unit);
+    }
+}
+
+//==================================================
+//=== Chess960: Game
+//==================================================
+
+impl Game {
+    /// Creates a new [`Game`] from the Chess960 start position with the given `index`
+    ///
+    /// See also [`Board::from_chess960`]
+    pub fn new_chess960(index: u16) -> Option<Self> {
+        Some(Game::from_board(Board::from_chess960(index)?))
+    }
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_chess960 {
+    use super::*;
+    use crate::pos::Pos;
+    use crate::unit::{Side, Unit};
+
+    #[test]
+    fn test_back_rows() {
+        assert_eq!(chess960_back_row(0).unwrap(), "BBQNNRKR");
+        assert_eq!(chess960_back_row(CHESS960_STANDARD).unwrap(), "RNBQKBNR");
+        assert_eq!(chess960_back_row(959).unwrap(), "RKRNNQBB");
+        assert_eq!(chess960_back_row(CHESS960_COUNT), None);
+    }
+
+    #[test]
+    fn test_every_start_position() {
+        let mut rows: Vec<String> = (0..CHESS960_COUNT)
+            .map(|index| chess960_back_row(index).unwrap())
+            .collect();
+
+        for row in &rows {
+            let find = |unit| row.match_indices(unit).map(|(x, _)| x).collect::<Vec<_>>();
+            let (bishops, rooks, king) = (find('B'), find('R'), find('K'));
+
+            assert_eq!(bishops[0] % 2 + bishops[1] % 2, 1);
+            assert!(rooks[0] < king[0] && king[0] < rooks[1]);
+        }
+
+        rows.sort();
+        rows.dedup();
+        assert_eq!(rows.len(), CHESS960_COUNT as usize);
+    }
+
+    #[test]
+    fn test_standard_index() {
+        let board = Board::from_chess960(CHESS960_STANDARD).unwrap();
+
+        assert!(board.is_chess960());
+        assert_eq!(board.to_fen(), crate::fen::START_FEN);
+        assert_eq!(board.perft(3), 8902);
+    }
+
+    #[test]
+    fn test_castle_onto_rook() {
+        // King B1, Rooks A1 and H1
+        let mut board = Board::from_fen("4r1k1/8/8/8/8/8/8/RK5R w HA - 0 1").unwrap();
+        assert!(board.is_chess960());
+
+        let chess_move = board.try_find_move(
+            &Pos::from_notation("B1"),
+            &Pos::from_notation("A1"),
+            &Unit::QUEEN,
+        );
+        let chess_move = chess_move.unwrap();
+        assert!(chess_move.is_castle());
+        assert_eq!(chess_move.get_unit_target(), Pos::new(2, 7));
+
+        let move_image = board.make_move_image(&chess_move).unwrap();
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("C1")),
+            Some(Unit::King(Side::White, true))
+        );
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("D1")),
+            Some(Unit::Rook(Side::White, true))
+        );
+        assert_eq!(board.get_unit(&Pos::from_notation("A1")), None);
+        assert_eq!(board.to_fen(), "4r1k1/8/8/8/8/8/8/2KR3R b - - 1 1");
+
+        board.unmake_move(&move_image);
+        assert!(board
+            .clone()
+            .test_step(&Pos::from_notation("B1"), &Pos::from_notation("A1")));
+        assert!(!board
+            .clone()
+            .test_step(&Pos::from_notation("B1"), &Pos::from_notation("H1")));
+        assert_eq!(board.to_shredder_fen(), "4r1k1/8/8/8/8/8/8/RK5R w HA - 0 1");
+
+        // The King can't pass through E1 attacked by the E8 Rook
+        assert!(board
+            .try_find_move(
+                &Pos::from_notation("B1"),
+                &Pos::from_notation("H1"),
+                &Unit::QUEEN
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_castling_rights() {
+        let board = Board::from_fen("r1k4r/8/8/8/8/8/8/1RK1R2R w KQk - 0 1").unwrap();
+
+        assert!(board.is_chess960());
+        assert_eq!(board.to_fen(), "r1k4r/8/8/8/8/8/8/1RK1R2R w KQk - 0 1");
+        assert_eq!(
+            board.to_shredder_fen(),
+            "r1k4r/8/8/8/8/8/8/1RK1R2R w HBh - 0 1"
+        );
+
+        // The inner E1 Rook needs the column in X-FEN
+        let board = Board::from_fen("r1k4r/8/8/8/8/8/8/1RK1R2R w EBh - 0 1").unwrap();
+        assert_eq!(board.to_fen(), "r1k4r/8/8/8/8/8/8/1RK1R2R w EQk - 0 1");
+    }
+
+    #[test]
+    fn test_perft() {
+        let tests = [
+            (
+                "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+                vec![21, 528, 12189],
+            ),
+            (
+                "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+                vec![21, 807, 18002],
+            ),
+            (
+                "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+                vec![20, 479, 10471],
+            ),
+        ];
+
+        for (fen, counts) in tests {
+            let board = Board::from_fen(fen).unwrap();
+
+            for (depth, count) in counts.into_iter().enumerate() {
+                assert_eq!(board.perft(depth as u32 + 1), count, "{}", fen);
+            }
+        }
+    }
+
+    #[test]
+    fn test_new_chess960() {
+        let game = Game::new_chess960(0).unwrap();
+
+        assert!(game.get_start_board().is_chess960());
+        assert_eq!(
+            game.to_fen(),
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+        );
+        assert!(Game::new_chess960(CHESS960_COUNT).is_none());
+    }
+}
@@ -0,0 +1,498 @@
+// Standard Crate
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+// Chess Crate
+use super::unit::Side;
+
+//==================================================
+//=== Time Source
+//==================================================
+
+/// Source of the time measured by the [`Clock`]
+///
+/// Use [`RealTime`] for playing and [`ManualTime`] for testing
+pub trait TimeSource: fmt::Debug {
+    /// Gives back the time passed since a fixed starting point
+    fn now(&self) -> Duration;
+}
+
+/// [`TimeSource`] following the system time
+#[derive(Debug)]
+pub struct RealTime {
+    start: Instant,
+}
+
+impl Default for RealTime {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl RealTime {
+    /// Creates a new RealTime starting now
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TimeSource for RealTime {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// [`TimeSource`] which only moves when told to
+///
+/// Clones share the same time, keep one to move the time of the [`Clock`]
+#[derive(Clone, Debug, Default)]
+pub struct ManualTime {
+    time: Rc<Cell<Duration>>,
+}
+
+impl ManualTime {
+    /// Creates a new ManualTime starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the time forward by `time`
+    pub fn advance(&self, time: Duration) {
+        self.time.set(self.time.get() + time);
+    }
+}
+
+impl TimeSource for ManualTime {
+    fn now(&self) -> Duration {
+        self.time.get()
+    }
+}
+
+//==================================================
+//=== Time Control
+//==================================================
+
+/// Time added for the moves of a [`Stage`]
+///
+/// * `Fischer` added after every move
+/// * `Bronstein` time spent on the move is given back after the move, at most the given delay
+/// * `Delay` the clock only starts counting after the given delay
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Increment {
+    None,
+    Fischer(Duration),
+    Bronstein(Duration),
+    Delay(Duration),
+}
+
+/// * `moves` number of moves to play in the stage, `None` for the rest of the game
+/// * `time` time added to the clock when the stage starts
+/// * `increment` time added for the moves of the stage, see [`Increment`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stage {
+    pub moves: Option<u32>,
+    pub time: Duration,
+    pub increment: Increment,
+}
+
+/// Stages of the time control, e.g. 40 moves in 90 minutes then 30 minutes for the rest of the game
+///
+/// When the last [`Stage`] has a number of `moves` it starts again after them
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeControl {
+    pub stages: Vec<Stage>,
+}
+
+impl TimeControl {
+    /// Creates a new TimeControl from the given [`Stage`]s
+    pub fn new(stages: Vec<Stage>) -> Self {
+        Self { stages }
+    }
+
+    /// All moves in `time`
+    pub fn sudden_death(time: Duration) -> Self {
+        Self::single(time, Increment::None)
+    }
+
+    /// All moves in `time`, `increment` is added after every move
+    pub fn fischer(time: Duration, increment: Duration) -> Self {
+        Self::single(time, Increment::Fischer(increment))
+    }
+
+    /// All moves in `time`, time spent on a move is given back up to `delay`
+    pub fn bronstein(time: Duration, delay: Duration) -> Self {
+        Self::single(time, Increment::Bronstein(delay))
+    }
+
+    /// All moves in `time`, the clock waits `delay` before counting down on each move
+    pub fn simple_delay(time: Duration, delay: Duration) -> Self {
+        Self::single(time, Increment::Delay(delay))
+    }
+
+    fn single(time: Duration, increment: Increment) -> Self {
+        Self::new(vec![Stage {
+            moves: None,
+            time,
+            increment,
+        }])
+    }
+}
+
+//==================================================
+//=== Clock
+//==================================================
+
+/// Chess clock of both sides following a [`TimeControl`]
+///
+/// Only the clock of the side in `turn` runs, [press](#method.press) ends the turn
+#[derive(Debug)]
+pub struct Clock {
+    time_control: TimeControl,
+    time_source: Box<dyn TimeSource>,
+    remaining: [Duration; 2],
+    stages: [usize; 2],
+    stage_moves: [u32; 2],
+    turn: Side,
+    running: bool,
+    turn_start: Duration,
+    turn_elapsed: Duration,
+}
+
+impl Clock {
+    /// Creates a new stopped Clock following the system time, White is in turn
+    pub fn new(time_control: TimeControl) -> Self {
+        Self::with_time_source(time_control, RealTime::new())
+    }
+
+    /// Creates a new stopped Clock following the given [`TimeSource`], White is in turn
+    pub fn with_time_source(
+        time_control: TimeControl,
+        time_source: impl TimeSource + 'static,
+    ) -> Self {
+        let time = time_control
+            .stages
+            .first()
+            .map(|stage| stage.time)
+            .unwrap_or_default();
+
+        Self {
+            time_control,
+            time_source: Box::new(time_source),
+            remaining: [time; 2],
+            stages: [0; 2],
+            stage_moves: [0; 2],
+            turn: Side::White,
+            running: false,
+            turn_start: Duration::ZERO,
+            turn_elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Gives back the [`TimeControl`] of the clock
+    pub fn get_time_control(&self) -> &TimeControl {
+        &self.time_control
+    }
+
+    /// Gives back the side whose clock is in turn
+    pub fn get_turn(&self) -> Side {
+        self.turn
+    }
+
+    /// Returns true when the clock in turn counts down
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Starts the clock of the side in turn
+    pub fn start(&mut self) {
+        if !self.running {
+            self.running = true;
+            self.turn_start = self.time_source.now();
+        }
+    }
+
+    /// Stops the clock of the side in turn, the turn goes on after [start](#method.start)
+    pub fn stop(&mut self) {
+        if self.running {
+            self.running = false;
+            self.turn_elapsed += self.time_source.now().saturating_sub(self.turn_start);
+        }
+    }
+
+    /// Gives the turn to `side` without counting a move, the time spent so far is kept
+    pub fn set_turn(&mut self, side: Side) {
+        let elapsed = self.get_turn_elapsed();
+        let idx = side_idx(self.turn);
+        self.remaining[idx] = self.remaining[idx].saturating_sub(self.get_spent(elapsed));
+
+        self.turn = side;
+        self.turn_start = self.time_source.now();
+        self.turn_elapsed = Duration::ZERO;
+    }
+
+    /// Ends the turn after a move, adds the increment and gives the turn to the other side
+    ///
+    /// Returns false when the time of the side in turn ran out, the turn stays then
+    pub fn press(&mut self) -> bool {
+        let elapsed = self.get_turn_elapsed();
+        let idx = side_idx(self.turn);
+        let spent = self.get_spent(elapsed);
+
+        if spent >= self.remaining[idx] {
+            self.remaining[idx] = Duration::ZERO;
+            return false;
+        }
+        self.remaining[idx] -= spent;
+
+        let stage = self.get_stage(self.turn).copied();
+        match stage.map(|stage| stage.increment) {
+            Some(Increment::Fischer(increment)) => self.remaining[idx] += increment,
+            Some(Increment::Bronstein(delay)) => self.remaining[idx] += elapsed.min(delay),
+            _ => {}
+        }
+
+        // Next Stage
+        self.stage_moves[idx] += 1;
+        if let Some(moves) = stage.and_then(|stage| stage.moves) {
+            if self.stage_moves[idx] >= moves {
+                if self.stages[idx] + 1 < self.time_control.stages.len() {
+                    self.stages[idx] += 1;
+                }
+                self.stage_moves[idx] = 0;
+                self.remaining[idx] += self.time_control.stages[self.stages[idx]].time;
+            }
+        }
+
+        self.turn = self.turn.oppose();
+        self.turn_start = self.time_source.now();
+        self.turn_elapsed = Duration::ZERO;
+
+        true
+    }
+
+    /// Gives back the time left on the clock of `side`
+    pub fn get_remaining(&self, side: Side) -> Duration {
+        let remaining = self.remaining[side_idx(side)];
+
+        if side == self.turn {
+            remaining.saturating_sub(self.get_spent(self.get_turn_elapsed()))
+        } else {
+            remaining
+        }
+    }
+
+    /// Gives back the time left on the clock of `side` as text
+    ///
+    /// E.g. "1:30:00", "4:59" or "9.5" under ten seconds
+    pub fn get_remaining_str(&self, side: Side) -> String {
+        let remaining = self.get_remaining(side);
+        let seconds = remaining.as_secs();
+
+        if seconds >= 3600 {
+            format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        } else if seconds >= 10 {
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        } else {
+            format!("{}.{}", seconds, remaining.subsec_millis() / 100)
+        }
+    }
+
+    /// Returns true when the time of `side` ran out
+    pub fn is_flagged(&self, side: Side) -> bool {
+        self.get_remaining(side).is_zero()
+    }
+
+    /// Gives back the [`Stage`] the clock of `side` is in
+    pub fn get_stage(&self, side: Side) -> Option<&Stage> {
+        self.time_control.stages.get(self.stages[side_idx(side)])
+    }
+
+    /// Gives back the time spent in the current turn
+    fn get_turn_elapsed(&self) -> Duration {
+        if self.running {
+            self.turn_elapsed + self.time_source.now().saturating_sub(self.turn_start)
+        } else {
+            self.turn_elapsed
+        }
+    }
+
+    /// Gives back the time counted down from the clock in turn for `elapsed` time
+    fn get_spent(&self, elapsed: Duration) -> Duration {
+        match self.get_stage(self.turn).map(|stage| stage.increment) {
+            Some(Increment::Delay(delay)) => elapsed.saturating_sub(delay),
+            _ => elapsed,
+        }
+    }
+}
+
+/// Gives back the index of the `side` in the clock arrays
+fn side_idx(side: Side) -> usize {
+    match side {
+        Side::White => 0,
+        Side::Black => 1,
+    }
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_clock {
+    use super::*;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    fn setup(time_control: TimeControl) -> (Clock, ManualTime) {
+        let time = ManualTime::new();
+        let mut clock = Clock::with_time_source(time_control, time.clone());
+        clock.start();
+
+        (clock, time)
+    }
+
+    #[test]
+    fn test_sudden_death() {
+        let (mut clock, time) = setup(TimeControl::sudden_death(secs(60)));
+
+        time.advance(secs(10));
+        assert_eq!(clock.get_remaining(Side::White), secs(50));
+        assert_eq!(clock.get_remaining(Side::Black), secs(60));
+        assert!(clock.press());
+
+        time.advance(secs(20));
+        assert_eq!(clock.get_remaining(Side::White), secs(50));
+        assert_eq!(clock.get_remaining(Side::Black), secs(40));
+        assert!(clock.press());
+
+        time.advance(secs(50));
+        assert!(clock.is_flagged(Side::White));
+        assert!(!clock.press());
+        assert_eq!(clock.get_turn(), Side::White);
+    }
+
+    #[test]
+    fn test_fischer() {
+        let (mut clock, time) = setup(TimeControl::fischer(secs(60), secs(5)));
+
+        time.advance(secs(10));
+        assert!(clock.press());
+        assert_eq!(clock.get_remaining(Side::White), secs(55));
+
+        time.advance(secs(2));
+        assert!(clock.press());
+        assert_eq!(clock.get_remaining(Side::Black), secs(63));
+    }
+
+    #[test]
+    fn test_bronstein() {
+        let (mut clock, time) = setup(TimeControl::bronstein(secs(60), secs(5)));
+
+        time.advance(secs(10));
+        assert_eq!(clock.get_remaining(Side::White), secs(50));
+        assert!(clock.press());
+        assert_eq!(clock.get_remaining(Side::White), secs(55));
+
+        // Never more than the time spent
+        time.advance(secs(2));
+        assert!(clock.press());
+        assert_eq!(clock.get_remaining(Side::Black), secs(60));
+    }
+
+    #[test]
+    fn test_simple_delay() {
+        let (mut clock, time) = setup(TimeControl::simple_delay(secs(60), secs(5)));
+
+        time.advance(secs(3));
+        assert_eq!(clock.get_remaining(Side::White), secs(60));
+        time.advance(secs(7));
+        assert_eq!(clock.get_remaining(Side::White), secs(55));
+        assert!(clock.press());
+        assert_eq!(clock.get_remaining(Side::White), secs(55));
+    }
+
+    #[test]
+    fn test_stages() {
+        // 2 moves in 60 seconds, then 30 seconds with 10 seconds increment
+        let time_control = TimeControl::new(vec![
+            Stage {
+                moves: Some(2),
+                time: secs(60),
+                increment: Increment::None,
+            },
+            Stage {
+                moves: None,
+                time: secs(30),
+                increment: Increment::Fischer(secs(10)),
+            },
+        ]);
+        let (mut clock, time) = setup(time_control);
+
+        for _ in 0..4 {
+            time.advance(secs(10));
+            assert!(clock.press());
+        }
+        assert_eq!(clock.get_remaining(Side::White), secs(70));
+        assert_eq!(clock.get_stage(Side::White).unwrap().moves, None);
+
+        time.advance(secs(10));
+        assert!(clock.press());
+        assert_eq!(clock.get_remaining(Side::White), secs(70));
+    }
+
+    #[test]
+    fn test_repeating_stage() {
+        let time_control = TimeControl::new(vec![Stage {
+            moves: Some(1),
+            time: secs(60),
+            increment: Increment::None,
+        }]);
+        let (mut clock, time) = setup(time_control);
+
+        time.advance(secs(10));
+        assert!(clock.press());
+        assert_eq!(clock.get_remaining(Side::White), secs(110));
+    }
+
+    #[test]
+    fn test_stop() {
+        let (mut clock, time) = setup(TimeControl::sudden_death(secs(60)));
+
+        time.advance(secs(10));
+        clock.stop();
+        time.advance(secs(10));
+        assert_eq!(clock.get_remaining(Side::White), secs(50));
+
+        clock.start();
+        time.advance(secs(10));
+        assert_eq!(clock.get_remaining(Side::White), secs(40));
+
+        clock.set_turn(Side::Black);
+        time.advance(secs(10));
+        assert_eq!(clock.get_remaining(Side::White), secs(40));
+        assert_eq!(clock.get_remaining(Side::Black), secs(50));
+    }
+
+    #[test]
+    fn test_remaining_str() {
+        let (clock, time) = setup(TimeControl::sudden_death(secs(5400)));
+        assert_eq!(clock.get_remaining_str(Side::White), "1:30:00");
+
+        time.advance(secs(5400 - 299));
+        assert_eq!(clock.get_remaining_str(Side::White), "4:59");
+
+        time.advance(Duration::from_millis(289_500));
+        assert_eq!(clock.get_remaining_str(Side::White), "9.5");
+    }
+}
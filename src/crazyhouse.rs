@@ -0,0 +1,362 @@
+// Chess Crate
+use super::unit::{Side, Unit};
+
+/// Highest count of a [`Unit`] type a [`Pocket`] can hold, 8 Pawns and 8 demoted promoted units
+pub const POCKET_LIMIT: u8 = 16;
+
+//==================================================
+//=== Pocket
+//==================================================
+
+/// Captured [`Unit`]s a side can drop back onto the [`Board`](crate::chess::Board) in Crazyhouse
+///
+/// See [`Board::set_crazyhouse`](crate::chess::Board::set_crazyhouse)
+///
+/// * `counts` number of [`Unit`]s of each type, indexed by [`Unit::get_id`] % 6, the King never gets captured
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pocket {
+    counts: [u8; 5],
+}
+
+impl Pocket {
+    /// [`Unit`] types a [`Pocket`] can hold, from the most valuable one, as written in the FEN
+    pub const UNITS: [Unit; 5] = [
+        Unit::QUEEN,
+        Unit::ROOK,
+        Unit::BISHOP,
+        Unit::KNIGHT,
+        Unit::PAWN,
+    ];
+
+    /// Creates an empty [`Pocket`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives back the number of [`Unit`]s with the type of `unit`, the [`Side`] does not matter
+    pub fn get_count(&self, unit: &Unit) -> u8 {
+        pocket_idx(unit).map_or(0, |idx| self.counts[idx])
+    }
+
+    /// Puts a [`Unit`] with the type of `unit` into the [`Pocket`]
+    ///
+    /// Returns false for a King or when the [`Pocket`] is full, see [`POCKET_LIMIT`]
+    pub fn add(&mut self, unit: &Unit) -> bool {
+        match pocket_idx(unit) {
+            Some(idx) if self.counts[idx] < POCKET_LIMIT => {
+                self.counts[idx] += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Takes a [`Unit`] with the type of `unit` out of the [`Pocket`]
+    ///
+    /// Returns false when there is no such [`Unit`] in it
+    pub fn remove(&mut self, unit: &Unit) -> bool {
+        match pocket_idx(unit) {
+            Some(idx) if self.counts[idx] > 0 => {
+                self.counts[idx] -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true when there is no [`Unit`] in the [`Pocket`]
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|count| *count == 0)
+    }
+
+    /// Gives back the number of [`Unit`]s in the [`Pocket`]
+    pub fn len(&self) -> usize {
+        self.counts.iter().map(|count| *count as usize).sum()
+    }
+
+    /// Gives back the [`Unit`] types in the [`Pocket`] for the given `side`, once each, in the order of [`Pocket::UNITS`]
+    pub fn get_units(&self, side: Side) -> Vec<Unit> {
+        Self::UNITS
+            .iter()
+            .filter(|unit| self.get_count(unit) > 0)
+            .map(|unit| unit.set_side(side))
+            .collect()
+    }
+
+    /// Produces the FEN characters of the [`Unit`]s in the [`Pocket`] of `side`, E.g. "QNPP" for White
+    pub fn to_fen(&self, side: Side) -> String {
+        Self::UNITS
+            .iter()
+            .flat_map(|unit| {
+                let c = unit.set_side(side).get_fen_char();
+                std::iter::repeat_n(c, self.get_count(unit) as usize)
+            })
+            .collect()
+    }
+}
+
+/// Gives back the index of the `unit` type in the `counts`, `None` for the King
+fn pocket_idx(unit: &Unit) -> Option<usize> {
+    match unit {
+        Unit::King(..) => None,
+        unit => Some(unit.get_id() as usize % 6),
+    }
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_crazyhouse {
+    use super::*;
+    use crate::chess::Board;
+    use crate::engine::{Engine, Limit};
+    use crate::game::{EndReason, Game, GameState};
+    use crate::moves::{MoveError, MoveKind};
+    use crate::pos::Pos;
+    use crate::variant::Crazyhouse;
+
+    /// Plays the moves written in Standard Algebraic Notation
+    fn play(game: &mut Game, moves: &[&str]) {
+        for san in moves {
+            game.play_san(san).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_pocket() {
+        let mut pocket = Pocket::new();
+        assert!(pocket.is_empty());
+        assert!(!pocket.add(&Unit::KING));
+
+        assert!(pocket.add(&Unit::Knight(Side::White)));
+        assert!(pocket.add(&Unit::PAWN));
+        assert!(pocket.add(&Unit::PAWN));
+        assert!(pocket.add(&Unit::QUEEN));
+        assert_eq!(pocket.len(), 4);
+        assert_eq!(pocket.get_count(&Unit::Pawn(Side::White, true)), 2);
+        assert_eq!(pocket.to_fen(Side::White), "QNPP");
+        assert_eq!(
+            pocket.get_units(Side::Black),
+            vec![
+                Unit::Queen(Side::Black),
+                Unit::Knight(Side::Black),
+                Unit::Pawn(Side::Black, false)
+            ]
+        );
+
+        assert!(pocket.remove(&Unit::QUEEN));
+        assert!(!pocket.remove(&Unit::QUEEN));
+        assert_eq!(pocket.to_fen(Side::Black), "npp");
+    }
+
+    #[test]
+    fn test_capture_into_pocket() {
+        let mut game = Game::with_variant(Crazyhouse);
+        play(&mut game, &["e4", "d5", "exd5"]);
+
+        assert_eq!(
+            game.board_state
+                .get_pocket(&Side::White)
+                .to_fen(Side::White),
+            "P"
+        );
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/ppp1pppp/8/3P4/8/8/PPPP1PPP/RNBQKBNR[P] b KQkq - 0 2"
+        );
+
+        play(&mut game, &["Qxd5", "P@e4"]);
+        assert!(game.board_state.get_pocket(&Side::White).is_empty());
+        assert_eq!(
+            game.to_fen(),
+            "rnb1kbnr/ppp1pppp/8/3q4/4P3/8/PPPP1PPP/RNBQKBNR[p] b KQkq - 0 3"
+        );
+
+        // Taking back the drop puts the Pawn back into the pocket
+        assert!(game.undo());
+        assert_eq!(game.board_state.get_pocket(&Side::White).len(), 1);
+        assert_eq!(game.board_state.get_hash(), game.board_state.compute_hash());
+    }
+
+    #[test]
+    fn test_drops() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3[Pn] w - - 0 1").unwrap();
+        let moves = board.legal_moves(&Side::White);
+
+        // Pawns only between the second and the seventh row, and the steps of the King
+        assert_eq!(moves.iter().filter(|m| m.is_drop()).count(), 48);
+        assert_eq!(moves.len(), 53);
+        assert!(moves
+            .iter()
+            .filter(|chess_move| chess_move.is_drop())
+            .all(|chess_move| chess_move.target_pos.y != 0 && chess_move.target_pos.y != 7));
+
+        assert_eq!(
+            board.try_find_drop(&Unit::PAWN, &Pos::from_notation("E8")),
+            Err(MoveError::IllegalDrop(
+                Unit::Pawn(Side::White, false),
+                Pos::from_notation("E8")
+            ))
+        );
+        assert_eq!(
+            board.try_find_drop(&Unit::PAWN, &Pos::from_notation("E1")),
+            Err(MoveError::IllegalDrop(
+                Unit::Pawn(Side::White, false),
+                Pos::from_notation("E1")
+            ))
+        );
+        assert_eq!(
+            board.try_find_drop(&Unit::KNIGHT, &Pos::from_notation("E4")),
+            Err(MoveError::EmptyPocket(Unit::Knight(Side::White)))
+        );
+
+        // A Pawn dropped onto its starting row can take a 2 Step
+        let mut board = board;
+        let chess_move = board
+            .try_find_drop(&Unit::PAWN, &Pos::from_notation("D2"))
+            .unwrap();
+        assert_eq!(chess_move.kind, MoveKind::Drop);
+        assert!(board.make_move(&chess_move));
+        assert!(board.make_move(&board.parse_san("Kd7").unwrap()));
+        assert!(board.make_move(&board.parse_san("d4").unwrap()));
+    }
+
+    #[test]
+    fn test_drop_blocks_check() {
+        let fen = "k7/8/8/8/8/8/5PPP/r5K1[N] w - - 0 1";
+        let board = Board::from_fen(fen).unwrap();
+        let moves = board.legal_moves(&Side::White);
+
+        assert_eq!(moves.len(), 5);
+        assert!(moves.iter().all(|chess_move| chess_move.is_drop()
+            && chess_move.target_pos.y == 7
+            && chess_move.target_pos.x > 0));
+        assert_eq!(Game::from_board(board).get_game_state(), GameState::Playing);
+
+        // Without the Knight it is mate
+        let game = Game::from_fen("k7/8/8/8/8/8/5PPP/r5K1[] w - - 0 1").unwrap();
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::Black)));
+    }
+
+    #[test]
+    fn test_demotion() {
+        let mut game = Game::from_fen("r1r1k3/1P6/8/8/8/8/8/4K3[] w - - 0 1").unwrap();
+
+        play(&mut game, &["bxc8=Q+"]);
+        assert!(game.board_state.is_promoted(&Pos::from_notation("C8")));
+        assert_eq!(game.to_fen(), "r1Q~1k3/8/8/8/8/8/8/4K3[R] b - - 0 1");
+
+        // The promoted Queen goes into the pocket as a Pawn
+        play(&mut game, &["Rxc8"]);
+        assert!(!game.board_state.is_promoted(&Pos::from_notation("C8")));
+        assert_eq!(game.to_fen(), "2r1k3/8/8/8/8/8/8/4K3[Rp] w - - 0 2");
+
+        assert!(game.undo());
+        assert_eq!(game.to_fen(), "r1Q~1k3/8/8/8/8/8/8/4K3[R] b - - 0 1");
+        assert!(game.undo());
+        assert_eq!(
+            game.board_state,
+            Board::from_fen("r1r1k3/1P6/8/8/8/8/8/4K3[] w - - 0 1").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fen() {
+        for fen in [
+            "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR[] w KQkq - 4 4",
+            "2r1k3/8/8/3Q~4/8/8/8/4K3[RBNPPqnp] w - - 0 30",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+            assert!(board.is_crazyhouse());
+            assert_eq!(board.to_fen(), fen);
+        }
+
+        // Pockets in a 9th row
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3/Nq w - - 0 1").unwrap();
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3[Nq] w - - 0 1");
+        assert_eq!(board.get_pocket(&Side::Black).get_count(&Unit::QUEEN), 1);
+
+        assert!(Board::from_fen("4k3/8/8/8/8/8/8/4K3[K] w - - 0 1").is_err());
+        assert!(Board::from_fen("4k3/8/8/8/8/8/8/4K3[x] w - - 0 1").is_err());
+        assert!(Board::from_fen("4k3/8/8/8/8/8/8/~4K3[] w - - 0 1").is_err());
+        assert!(!Board::from_fen(crate::fen::START_FEN)
+            .unwrap()
+            .is_crazyhouse());
+    }
+
+    #[test]
+    fn test_notation() {
+        let board = Board::from_fen("6rk/6pp/8/8/8/8/8/K7[NP] w - - 0 1").unwrap();
+
+        let chess_move = board.parse_san("N@f7").unwrap();
+        assert!(chess_move.is_drop());
+        assert_eq!(chess_move.to_san(&board), "N@f7#");
+        assert_eq!(chess_move.to_lan(), "N@f7");
+        assert_eq!(board.parse_lan("N@f7"), Ok(chess_move));
+
+        let chess_move = board.parse_san("@e4").unwrap();
+        assert_eq!(chess_move, board.parse_san("P@e4").unwrap());
+        assert_eq!(chess_move.to_san(&board), "P@e4");
+
+        assert!(board.parse_san("P@e8").is_err());
+        assert!(board.parse_san("Q@e4").is_err());
+        assert!(board.parse_san("K@e4").is_err());
+    }
+
+    #[test]
+    fn test_hash() {
+        let board = Board::from_fen(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR[Bp] w KQkq - 4 4",
+        )
+        .unwrap();
+        let empty = Board::from_fen(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR[] w KQkq - 4 4",
+        )
+        .unwrap();
+        assert_ne!(board.get_hash(), empty.get_hash());
+
+        for chess_move in board.legal_moves(&Side::White) {
+            let mut board_state = board.clone();
+            let move_image = board_state.make_move_image(&chess_move).unwrap();
+            assert_eq!(board_state.get_hash(), board_state.compute_hash());
+
+            board_state.unmake_move(&move_image);
+            assert_eq!(board_state, board);
+        }
+    }
+
+    #[test]
+    fn test_game() {
+        let mut game = Game::with_variant(Crazyhouse);
+        assert!(game.board_state.is_crazyhouse());
+        assert_eq!(
+            game.make_drop(&Unit::PAWN, &Pos::from_notation("E4")),
+            Err(MoveError::EmptyPocket(Unit::Pawn(Side::White, false)))
+        );
+
+        let mut game = Game::from_fen("6rk/6pp/8/8/8/8/8/K7[N] w - - 0 1").unwrap();
+        assert!(!game.board_state.test_insufficient_material());
+
+        let outcome = game
+            .make_drop(&Unit::KNIGHT, &Pos::from_notation("F7"))
+            .unwrap();
+        assert!(outcome.check);
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+        assert_eq!(game.get_end_reason(), Some(EndReason::Checkmate));
+        assert_eq!(
+            game.make_drop(&Unit::KNIGHT, &Pos::from_notation("F6")),
+            Err(MoveError::GameOver)
+        );
+    }
+
+    #[test]
+    fn test_engine_drops() {
+        let game = Game::from_fen("6rk/6pp/8/8/8/8/8/K7[N] w - - 0 1").unwrap();
+        let result = Engine::new().search(&game, Limit::depth(2)).unwrap();
+
+        assert!(result.best_move.is_drop());
+        assert_eq!(result.best_move.target_pos, Pos::from_notation("F7"));
+    }
+}
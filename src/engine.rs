@@ -0,0 +1,831 @@
+// Standard Crate
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Chess Crate
+use super::chess::{Board, MoveImage};
+use super::eval::EvalParams;
+use super::game::{Game, GameState};
+use super::moves::Move;
+use super::unit::Unit;
+use super::variant::{Standard, Variant};
+
+/// Score of a checkmate on the board, a mate `n` plies away scores `MATE_SCORE - n`
+pub const MATE_SCORE: i32 = 100_000;
+
+/// Deepest ply the search reaches, including Check extensions and captures
+const MAX_PLY: usize = 64;
+
+/// Scores above this are checkmates
+const MATE_BOUND: i32 = MATE_SCORE - MAX_PLY as i32;
+
+/// Bound of the search window, above any score
+const INFINITY: i32 = MATE_SCORE + 1;
+
+/// Default size of the transposition table in megabytes
+pub const DEFAULT_HASH_SIZE: usize = 4;
+
+/// Number of moves left until the next time control, when it is unknown
+const MOVES_TO_GO: u32 = 30;
+
+/// Time kept on the clock for the communication with a GUI
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+
+/// Number of searched positions between two checks of the time limit
+const TIME_CHECK_NODES: u64 = 1024;
+
+/// Value of the [`Unit`] types in centipawns for the move ordering, indexed by [`Unit::get_id`] % 6
+const UNIT_VALUES: [i32; 6] = [100, 330, 320, 500, 900, 0];
+
+//==================================================
+//=== Engine
+//==================================================
+
+/// Computer opponent searching the best [`Move`] of a [`Game`]
+///
+/// Iterative deepening alpha-beta search with a transposition table, move ordering and quiescence search
+///
+/// * `params` weights of the evaluation, see [`Board::evaluate_with`]
+/// * `table` transposition table, indexed by the Zobrist hash of the position, see [`Board::get_hash`]
+/// * `killers` quiet [`Move`]s which caused a cutoff, 2 for each ply
+/// * `path` keys of the positions from the start of the game to the searched one, for repetitions
+/// * `variant` rules of the searched [`Game`], see [`Variant`]
+/// * `history` [`MoveImage`]s from the start of the game to the searched position, for the [`Variant`] endings
+/// * `nodes` number of positions searched
+/// * `deadline` time when the search stops
+/// * `node_limit` number of positions when the search stops
+/// * `stop` set from another thread to stop the search, see [get_stop_handle](#method.get_stop_handle)
+/// * `interruptible` the first iteration is done, the search can stop
+/// * `stopped` the search ran out of time or got stopped, the current iteration is thrown away
+pub struct Engine {
+    params: EvalParams,
+    table: Vec<Option<Entry>>,
+    killers: [[Option<Move>; 2]; MAX_PLY],
+    path: Vec<u64>,
+    variant: Arc<dyn Variant>,
+    history: Vec<MoveImage>,
+    nodes: u64,
+    deadline: Option<Instant>,
+    node_limit: Option<u64>,
+    stop: Arc<AtomicBool>,
+    interruptible: bool,
+    stopped: bool,
+}
+
+impl Engine {
+    /// Creates a new [`Engine`] with an empty transposition table
+    pub fn new() -> Self {
+        let mut engine = Self {
+            params: EvalParams::default(),
+            table: Vec::new(),
+            killers: [[None; 2]; MAX_PLY],
+            path: Vec::new(),
+            variant: Arc::new(Standard),
+            history: Vec::new(),
+            nodes: 0,
+            deadline: None,
+            node_limit: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            interruptible: false,
+            stopped: false,
+        };
+
+        engine.set_hash_size(DEFAULT_HASH_SIZE);
+        engine
+    }
+
+    /// Resizes the transposition table to at most `megabytes`, forgets the searched positions
+    pub fn set_hash_size(&mut self, megabytes: usize) {
+        let entries = (megabytes * 1024 * 1024 / mem::size_of::<Option<Entry>>()).max(1);
+
+        // The index of an entry is the lowest bits of the hash
+        self.table = vec![None; 1 << entries.ilog2()];
+    }
+
+    /// Gives back the flag which stops the running search, E.g. from another thread
+    ///
+    /// The search keeps the best [`Move`] of the last complete iteration, the flag stays set until cleared
+    pub fn get_stop_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    /// Gives back the weights of the evaluation
+    pub fn get_params(&self) -> &EvalParams {
+        &self.params
+    }
+
+    /// Sets the weights of the evaluation, the searched positions are forgotten
+    pub fn set_params(&mut self, params: EvalParams) {
+        self.params = params;
+        self.clear();
+    }
+
+    /// Forgets the searched positions, E.g. before a new game
+    pub fn clear(&mut self) {
+        self.table.fill(None);
+    }
+
+    /// Searches the best [`Move`] of the side in the current turn
+    ///
+    /// Deepens the search one ply at a time, until it reaches the depth, the time or the nodes of the `limit`,
+    /// only complete iterations count, except the first one
+    ///
+    /// Returns `None` when the game is over
+    pub fn search(&mut self, game: &Game, limit: Limit) -> Option<SearchResult> {
+        self.search_with(game, limit, |_| ())
+    }
+
+    /// Searches like [search](#method.search), calls `report` with the result of each complete iteration
+    pub fn search_with<F>(
+        &mut self,
+        game: &Game,
+        limit: Limit,
+        mut report: F,
+    ) -> Option<SearchResult>
+    where
+        F: FnMut(&SearchResult),
+    {
+        if game.get_game_state() != GameState::Playing {
+            return None;
+        }
+
+        self.variant = game.share_variant();
+        self.history = game.history().to_vec();
+
+        let mut board = game.board_state.clone();
+        let moves = self.legal_moves(&board);
+        let first_move = *moves.first()?;
+
+        let keys = game.get_position_keys();
+        self.path = keys[..keys.len() - 1].to_vec();
+        self.killers = [[None; 2]; MAX_PLY];
+        self.nodes = 0;
+        self.interruptible = false;
+        self.stopped = false;
+
+        let mut result = SearchResult {
+            best_move: first_move,
+            score: 0,
+            depth: 0,
+            pv: vec![first_move],
+            nodes: 0,
+        };
+
+        self.deadline = limit.time.map(|time| Instant::now() + time);
+        self.node_limit = limit.nodes;
+
+        for depth in 1..=limit.depth.clamp(1, MAX_PLY as u32 - 1) {
+            let mut pv = Vec::new();
+            let score = self.alpha_beta(&mut board, depth as i32, 0, -INFINITY, INFINITY, &mut pv);
+
+            if self.stopped {
+                break;
+            }
+
+            self.extend_pv(&mut board, &mut pv, depth);
+
+            result.best_move = pv.first().copied().unwrap_or(first_move);
+            result.score = score;
+            result.depth = depth;
+            result.pv = pv;
+            result.nodes = self.nodes;
+            report(&result);
+
+            // The first iteration always completes, so there is a move to play
+            self.interruptible = true;
+
+            // A checkmate found within the depth can't get any closer
+            let mate_found = score.abs() > MATE_BOUND && MATE_SCORE - score.abs() <= depth as i32;
+
+            if mate_found || self.test_time_out() || self.test_node_limit() {
+                break;
+            }
+        }
+
+        result.nodes = self.nodes;
+        Some(result)
+    }
+
+    /// Follows the best [`Move`]s of the transposition table after the end of `pv`, up to `depth` [`Move`]s
+    ///
+    /// The `pv` ends early where the search took the score from the table
+    fn extend_pv(&mut self, board: &mut Board, pv: &mut Vec<Move>, depth: u32) {
+        for chess_move in pv.iter() {
+            self.push_move(board, chess_move);
+        }
+
+        while pv.len() < depth as usize && self.test_variant_ending(board, 0).is_none() {
+            let chess_move = match self
+                .probe(board.get_hash())
+                .and_then(|entry| entry.best_move)
+            {
+                Some(chess_move) => chess_move,
+                None => break,
+            };
+
+            if !self.legal_moves(board).contains(&chess_move) {
+                break;
+            }
+
+            self.push_move(board, &chess_move);
+            pv.push(chess_move);
+        }
+
+        for _ in 0..pv.len() {
+            self.pop_move(board);
+        }
+    }
+
+    /// Searches the position `depth` plies deep within the `alpha` - `beta` window
+    ///
+    /// Gives back the score for the side in the current turn and fills `pv` with the best line
+    fn alpha_beta(
+        &mut self,
+        board: &mut Board,
+        depth: i32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        pv.clear();
+
+        if self.test_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        let hash = board.get_hash();
+        if ply > 0 {
+            if let Some(score) = self.test_variant_ending(board, ply) {
+                return score;
+            }
+
+            if self.test_draw(board, hash) {
+                return 0;
+            }
+        }
+
+        let side = board.get_current_turn();
+        let check = board.test_checked_status(&side);
+
+        // Check Extension
+        let depth = if check { depth + 1 } else { depth };
+
+        if depth <= 0 || ply >= MAX_PLY - 1 {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+
+        let mut table_move = None;
+        if let Some(entry) = self.probe(hash) {
+            table_move = entry.best_move;
+
+            if ply > 0 && entry.depth >= depth {
+                let score = score_from_table(entry.score, ply);
+                match entry.bound {
+                    Bound::Exact => return score,
+                    Bound::Lower if score >= beta => return score,
+                    Bound::Upper if score <= alpha => return score,
+                    _ => (),
+                }
+            }
+        }
+
+        let mut moves = self.legal_moves(board);
+        if moves.is_empty() {
+            return if check { ply as i32 - MATE_SCORE } else { 0 };
+        }
+        self.order_moves(&mut moves, table_move, ply);
+
+        let start_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        let mut child_pv = Vec::new();
+
+        self.path.push(hash);
+        for chess_move in moves {
+            self.push_move(board, &chess_move);
+            let score = -self.alpha_beta(board, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            self.pop_move(board);
+
+            if self.stopped {
+                self.path.pop();
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(chess_move);
+            }
+
+            if score > alpha {
+                alpha = score;
+
+                pv.clear();
+                pv.push(chess_move);
+                pv.extend_from_slice(&child_pv);
+            }
+
+            if alpha >= beta {
+                if !chess_move.is_capture() && !chess_move.is_promotion() {
+                    self.store_killer(chess_move, ply);
+                }
+                break;
+            }
+        }
+        self.path.pop();
+
+        let bound = if best_score >= beta {
+            Bound::Lower
+        } else if best_score > start_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        self.store(
+            hash,
+            depth,
+            score_to_table(best_score, ply),
+            bound,
+            best_move,
+        );
+
+        best_score
+    }
+
+    /// Searches only the captures and promotions until the position is quiet
+    ///
+    /// In Check every [`Move`] counts, otherwise the side can stand pat with the evaluation
+    fn quiescence(&mut self, board: &mut Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        if self.test_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        if let Some(score) = self.test_variant_ending(board, ply) {
+            return score;
+        }
+
+        let side = board.get_current_turn();
+        let check = board.test_checked_status(&side);
+
+        let mut moves = self.legal_moves(board);
+        if moves.is_empty() {
+            return if check { ply as i32 - MATE_SCORE } else { 0 };
+        }
+
+        if ply >= MAX_PLY - 1 {
+            return self.evaluate(board);
+        }
+
+        if !check {
+            let stand_pat = self.evaluate(board);
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+
+            moves.retain(|chess_move| chess_move.is_capture() || chess_move.is_promotion());
+        }
+        self.order_moves(&mut moves, None, ply);
+
+        for chess_move in moves {
+            self.push_move(board, &chess_move);
+            let score = -self.quiescence(board, ply + 1, -beta, -alpha);
+            self.pop_move(board);
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+
+    /// Gives back the legal [`Move`]s of the side in the current turn allowed by the [`Variant`]
+    fn legal_moves(&self, board: &Board) -> Vec<Move> {
+        let mut moves = board.legal_moves(&board.get_current_turn());
+        moves.retain(|chess_move| self.variant.test_move(board, chess_move));
+
+        moves
+    }
+
+    /// Plays the [`Move`] on the `board` and keeps its [`MoveImage`] in the `history`
+    fn push_move(&mut self, board: &mut Board, chess_move: &Move) {
+        self.history.push(board.apply_move(chess_move));
+    }
+
+    /// Takes back the last [`Move`] played by [push_move](#method.push_move)
+    fn pop_move(&mut self, board: &mut Board) {
+        if let Some(move_image) = self.history.pop() {
+            board.unmake_move(&move_image);
+        }
+    }
+
+    /// Gives back the score for the side in the current turn when the [`Variant`] ends the game, `ply` plies away
+    fn test_variant_ending(&self, board: &Board, ply: usize) -> Option<i32> {
+        let outcome = self.variant.test_ending(board, &self.history)?;
+
+        Some(match outcome.winner {
+            Some(winner) if winner == board.get_current_turn() => MATE_SCORE - ply as i32,
+            Some(_) => ply as i32 - MATE_SCORE,
+            None => 0,
+        })
+    }
+
+    /// Evaluates the position for the side in the current turn
+    fn evaluate(&self, board: &Board) -> i32 {
+        board
+            .evaluate_with(&self.params)
+            .for_side(&board.get_current_turn())
+    }
+
+    /// Sorts the [`Move`]s, the most promising comes first
+    ///
+    /// Table move -> Captures by MVV-LVA -> Promotions -> Killer moves -> Other moves
+    fn order_moves(&self, moves: &mut [Move], table_move: Option<Move>, ply: usize) {
+        let killers = self.killers[ply];
+
+        moves.sort_by_cached_key(|chess_move| {
+            let score = if Some(*chess_move) == table_move {
+                1_000_000
+            } else if let Some(captured) = chess_move.captured {
+                100_000 + 10 * unit_value(&captured) - unit_value(&chess_move.unit)
+            } else if let Some(promotion) = chess_move.promotion {
+                90_000 + unit_value(&promotion)
+            } else if Some(*chess_move) == killers[0] {
+                80_000
+            } else if Some(*chess_move) == killers[1] {
+                70_000
+            } else {
+                0
+            };
+
+            -score
+        });
+    }
+
+    /// Keeps the quiet [`Move`] which caused a cutoff at `ply`, the older one moves to the second place
+    fn store_killer(&mut self, chess_move: Move, ply: usize) {
+        let killers = &mut self.killers[ply];
+
+        if killers[0] != Some(chess_move) {
+            killers[1] = killers[0];
+            killers[0] = Some(chess_move);
+        }
+    }
+
+    /// Returns true when the position is a draw by the fifty-move rule or it appeared before
+    fn test_draw(&self, board: &Board, hash: u64) -> bool {
+        let halfmove_clock = board.get_halfmove_clock() as usize;
+        if halfmove_clock >= 100 {
+            return true;
+        }
+
+        // Positions before the last capture or Pawn step can't repeat
+        let start = self.path.len().saturating_sub(halfmove_clock);
+        self.path[start..].contains(&hash)
+    }
+
+    /// Returns true when the search ran out of time or nodes or got stopped, checks the time every few positions
+    fn test_stop(&mut self) -> bool {
+        if !self.stopped && self.interruptible {
+            self.stopped = self.test_node_limit()
+                || (self.nodes.is_multiple_of(TIME_CHECK_NODES) && self.test_time_out());
+        }
+
+        self.stopped
+    }
+
+    /// Returns true when the search reached the number of positions of its [`Limit`]
+    fn test_node_limit(&self) -> bool {
+        self.node_limit.is_some_and(|nodes| self.nodes >= nodes)
+    }
+
+    /// Returns true when the time of the search is over or the stop flag is set
+    fn test_time_out(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Gives back the [`Entry`] of the position with the Zobrist `hash`
+    fn probe(&self, hash: u64) -> Option<Entry> {
+        self.table[hash as usize & (self.table.len() - 1)].filter(|entry| entry.hash == hash)
+    }
+
+    /// Stores the result of a search in the transposition table, replaces the former entry
+    fn store(&mut self, hash: u64, depth: i32, score: i32, bound: Bound, best_move: Option<Move>) {
+        let idx = hash as usize & (self.table.len() - 1);
+        self.table[idx] = Some(Entry {
+            hash,
+            depth,
+            score,
+            bound,
+            best_move,
+        });
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//==================================================
+//=== Limit
+//==================================================
+
+/// How long the [`Engine`] searches
+///
+/// * `depth` maximum number of plies
+/// * `time` maximum time, the first ply is searched in any case
+/// * `nodes` maximum number of positions searched, the first ply is searched in any case
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Limit {
+    pub depth: u32,
+    pub time: Option<Duration>,
+    pub nodes: Option<u64>,
+}
+
+impl Limit {
+    /// Searches `depth` plies deep
+    pub fn depth(depth: u32) -> Self {
+        Self {
+            depth,
+            time: None,
+            nodes: None,
+        }
+    }
+
+    /// Searches as deep as possible within `time`
+    pub fn time(time: Duration) -> Self {
+        Self {
+            depth: MAX_PLY as u32,
+            time: Some(time),
+            nodes: None,
+        }
+    }
+
+    /// Searches as deep as possible within `nodes` positions
+    pub fn nodes(nodes: u64) -> Self {
+        Self {
+            depth: MAX_PLY as u32,
+            time: None,
+            nodes: Some(nodes),
+        }
+    }
+
+    /// Searches a share of the `time` left on the clock, the moves until the next time control share it
+    ///
+    /// Spends most of the `increment` too, but never more than the clock holds
+    pub fn clock(time: Duration, increment: Duration, moves_to_go: Option<u32>) -> Self {
+        let budget = time / moves_to_go.unwrap_or(MOVES_TO_GO).max(1) + increment * 3 / 4;
+        let left = time
+            .saturating_sub(MOVE_OVERHEAD)
+            .max(Duration::from_millis(1));
+
+        Self::time(budget.min(left))
+    }
+}
+
+//==================================================
+//=== Search Result
+//==================================================
+
+/// The result of [`Engine::search`]
+///
+/// * `best_move` the [`Move`] to play
+/// * `score` score in centipawns for the side in the current turn, see [get_mate](#method.get_mate)
+/// * `depth` number of plies of the last complete iteration
+/// * `pv` principal variation, the expected line of play starting with `best_move`
+/// * `nodes` number of positions searched
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    pub best_move: Move,
+    pub score: i32,
+    pub depth: u32,
+    pub pv: Vec<Move>,
+    pub nodes: u64,
+}
+
+impl SearchResult {
+    /// Gives back the number of moves until checkmate, negative when the side in the current turn gets mated
+    ///
+    /// Returns `None` when the `score` is not a checkmate
+    pub fn get_mate(&self) -> Option<i32> {
+        if self.score > MATE_BOUND {
+            Some((MATE_SCORE - self.score + 1) / 2)
+        } else if self.score < -MATE_BOUND {
+            Some(-(MATE_SCORE + self.score) / 2)
+        } else {
+            None
+        }
+    }
+}
+
+//==================================================
+//=== Transposition Table
+//==================================================
+
+/// * `Exact` the score is exact
+/// * `Lower` the score is at least this, the search was cut off
+/// * `Upper` the score is at most this, no [`Move`] reached alpha
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// * `hash` full Zobrist hash of the position, tells apart positions on the same index
+/// * `depth` remaining depth of the search
+/// * `score` result of the search, mates counted from the position
+/// * `bound` how the score relates to the real one, see [`Bound`]
+/// * `best_move` best [`Move`] found, searched first next time
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    hash: u64,
+    depth: i32,
+    score: i32,
+    bound: Bound,
+    best_move: Option<Move>,
+}
+
+/// Converts mate scores from the root to the position at `ply`, the same mate scores the same everywhere
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    if score > MATE_BOUND {
+        score + ply as i32
+    } else if score < -MATE_BOUND {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+/// Converts mate scores from the position at `ply` back to the root
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    if score > MATE_BOUND {
+        score - ply as i32
+    } else if score < -MATE_BOUND {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+//==================================================
+//=== Move Ordering
+//==================================================
+
+/// Gives back the value of the [`Unit`] type in centipawns
+fn unit_value(unit: &Unit) -> i32 {
+    UNIT_VALUES[unit.get_id() as usize % 6]
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_engine {
+    use super::*;
+    use crate::pos::Pos;
+    use crate::unit::Side;
+
+    fn search_fen(fen: &str, limit: Limit) -> Option<SearchResult> {
+        let game = Game::from_board(Board::from_fen(fen).unwrap());
+        Engine::new().search(&game, limit)
+    }
+
+    #[test]
+    fn test_mate_in_one() {
+        let result = search_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", Limit::depth(3)).unwrap();
+
+        assert_eq!(result.best_move.target_pos, Pos::from_notation("A8"));
+        assert_eq!(result.get_mate(), Some(1));
+        assert_eq!(result.pv, vec![result.best_move]);
+    }
+
+    #[test]
+    fn test_mate_in_two() {
+        // 1. Qd8+ Rxd8 2. Rxd8#
+        let result =
+            search_fen("2r3k1/5ppp/8/8/8/8/3Q1PPP/3R2K1 w - - 0 1", Limit::depth(5)).unwrap();
+
+        assert_eq!(result.get_mate(), Some(2));
+        assert_eq!(result.pv.len(), 3);
+    }
+
+    #[test]
+    fn test_capture() {
+        // The Queen hangs to the Pawn on E4
+        let result = search_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1", Limit::depth(2)).unwrap();
+
+        assert_eq!(result.best_move.target_pos, Pos::from_notation("D5"));
+        assert!(result.score > 0);
+    }
+
+    #[test]
+    fn test_quiescence() {
+        // Taking the Pawn on D5 loses the Queen to the Pawn on E6
+        let result = search_fen("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1", Limit::depth(1)).unwrap();
+        assert_ne!(result.best_move.target_pos, Pos::from_notation("D5"));
+    }
+
+    #[test]
+    fn test_pv_legal() {
+        let mut game = Game::new();
+        let result = Engine::new().search(&game, Limit::depth(4)).unwrap();
+
+        assert_eq!(result.depth, 4);
+        assert_eq!(result.pv[0], result.best_move);
+        for chess_move in &result.pv {
+            assert!(game.play_move(chess_move));
+        }
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let start = Instant::now();
+        let result = Engine::new()
+            .search(&Game::new(), Limit::time(Duration::from_millis(100)))
+            .unwrap();
+
+        assert!(result.depth >= 1);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_node_limit() {
+        let result = Engine::new()
+            .search(&Game::new(), Limit::nodes(5000))
+            .unwrap();
+
+        assert!(result.depth >= 1);
+        assert!(result.nodes <= 5000);
+    }
+
+    #[test]
+    fn test_game_over() {
+        let mut game = Game::new();
+        game.resign(Side::White);
+
+        assert_eq!(Engine::new().search(&game, Limit::depth(1)), None);
+    }
+
+    #[test]
+    fn test_stop_handle() {
+        let mut engine = Engine::new();
+        engine.get_stop_handle().store(true, Ordering::Relaxed);
+
+        // The first iteration completes anyway
+        let result = engine.search(&Game::new(), Limit::depth(10)).unwrap();
+        assert_eq!(result.depth, 1);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut depths = Vec::new();
+        let result = Engine::new()
+            .search_with(&Game::new(), Limit::depth(3), |result| {
+                depths.push(result.depth)
+            })
+            .unwrap();
+
+        assert_eq!(depths, vec![1, 2, 3]);
+        assert_eq!(result.depth, 3);
+    }
+
+    #[test]
+    fn test_hash_size() {
+        let mut engine = Engine::new();
+        engine.set_hash_size(1);
+
+        assert!(engine.table.len().is_power_of_two());
+        assert!(engine.table.len() * mem::size_of::<Option<Entry>>() <= 1024 * 1024);
+        assert!(engine.search(&Game::new(), Limit::depth(3)).is_some());
+    }
+
+    #[test]
+    fn test_clock_limit() {
+        let limit = Limit::clock(Duration::from_secs(60), Duration::ZERO, Some(20));
+        assert_eq!(limit.time, Some(Duration::from_secs(3)));
+
+        let limit = Limit::clock(Duration::from_secs(30), Duration::from_secs(2), None);
+        assert_eq!(limit.time, Some(Duration::from_millis(2500)));
+
+        // The increment can't be spent before it arrives
+        let limit = Limit::clock(Duration::from_millis(100), Duration::from_secs(2), None);
+        assert_eq!(limit.time, Some(Duration::from_millis(50)));
+    }
+}
@@ -0,0 +1,1001 @@
+// Standard Crate
+use std::{error, fmt, fs, ops, path::Path};
+
+// Chess Crate
+use super::bitboard::{self, Bitboard};
+use super::chess::Board;
+use super::pos::Pos;
+use super::unit::{Side, Unit};
+
+/// Phase of the starting material, the middlegame weights count fully at this phase
+const MAX_PHASE: i32 = 24;
+
+/// Phase of each [`Unit`] type, indexed by [`Unit::get_id`] % 6
+const UNIT_PHASES: [i32; 6] = [0, 1, 1, 2, 4, 0];
+
+/// [`Unit`] types in the order of [`Unit::get_id`]
+const UNIT_TYPES: [Unit; 6] = [
+    Unit::PAWN,
+    Unit::BISHOP,
+    Unit::KNIGHT,
+    Unit::ROOK,
+    Unit::QUEEN,
+    Unit::KING,
+];
+
+/// Names of the [`Unit`] types in the parameter file
+const UNIT_NAMES: [&str; 6] = ["pawn", "bishop", "knight", "rook", "queen", "king"];
+
+/// Squares of the A column
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+
+//==================================================
+//=== Evaluation
+//==================================================
+
+impl Board {
+    /// Evaluates the position with the default [`EvalParams`], see [evaluate_with](#method.evaluate_with)
+    pub fn evaluate(&self) -> Score {
+        self.evaluate_with(&EvalParams::default())
+    }
+
+    /// Evaluates the position without searching, from the side of White
+    ///
+    /// The same position always gets the same [`Score`], whichever side is in the current turn
+    pub fn evaluate_with(&self, params: &EvalParams) -> Score {
+        let (terms, phase) = self.evaluate_terms(params);
+
+        terms.iter().fold(Score(0), |score, [black, white]| {
+            score + Score(white.taper(phase) - black.taper(phase))
+        })
+    }
+
+    /// Evaluates the position like [evaluate_with](#method.evaluate_with), gives back the [`Score`] of each [`Term`]
+    pub fn get_evaluation(&self, params: &EvalParams) -> Evaluation {
+        let (terms, phase) = self.evaluate_terms(params);
+
+        let terms: Vec<_> = Term::ALL
+            .iter()
+            .zip(terms)
+            .map(|(term, [black, white])| TermScore {
+                term: *term,
+                white: Score(white.taper(phase)),
+                black: Score(black.taper(phase)),
+            })
+            .collect();
+
+        let score = terms
+            .iter()
+            .fold(Score(0), |score, term_score| score + term_score.get_total());
+
+        Evaluation {
+            terms,
+            phase,
+            score,
+        }
+    }
+
+    /// Gives back the [`Weight`] of each [`Term`] for Black and White and the phase of the game
+    fn evaluate_terms(&self, params: &EvalParams) -> ([[Weight; 2]; 5], i32) {
+        let bitboards = self.get_bitboards();
+        let occupied = bitboards.get_occupied();
+
+        let mut terms = [[Weight::default(); 2]; 5];
+        let mut phase = 0;
+
+        for (side_idx, side) in [Side::Black, Side::White].iter().enumerate() {
+            let [material, squares, mobility, king_safety, pawn_structure] = &mut terms;
+            let own = bitboards.get_side(side);
+
+            // Material + Piece-Square + Mobility
+            for (idx, unit_type) in UNIT_TYPES.iter().enumerate() {
+                for pos in bitboards.get_units(unit_type, side) {
+                    material[side_idx] += params.unit_values[idx];
+                    squares[side_idx] += params.square_values[idx][relative_square(&pos, side)];
+                    phase += UNIT_PHASES[idx];
+
+                    if let Some(unit) = self.get_unit(&pos) {
+                        let targets = bitboard::attacks(&unit, &pos, occupied) & !own;
+                        mobility[side_idx] += params.mobility[idx] * targets.count() as i32;
+                    }
+                }
+            }
+
+            // Pockets, the Crazyhouse drops count as Material
+            let pocket = self.get_pocket(side);
+            for (idx, unit_type) in UNIT_TYPES.iter().enumerate() {
+                let count = pocket.get_count(unit_type) as i32;
+                material[side_idx] += params.unit_values[idx] * count;
+                phase += UNIT_PHASES[idx] * count;
+            }
+
+            // King Safety
+            let king = bitboards.get_units(&Unit::KING, side);
+            for king_pos in king {
+                let shield = king_front(&king_pos, side) & bitboards.get_units(&Unit::PAWN, side);
+                king_safety[side_idx] += params.king_shield * shield.count() as i32;
+
+                let attacked = bitboard::attacks(&Unit::KING, &king_pos, occupied)
+                    .filter(|pos| bitboards.test_attacked(pos, &side.oppose()))
+                    .count();
+                king_safety[side_idx] += params.king_attack * attacked as i32;
+            }
+
+            // Pawn Structure
+            let pawns = bitboards.get_units(&Unit::PAWN, side);
+            let enemy_pawns = bitboards.get_units(&Unit::PAWN, &side.oppose());
+            for x in 0..8 {
+                let count = (pawns & Bitboard(FILE_A << x)).count() as i32;
+                if count > 1 {
+                    pawn_structure[side_idx] += params.doubled_pawn * (count - 1);
+                }
+            }
+
+            for pos in pawns {
+                if (pawns & Bitboard(neighbour_files(pos.x))).is_empty() {
+                    pawn_structure[side_idx] += params.isolated_pawn;
+                }
+
+                let files = Bitboard((FILE_A << pos.x) | neighbour_files(pos.x));
+                if (enemy_pawns & files & front_span(&pos, side)).is_empty() {
+                    let rank = 7 - relative_square(&pos, side) / 8;
+                    pawn_structure[side_idx] += params.passed_pawn[rank];
+                }
+            }
+        }
+
+        (terms, phase.min(MAX_PHASE))
+    }
+}
+
+/// Gives back the index of `pos` in the tables of the [`EvalParams`], Black reads them mirrored
+fn relative_square(pos: &Pos, side: &Side) -> usize {
+    let y = match side {
+        Side::Black => 7 - pos.y,
+        Side::White => pos.y,
+    };
+
+    (y * 8 + pos.x) as usize
+}
+
+/// Gives back the squares of the columns next to column `x`
+fn neighbour_files(x: i8) -> u64 {
+    let left = if x > 0 { FILE_A << (x - 1) } else { 0 };
+    let right = if x < 7 { FILE_A << (x + 1) } else { 0 };
+
+    left | right
+}
+
+/// Gives back the rows in front of `pos` from the side of `side`
+fn front_span(pos: &Pos, side: &Side) -> Bitboard {
+    let row = pos.y as u32 * 8;
+
+    match side {
+        Side::Black => Bitboard(u64::MAX.checked_shl(row + 8).unwrap_or(0)),
+        Side::White => Bitboard((1u64 << row) - 1),
+    }
+}
+
+/// Gives back the 3 squares right in front of the King at `pos`
+fn king_front(pos: &Pos, side: &Side) -> Bitboard {
+    let y = match side {
+        Side::Black => pos.y + 1,
+        Side::White => pos.y - 1,
+    };
+
+    (pos.x - 1..=pos.x + 1)
+        .map(|x| Pos::new(x, y))
+        .filter(|pos| pos.is_onboard())
+        .fold(Bitboard::EMPTY, |front, pos| {
+            front | Bitboard::from_pos(&pos)
+        })
+}
+
+//==================================================
+//=== Score
+//==================================================
+
+/// Evaluation in centipawns from the side of White, positive when White stands better
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Score(pub i32);
+
+impl Score {
+    /// Gives back the centipawns from the side of `side`
+    pub fn for_side(&self, side: &Side) -> i32 {
+        match side {
+            Side::Black => -self.0,
+            Side::White => self.0,
+        }
+    }
+}
+
+impl ops::Add for Score {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl ops::Sub for Score {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl ops::Neg for Score {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+/// Pawn units with sign, E.g. "+1.25", "-0.05", "0.00"
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self.0 {
+            0 => "",
+            score if score > 0 => "+",
+            _ => "-",
+        };
+
+        let score = self.0.unsigned_abs();
+        write!(f, "{}{}.{:02}", sign, score / 100, score % 100)
+    }
+}
+
+//==================================================
+//=== Weight
+//==================================================
+
+/// Middlegame and endgame value in centipawns, blended by the phase of the game
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Weight {
+    pub mg: i32,
+    pub eg: i32,
+}
+
+impl Weight {
+    /// Creates a new [`Weight`]
+    pub const fn new(mg: i32, eg: i32) -> Self {
+        Self { mg, eg }
+    }
+
+    /// Gives back the value at `phase`, from 0 in the endgame to 24 in the middlegame
+    fn taper(&self, phase: i32) -> i32 {
+        (self.mg * phase + self.eg * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+impl ops::Add for Weight {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.mg + other.mg, self.eg + other.eg)
+    }
+}
+
+impl ops::AddAssign for Weight {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl ops::Mul<i32> for Weight {
+    type Output = Self;
+
+    fn mul(self, count: i32) -> Self {
+        Self::new(self.mg * count, self.eg * count)
+    }
+}
+
+/// Middlegame and endgame value, E.g. "82 94"
+impl fmt::Display for Weight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.mg, self.eg)
+    }
+}
+
+//==================================================
+//=== Term
+//==================================================
+
+/// Parts of the evaluation
+///
+/// * `Material` value of the [`Unit`]s
+/// * `PieceSquares` value of the squares the [`Unit`]s stand on
+/// * `Mobility` squares the [`Unit`]s can step to
+/// * `KingSafety` Pawns in front of the King and attacked squares around it
+/// * `PawnStructure` doubled, isolated and passed Pawns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Term {
+    Material,
+    PieceSquares,
+    Mobility,
+    KingSafety,
+    PawnStructure,
+}
+
+impl Term {
+    /// Every [`Term`] in the order of the [`Evaluation`]
+    pub const ALL: [Term; 5] = [
+        Term::Material,
+        Term::PieceSquares,
+        Term::Mobility,
+        Term::KingSafety,
+        Term::PawnStructure,
+    ];
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Material => write!(f, "Material"),
+            Self::PieceSquares => write!(f, "Piece-Square"),
+            Self::Mobility => write!(f, "Mobility"),
+            Self::KingSafety => write!(f, "King Safety"),
+            Self::PawnStructure => write!(f, "Pawn Structure"),
+        }
+    }
+}
+
+/// * `term` the part of the evaluation
+/// * `white` / `black` score of the side, blended by the phase
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TermScore {
+    pub term: Term,
+    pub white: Score,
+    pub black: Score,
+}
+
+impl TermScore {
+    /// Gives back the score of White minus the score of Black
+    pub fn get_total(&self) -> Score {
+        self.white - self.black
+    }
+}
+
+/// The breakdown of [`Board::get_evaluation`]
+///
+/// * `terms` score of each [`Term`]
+/// * `phase` phase of the game, from 0 in the endgame to 24 in the middlegame
+/// * `score` the sum of the [`Term`]s, same as [`Board::evaluate_with`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluation {
+    pub terms: Vec<TermScore>,
+    pub phase: i32,
+    pub score: Score,
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<16}{:>8}{:>8}{:>8}",
+            "Term", "White", "Black", "Total"
+        )?;
+
+        for term_score in &self.terms {
+            writeln!(
+                f,
+                "{:<16}{:>8}{:>8}{:>8}",
+                term_score.term.to_string(),
+                term_score.white.to_string(),
+                term_score.black.to_string(),
+                term_score.get_total().to_string()
+            )?;
+        }
+
+        writeln!(f, "Phase: {}/{}", self.phase, MAX_PHASE)?;
+        write!(f, "Score: {}", self.score)
+    }
+}
+
+//==================================================
+//=== Parameters
+//==================================================
+
+/// Weights of the evaluation, see [`Board::evaluate_with`]
+///
+/// * `unit_values` value of each [`Unit`] type, indexed by [`Unit::get_id`] % 6
+/// * `square_values` piece-square tables of each [`Unit`] type from the side of White, indexed by `y * 8 + x` of [`Pos`]
+/// * `mobility` value of each square a [`Unit`] type can step to
+/// * `king_shield` value of each own Pawn right in front of the King
+/// * `king_attack` value of each attacked square around the King
+/// * `doubled_pawn` value of each extra Pawn in the same column
+/// * `isolated_pawn` value of each Pawn without own Pawns in the columns next to it
+/// * `passed_pawn` value of a Pawn without enemy Pawns in front of it, indexed by its row counted from its own side
+///
+/// Reads and writes a text format of `key = mg eg` lines, see [parse](#method.parse)
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvalParams {
+    pub unit_values: [Weight; 6],
+    pub square_values: [[Weight; 64]; 6],
+    pub mobility: [Weight; 6],
+    pub king_shield: Weight,
+    pub king_attack: Weight,
+    pub doubled_pawn: Weight,
+    pub isolated_pawn: Weight,
+    pub passed_pawn: [Weight; 8],
+}
+
+impl EvalParams {
+    /// Reads the [`EvalParams`] from the file at `path`, see [parse](#method.parse)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ParamsError> {
+        let text = fs::read_to_string(path).map_err(|error| ParamsError {
+            line: 0,
+            kind: ParamsErrorKind::Io(error.to_string()),
+        })?;
+
+        Self::parse(&text)
+    }
+
+    /// Writes the [`EvalParams`] into the file at `path`, [load](#method.load) reads it back
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ParamsError> {
+        fs::write(path, self.to_string()).map_err(|error| ParamsError {
+            line: 0,
+            kind: ParamsErrorKind::Io(error.to_string()),
+        })
+    }
+
+    /// Reads the [`EvalParams`] from text, the keys missing from it keep their default
+    ///
+    /// Each line holds a key and middlegame-endgame pairs split by commas, E.g. `value.pawn = 82 94`,
+    /// lines without a key continue the pairs of the former one, `#` starts a comment
+    ///
+    /// * `value.<unit>`, `mobility.<unit>` 1 pair for each unit type: pawn, bishop, knight, rook, queen, king
+    /// * `squares.<unit>` 64 pairs, the piece-square table of the unit type
+    /// * `king_shield`, `king_attack`, `doubled_pawn`, `isolated_pawn` 1 pair
+    /// * `passed_pawn` 8 pairs
+    pub fn parse(text: &str) -> Result<Self, ParamsError> {
+        let mut params = Self::default();
+        let mut entries: Vec<(usize, &str, String)> = Vec::new();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match line.split_once('=') {
+                Some((key, value)) => entries.push((idx + 1, key.trim(), value.to_owned())),
+                None => match entries.last_mut() {
+                    Some((_, _, value)) => {
+                        if !value.trim().is_empty() && !value.trim_end().ends_with(',') {
+                            value.push(',');
+                        }
+                        value.push(' ');
+                        value.push_str(line);
+                    }
+                    None => {
+                        return Err(ParamsError {
+                            line: idx + 1,
+                            kind: ParamsErrorKind::InvalidLine(line.to_owned()),
+                        })
+                    }
+                },
+            }
+        }
+
+        for (line, key, value) in entries {
+            params
+                .set_value(key, &value)
+                .map_err(|kind| ParamsError { line, kind })?;
+        }
+
+        Ok(params)
+    }
+
+    /// Sets the [`Weight`]s of `key` from the `value` text
+    fn set_value(&mut self, key: &str, value: &str) -> Result<(), ParamsErrorKind> {
+        let weights = parse_weights(value)?;
+
+        let target: &mut [Weight] = match key.split_once('.') {
+            Some((prefix, unit_name)) => {
+                let idx = UNIT_NAMES
+                    .iter()
+                    .position(|name| *name == unit_name)
+                    .ok_or_else(|| ParamsErrorKind::UnknownKey(key.to_owned()))?;
+
+                match prefix {
+                    "value" => &mut self.unit_values[idx..=idx],
+                    "squares" => &mut self.square_values[idx],
+                    "mobility" => &mut self.mobility[idx..=idx],
+                    _ => return Err(ParamsErrorKind::UnknownKey(key.to_owned())),
+                }
+            }
+            None => match key {
+                "king_shield" => std::slice::from_mut(&mut self.king_shield),
+                "king_attack" => std::slice::from_mut(&mut self.king_attack),
+                "doubled_pawn" => std::slice::from_mut(&mut self.doubled_pawn),
+                "isolated_pawn" => std::slice::from_mut(&mut self.isolated_pawn),
+                "passed_pawn" => &mut self.passed_pawn,
+                _ => return Err(ParamsErrorKind::UnknownKey(key.to_owned())),
+            },
+        };
+
+        if weights.len() != target.len() {
+            return Err(ParamsErrorKind::WeightCount(
+                key.to_owned(),
+                target.len(),
+                weights.len(),
+            ));
+        }
+
+        target.copy_from_slice(&weights);
+        Ok(())
+    }
+}
+
+/// Reads comma separated `mg eg` pairs
+fn parse_weights(value: &str) -> Result<Vec<Weight>, ParamsErrorKind> {
+    value
+        .split(',')
+        .map(|pair| {
+            let numbers: Vec<_> = pair.split_whitespace().map(str::parse::<i32>).collect();
+
+            match numbers[..] {
+                [Ok(mg), Ok(eg)] => Ok(Weight::new(mg, eg)),
+                _ => Err(ParamsErrorKind::InvalidWeight(pair.trim().to_owned())),
+            }
+        })
+        .collect()
+}
+
+/// Writes the [`EvalParams`] in the format of [`EvalParams::parse`]
+impl fmt::Display for EvalParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |weights: &[Weight]| {
+            weights
+                .iter()
+                .map(Weight::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        writeln!(f, "# Middlegame and endgame weights in centipawns")?;
+        for (name, weight) in UNIT_NAMES.iter().zip(self.unit_values) {
+            writeln!(f, "value.{} = {}", name, weight)?;
+        }
+
+        for (name, table) in UNIT_NAMES.iter().zip(&self.square_values) {
+            writeln!(f, "squares.{} =", name)?;
+            for row in table.chunks(8) {
+                writeln!(f, "    {}", join(row))?;
+            }
+        }
+
+        for (name, weight) in UNIT_NAMES.iter().zip(self.mobility) {
+            writeln!(f, "mobility.{} = {}", name, weight)?;
+        }
+
+        writeln!(f, "king_shield = {}", self.king_shield)?;
+        writeln!(f, "king_attack = {}", self.king_attack)?;
+        writeln!(f, "doubled_pawn = {}", self.doubled_pawn)?;
+        writeln!(f, "isolated_pawn = {}", self.isolated_pawn)?;
+        write!(f, "passed_pawn = {}", join(&self.passed_pawn))
+    }
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        let mut square_values = [[Weight::default(); 64]; 6];
+        for (idx, table) in square_values.iter_mut().enumerate() {
+            let eg_table = match idx {
+                0 => &PAWN_ENDGAME_SQUARES,
+                5 => &KING_ENDGAME_SQUARES,
+                _ => &MIDDLEGAME_SQUARES[idx],
+            };
+
+            for (square, weight) in table.iter_mut().enumerate() {
+                *weight = Weight::new(MIDDLEGAME_SQUARES[idx][square], eg_table[square]);
+            }
+        }
+
+        Self {
+            unit_values: [
+                Weight::new(82, 94),
+                Weight::new(365, 297),
+                Weight::new(337, 281),
+                Weight::new(477, 512),
+                Weight::new(1025, 936),
+                Weight::new(0, 0),
+            ],
+            square_values,
+            mobility: [
+                Weight::new(0, 0),
+                Weight::new(5, 5),
+                Weight::new(4, 4),
+                Weight::new(2, 4),
+                Weight::new(1, 2),
+                Weight::new(0, 0),
+            ],
+            king_shield: Weight::new(15, 0),
+            king_attack: Weight::new(-8, -2),
+            doubled_pawn: Weight::new(-10, -20),
+            isolated_pawn: Weight::new(-12, -8),
+            passed_pawn: [
+                Weight::new(0, 0),
+                Weight::new(5, 10),
+                Weight::new(5, 15),
+                Weight::new(10, 25),
+                Weight::new(20, 45),
+                Weight::new(35, 75),
+                Weight::new(60, 120),
+                Weight::new(0, 0),
+            ],
+        }
+    }
+}
+
+/// Default middlegame piece-square tables from the side of White, in the order of [`UNIT_TYPES`]
+#[rustfmt::skip]
+const MIDDLEGAME_SQUARES: [[i32; 64]; 6] = [
+    // Pawn
+    [
+          0,   0,   0,   0,   0,   0,   0,   0,
+         50,  50,  50,  50,  50,  50,  50,  50,
+         10,  10,  20,  30,  30,  20,  10,  10,
+          5,   5,  10,  25,  25,  10,   5,   5,
+          0,   0,   0,  20,  20,   0,   0,   0,
+          5,  -5, -10,   0,   0, -10,  -5,   5,
+          5,  10,  10, -20, -20,  10,  10,   5,
+          0,   0,   0,   0,   0,   0,   0,   0,
+    ],
+    // Bishop
+    [
+        -20, -10, -10, -10, -10, -10, -10, -20,
+        -10,   0,   0,   0,   0,   0,   0, -10,
+        -10,   0,   5,  10,  10,   5,   0, -10,
+        -10,   5,   5,  10,  10,   5,   5, -10,
+        -10,   0,  10,  10,  10,  10,   0, -10,
+        -10,  10,  10,  10,  10,  10,  10, -10,
+        -10,   5,   0,   0,   0,   0,   5, -10,
+        -20, -10, -10, -10, -10, -10, -10, -20,
+    ],
+    // Knight
+    [
+        -50, -40, -30, -30, -30, -30, -40, -50,
+        -40, -20,   0,   0,   0,   0, -20, -40,
+        -30,   0,  10,  15,  15,  10,   0, -30,
+        -30,   5,  15,  20,  20,  15,   5, -30,
+        -30,   0,  15,  20,  20,  15,   0, -30,
+        -30,   5,  10,  15,  15,  10,   5, -30,
+        -40, -20,   0,   5,   5,   0, -20, -40,
+        -50, -40, -30, -30, -30, -30, -40, -50,
+    ],
+    // Rook
+    [
+          0,   0,   0,   0,   0,   0,   0,   0,
+          5,  10,  10,  10,  10,  10,  10,   5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+         -5,   0,   0,   0,   0,   0,   0,  -5,
+          0,   0,   0,   5,   5,   0,   0,   0,
+    ],
+    // Queen
+    [
+        -20, -10, -10,  -5,  -5, -10, -10, -20,
+        -10,   0,   0,   0,   0,   0,   0, -10,
+        -10,   0,   5,   5,   5,   5,   0, -10,
+         -5,   0,   5,   5,   5,   5,   0,  -5,
+          0,   0,   5,   5,   5,   5,   0,  -5,
+        -10,   5,   5,   5,   5,   5,   0, -10,
+        -10,   0,   5,   0,   0,   0,   0, -10,
+        -20, -10, -10,  -5,  -5, -10, -10, -20,
+    ],
+    // King
+    [
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -30, -40, -40, -50, -50, -40, -40, -30,
+        -20, -30, -30, -40, -40, -30, -30, -20,
+        -10, -20, -20, -20, -20, -20, -20, -10,
+         20,  20,   0,   0,   0,   0,  20,  20,
+         20,  30,  10,   0,   0,  10,  30,  20,
+    ],
+];
+
+/// Default endgame piece-square table of the Pawn, it heads to the promotion
+#[rustfmt::skip]
+const PAWN_ENDGAME_SQUARES: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     60,  60,  60,  60,  60,  60,  60,  60,
+     40,  40,  40,  40,  40,  40,  40,  40,
+     25,  25,  25,  25,  25,  25,  25,  25,
+     10,  10,  10,  10,  10,  10,  10,  10,
+      5,   5,   5,   5,   5,   5,   5,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+/// Default endgame piece-square table of the King, it heads to the center
+#[rustfmt::skip]
+const KING_ENDGAME_SQUARES: [i32; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+//==================================================
+//=== Parameters: Error
+//==================================================
+
+/// Error produced while reading [`EvalParams`]
+///
+/// * `line` number of the line in the text, 0 when the file can't be read
+/// * `kind` what went wrong, see [`ParamsErrorKind`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParamsError {
+    pub line: usize,
+    pub kind: ParamsErrorKind,
+}
+
+/// * `Io` the file can't be read or written, holds the message
+/// * `InvalidLine` the line has no key to continue
+/// * `UnknownKey` the key is not a parameter
+/// * `InvalidWeight` the pair is not 2 whole numbers
+/// * `WeightCount` the key needs a different number of pairs, holds the key, the expected and the found count
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamsErrorKind {
+    Io(String),
+    InvalidLine(String),
+    UnknownKey(String),
+    InvalidWeight(String),
+    WeightCount(String, usize, usize),
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let ParamsErrorKind::Io(message) = &self.kind {
+            return write!(f, "Can't access the parameter file: {}", message);
+        }
+
+        write!(f, "Line {}: ", self.line)?;
+
+        match &self.kind {
+            ParamsErrorKind::InvalidLine(line) => write!(f, "Line without key: '{}'", line),
+            ParamsErrorKind::UnknownKey(key) => write!(f, "Unknown key: '{}'", key),
+            ParamsErrorKind::InvalidWeight(pair) => {
+                write!(f, "Invalid weight: '{}', needs 2 numbers", pair)
+            }
+            ParamsErrorKind::WeightCount(key, expected, found) => {
+                write!(f, "{} needs {} weights, found {}", key, expected, found)
+            }
+            ParamsErrorKind::Io(_) => Ok(()),
+        }
+    }
+}
+
+impl error::Error for ParamsError {}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_eval {
+    use super::*;
+
+    /// Gives back the [`Score`] of the given [`Term`]
+    fn term_total(board: &Board, term: Term) -> Score {
+        board
+            .get_evaluation(&EvalParams::default())
+            .terms
+            .iter()
+            .find(|term_score| term_score.term == term)
+            .unwrap()
+            .get_total()
+    }
+
+    #[test]
+    fn test_start_position() {
+        let board = Board::new();
+        let evaluation = board.get_evaluation(&EvalParams::default());
+
+        assert_eq!(board.evaluate(), Score(0));
+        assert_eq!(evaluation.phase, MAX_PHASE);
+        assert_eq!(evaluation.terms.len(), Term::ALL.len());
+    }
+
+    #[test]
+    fn test_mirrored() {
+        let board =
+            Board::from_fen("r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4")
+                .unwrap();
+        let mirrored =
+            Board::from_fen("rnbqk2r/pppp1ppp/5n2/2b1p3/4P3/2N2N2/PPPP1PPP/R1BQKB1R b KQkq - 4 4")
+                .unwrap();
+
+        assert_eq!(board.evaluate(), -mirrored.evaluate());
+
+        // The side in the current turn does not change the evaluation
+        let mut board_black = board.clone();
+        board_black.set_current_turn(Side::Black);
+        assert_eq!(board.evaluate(), board_black.evaluate());
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let params = EvalParams::default();
+        let evaluation = board.get_evaluation(&params);
+
+        let total = evaluation
+            .terms
+            .iter()
+            .fold(Score(0), |score, term_score| score + term_score.get_total());
+        assert_eq!(evaluation.score, total);
+        assert_eq!(evaluation.score, board.evaluate_with(&params));
+
+        let text = evaluation.to_string();
+        assert!(text.contains("Pawn Structure"));
+        assert!(text.ends_with(&format!("Score: {}", evaluation.score)));
+    }
+
+    #[test]
+    fn test_material() {
+        // White has an extra Knight
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/3NK3 w - - 0 1").unwrap();
+        let evaluation = board.get_evaluation(&EvalParams::default());
+
+        assert_eq!(evaluation.phase, 1);
+        assert_eq!(
+            term_total(&board, Term::Material),
+            Score((337 + 281 * 23) / 24)
+        );
+        assert!(board.evaluate() > Score(0));
+    }
+
+    #[test]
+    fn test_taper() {
+        // Kings only, the endgame weights count
+        let board = Board::from_fen("4k3/8/8/8/3K4/8/8/8 w - - 0 1").unwrap();
+        let evaluation = board.get_evaluation(&EvalParams::default());
+
+        assert_eq!(evaluation.phase, 0);
+        assert_eq!(term_total(&board, Term::PieceSquares), Score(40 - (-30)));
+    }
+
+    #[test]
+    fn test_pawn_structure() {
+        let params = EvalParams::default();
+
+        // Doubled + Isolated + Passed Pawns on C3 and C4, Black has 2 passed Pawns
+        let board = Board::from_fen("4k3/5pp1/8/8/2P5/2P5/8/4K3 w - - 0 1").unwrap();
+        let pawn_structure = board.get_evaluation(&params).terms[4];
+
+        let expected = params.doubled_pawn
+            + params.isolated_pawn * 2
+            + params.passed_pawn[3]
+            + params.passed_pawn[2];
+        assert_eq!(pawn_structure.white, Score(expected.taper(0)));
+        assert_eq!(
+            pawn_structure.black,
+            Score((params.passed_pawn[1] * 2).taper(0))
+        );
+
+        // A passed Pawn on the 7th row
+        let board = Board::from_fen("4k3/P7/8/8/8/8/7p/4K3 w - - 0 1").unwrap();
+        let pawn_structure = board.get_evaluation(&params).terms[4];
+        assert_eq!(
+            pawn_structure.white,
+            Score((params.passed_pawn[6] + params.isolated_pawn).taper(0))
+        );
+        assert_eq!(
+            pawn_structure.black,
+            Score((params.passed_pawn[6] + params.isolated_pawn).taper(0))
+        );
+
+        // Blocked by an enemy Pawn in the next column, not passed
+        let board = Board::from_fen("4k3/1p6/8/P7/8/8/8/4K3 w - - 0 1").unwrap();
+        let pawn_structure = board.get_evaluation(&params).terms[4];
+        assert_eq!(pawn_structure.white, Score(params.isolated_pawn.taper(0)));
+    }
+
+    #[test]
+    fn test_king_safety() {
+        let params = EvalParams::default();
+
+        // The castled King with 3 Pawns in front of it
+        let board = Board::from_fen("4k3/8/8/8/8/8/5PPP/Q5K1 w - - 0 1").unwrap();
+        let evaluation = board.get_evaluation(&params);
+        assert_eq!(
+            evaluation.terms[3].white,
+            Score((params.king_shield * 3).taper(evaluation.phase))
+        );
+
+        // The Rook attacks F1 next to the King, G1 blocks the rest of the row
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/r5K1 w - - 0 1").unwrap();
+        let evaluation = board.get_evaluation(&params);
+        assert_eq!(
+            evaluation.terms[3].white,
+            Score(params.king_attack.taper(evaluation.phase))
+        );
+    }
+
+    #[test]
+    fn test_mobility() {
+        // The Knight in the corner reaches 2 squares, in the center 8
+        let corner = Board::from_fen("4k3/8/8/8/8/8/8/N3K3 w - - 0 1").unwrap();
+        let center = Board::from_fen("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1").unwrap();
+
+        let params = EvalParams::default();
+        let mobility = |board: &Board| board.get_evaluation(&params).terms[2].white;
+        assert_eq!(mobility(&corner), Score((params.mobility[2] * 2).taper(1)));
+        assert_eq!(mobility(&center), Score((params.mobility[2] * 8).taper(1)));
+    }
+
+    #[test]
+    fn test_params_round_trip() {
+        let params = EvalParams::default();
+        assert_eq!(EvalParams::parse(&params.to_string()), Ok(params));
+
+        let mut params = EvalParams::default();
+        params.unit_values[0] = Weight::new(100, 120);
+        params.passed_pawn[6] = Weight::new(-1, 200);
+        assert_eq!(EvalParams::parse(&params.to_string()), Ok(params));
+    }
+
+    #[test]
+    fn test_params_parse() {
+        let params = EvalParams::parse(
+            "# Stronger Pawns\nvalue.pawn = 100 120 # comment\n\nking_shield = 20 5\n",
+        )
+        .unwrap();
+
+        assert_eq!(params.unit_values[0], Weight::new(100, 120));
+        assert_eq!(params.king_shield, Weight::new(20, 5));
+        assert_eq!(params.unit_values[1], EvalParams::default().unit_values[1]);
+    }
+
+    #[test]
+    fn test_params_errors() {
+        let error = |text: &str| EvalParams::parse(text).unwrap_err();
+
+        assert_eq!(
+            error("value.pawn = 1 2\nvalue.dragon = 1 2"),
+            ParamsError {
+                line: 2,
+                kind: ParamsErrorKind::UnknownKey(String::from("value.dragon"))
+            }
+        );
+        assert_eq!(
+            error("passed_pawn = 1 2, 3 4").kind,
+            ParamsErrorKind::WeightCount(String::from("passed_pawn"), 8, 2)
+        );
+        assert_eq!(
+            error("king_shield = 1").kind,
+            ParamsErrorKind::InvalidWeight(String::from("1"))
+        );
+        assert_eq!(
+            error("1 2").kind,
+            ParamsErrorKind::InvalidLine(String::from("1 2"))
+        );
+        assert!(matches!(
+            EvalParams::load("missing/params.txt").unwrap_err().kind,
+            ParamsErrorKind::Io(_)
+        ));
+    }
+
+    #[test]
+    fn test_score_display() {
+        assert_eq!(Score(125).to_string(), "+1.25");
+        assert_eq!(Score(-5).to_string(), "-0.05");
+        assert_eq!(Score(0).to_string(), "0.00");
+    }
+}
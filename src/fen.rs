@@ -0,0 +1,744 @@
+// Standard Crate
+use std::{error, fmt};
+
+// Chess Crate
+use super::chess::Board;
+use super::crazyhouse::Pocket;
+use super::game::Game;
+use super::pos::Pos;
+use super::unit::{Side, Unit};
+
+/// FEN of the default starting position
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+const BOARD_SIZE: i8 = 8;
+
+/// Castling rights in the order of [`Board::get_castling_rooks`]
+const CASTLING: [char; 4] = ['K', 'Q', 'k', 'q'];
+
+//==================================================
+//=== FEN: Board
+//==================================================
+
+impl Board {
+    /// Creates a [`Board`] from Forsyth–Edwards Notation
+    ///
+    /// E.g. "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    ///
+    /// The halfmove clock and the fullmove number are optional
+    ///
+    /// Chess960 positions are read from X-FEN or Shredder-FEN, E.g. "bqnb1rkr/.../BQ1BNRKR w HFhf - 2 9",
+    /// the [`Board`] follows the Chess960 castling rules when the King or a castling Rook stands off
+    /// its standard square or the castling rights name the column of the Rook
+    ///
+    /// Crazyhouse positions carry the pockets after the placement and mark the promoted units with `~`,
+    /// E.g. "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR[] w KQkq - 4 4" or ".../RNB1K1NR/Pn w ...",
+    /// then the [`Board`] follows the Crazyhouse rules
+    ///
+    /// Three-check positions end with the checks given by White and Black, E.g. "... w KQkq - 0 3 +1+0",
+    /// then the [`Board`] counts the checks
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let mut fields: Vec<&str> = fen.split_whitespace().collect();
+        let checks = fields
+            .last()
+            .filter(|field| field.starts_with('+'))
+            .copied();
+        if checks.is_some() {
+            fields.pop();
+        }
+        if fields.len() != 4 && fields.len() != 6 {
+            return Err(FenError::FieldCount(fields.len()));
+        }
+
+        let mut board = Board::empty();
+
+        parse_placement(&mut board, fields[0])?;
+
+        match fields[1] {
+            "w" => board.set_current_turn(Side::White),
+            "b" => board.set_current_turn(Side::Black),
+            side => return Err(FenError::InvalidSide(side.to_owned())),
+        }
+
+        parse_castling(&mut board, fields[2])?;
+        parse_en_passant(&mut board, fields[3])?;
+
+        if fields.len() == 6 {
+            let halfmove_clock = fields[4]
+                .parse()
+                .map_err(|_| FenError::InvalidHalfmoveClock(fields[4].to_owned()))?;
+            board.set_halfmove_clock(halfmove_clock);
+
+            let fullmove_number = fields[5]
+                .parse()
+                .ok()
+                .filter(|number| *number > 0)
+                .ok_or_else(|| FenError::InvalidFullmoveNumber(fields[5].to_owned()))?;
+            board.set_fullmove_number(fullmove_number);
+        }
+
+        if let Some(checks) = checks {
+            parse_checks(&mut board, checks)?;
+        }
+
+        // The side which just moved can't be in check
+        if board.test_checked_status(&board.get_current_turn().oppose()) {
+            return Err(FenError::InvalidCheck(board.get_current_turn().oppose()));
+        }
+
+        Ok(board)
+    }
+
+    /// Produces the Forsyth–Edwards Notation of the [`Board`]
+    ///
+    /// Castling rights are derived from the `Moved` status of the Kings and Rooks,
+    /// on a Chess960 [`Board`] they are written as X-FEN, see [to_shredder_fen](#method.to_shredder_fen)
+    ///
+    /// On a Crazyhouse [`Board`] the pockets follow the placement in brackets, E.g. "...RNB1K1NR[Qnp] w ...",
+    /// on a Three-check [`Board`] the checks of White and Black follow the clocks, E.g. "... 0 3 +1+0"
+    pub fn to_fen(&self) -> String {
+        self.write_fen(false)
+    }
+
+    /// Produces the Shredder-FEN of the [`Board`], which names the column of each castling Rook
+    ///
+    /// E.g. "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1"
+    pub fn to_shredder_fen(&self) -> String {
+        self.write_fen(true)
+    }
+
+    /// Produces the FEN of the [`Board`], writes the castling rights with the columns of the Rooks when `shredder` is set
+    fn write_fen(&self, shredder: bool) -> String {
+        let mut fen = String::new();
+
+        // Placement
+        for y in 0..BOARD_SIZE {
+            let mut empty = 0;
+
+            for x in 0..BOARD_SIZE {
+                match self.get_unit(&Pos::new(x, y)) {
+                    Some(unit) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        fen.push(unit.get_fen_char());
+
+                        if self.is_crazyhouse() && self.is_promoted(&Pos::new(x, y)) {
+                            fen.push('~');
+                        }
+                    }
+                    None => empty += 1,
+                }
+            }
+
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+
+            if y < BOARD_SIZE - 1 {
+                fen.push('/');
+            }
+        }
+
+        // Pockets
+        if self.is_crazyhouse() {
+            fen.push('[');
+            for side in [Side::White, Side::Black] {
+                fen.push_str(&self.get_pocket(&side).to_fen(side));
+            }
+            fen.push(']');
+        }
+
+        // Side
+        match self.get_current_turn() {
+            Side::White => fen.push_str(" w "),
+            Side::Black => fen.push_str(" b "),
+        }
+
+        // Castling
+        let castling: String = self
+            .get_castling_rooks()
+            .iter()
+            .zip(CASTLING)
+            .filter_map(|(rook_pos, c)| {
+                let rook_pos = (*rook_pos)?;
+                if !shredder && self.find_outer_rook(c, &rook_pos) == Some(rook_pos) {
+                    return Some(c);
+                }
+
+                let column = (b'a' + rook_pos.x as u8) as char;
+                Some(match c.is_ascii_uppercase() {
+                    true => column.to_ascii_uppercase(),
+                    false => column,
+                })
+            })
+            .collect();
+
+        if castling.is_empty() {
+            fen.push('-');
+        } else {
+            fen.push_str(&castling);
+        }
+
+        // En Passant
+        match self.get_en_passant_pos() {
+            Some(pos) => fen.push_str(&format!(" {}", pos.to_notation())),
+            None => fen.push_str(" -"),
+        }
+
+        // Clocks
+        fen.push_str(&format!(
+            " {} {}",
+            self.get_halfmove_clock(),
+            self.get_fullmove_number()
+        ));
+
+        // Checks
+        if self.is_three_check() {
+            fen.push_str(&format!(
+                " +{}+{}",
+                self.get_checks(&Side::White),
+                self.get_checks(&Side::Black)
+            ));
+        }
+
+        fen
+    }
+
+    /// Gives back the [`Pos`] of the outermost Rook on the wing of the castling right `c`, moved or not
+    ///
+    /// The King has to stand on the row of `pos`
+    fn find_outer_rook(&self, c: char, pos: &Pos) -> Option<Pos> {
+        let side = match c.is_ascii_uppercase() {
+            true => Side::White,
+            false => Side::Black,
+        };
+        let king_pos = self
+            .get_king_pos(&side)
+            .filter(|king_pos| king_pos.y == pos.y)?;
+        let columns: Vec<i8> = match c.to_ascii_uppercase() {
+            'K' => (king_pos.x + 1..BOARD_SIZE).rev().collect(),
+            _ => (0..king_pos.x).collect(),
+        };
+
+        columns.into_iter().map(|x| Pos::new(x, pos.y)).find(
+            |pos| matches!(self.get_unit(pos), Some(Unit::Rook(rook_side, _)) if rook_side == side),
+        )
+    }
+}
+
+/// Places the [`Unit`]s on the [`Board`] from the first field of the FEN
+///
+/// Kings and Rooks are placed as moved, [`parse_castling`] resets them
+///
+/// The pockets in brackets or in a 9th row switch the [`Board`] to the Crazyhouse rules, see [`parse_pockets`]
+fn parse_placement(board: &mut Board, placement: &str) -> Result<(), FenError> {
+    let mut rows: Vec<&str> = placement.split('/').collect();
+
+    if let Some((last, pockets)) = rows
+        .last()
+        .and_then(|row| row.strip_suffix(']'))
+        .and_then(|row| row.split_once('['))
+    {
+        rows.pop();
+        rows.push(last);
+        parse_pockets(board, pockets)?;
+    } else if rows.len() == BOARD_SIZE as usize + 1 {
+        let pockets = rows.pop().unwrap_or_default();
+        parse_pockets(board, pockets)?;
+    }
+
+    if rows.len() != BOARD_SIZE as usize {
+        return Err(FenError::RowCount(rows.len()));
+    }
+
+    let mut king_count = [0; 2];
+
+    for (y, row) in rows.iter().enumerate() {
+        let y = y as i8;
+        let mut x = 0;
+
+        for c in row.chars() {
+            if let Some(empty) = c.to_digit(10) {
+                let empty = empty as i8;
+                if !(1..=BOARD_SIZE).contains(&empty) || x + empty > BOARD_SIZE {
+                    return Err(FenError::RowLength(BOARD_SIZE - y));
+                }
+
+                x += empty;
+                continue;
+            }
+
+            // Promoted Unit, marked after its character
+            if c == '~' {
+                let pos = Pos::new(x - 1, y);
+                match board.try_get_unit(&pos) {
+                    Ok(Some(Unit::Pawn(..) | Unit::King(..)) | None) | Err(_) => {
+                        return Err(FenError::InvalidUnit(c))
+                    }
+                    Ok(Some(_)) => board.set_promoted(&pos, true),
+                }
+                board.set_crazyhouse(true);
+                continue;
+            }
+
+            let unit = Unit::from_fen_char(c).ok_or(FenError::InvalidUnit(c))?;
+            if x >= BOARD_SIZE {
+                return Err(FenError::RowLength(BOARD_SIZE - y));
+            }
+
+            let pos = Pos::new(x, y);
+            let unit = match unit {
+                Unit::Pawn(side, _) => {
+                    if y == 0 || y == BOARD_SIZE - 1 {
+                        return Err(FenError::InvalidPawn(pos.to_notation()));
+                    }
+
+                    let start_row = match side {
+                        Side::Black => 1,
+                        Side::White => BOARD_SIZE - 2,
+                    };
+                    Unit::Pawn(side, y != start_row)
+                }
+                Unit::King(side, _) => {
+                    king_count[side as usize] += 1;
+                    Unit::King(side, true)
+                }
+                Unit::Rook(side, _) => Unit::Rook(side, true),
+                unit => unit,
+            };
+
+            board.set_unit(unit, pos);
+            x += 1;
+        }
+
+        if x != BOARD_SIZE {
+            return Err(FenError::RowLength(BOARD_SIZE - y));
+        }
+    }
+
+    for side in [Side::Black, Side::White] {
+        if king_count[side as usize] != 1 {
+            return Err(FenError::KingCount(side, king_count[side as usize]));
+        }
+    }
+
+    Ok(())
+}
+
+/// Fills the pockets of the [`Board`] from the FEN characters of the captured [`Unit`]s, E.g. "QNPqp"
+///
+/// Uppercase characters go to White, lowercase ones to Black, "-" stands for empty pockets
+fn parse_pockets(board: &mut Board, pockets: &str) -> Result<(), FenError> {
+    let invalid = || FenError::InvalidPocket(pockets.to_owned());
+    let mut black = Pocket::new();
+    let mut white = Pocket::new();
+
+    for c in pockets.chars().filter(|c| *c != '-') {
+        let unit = Unit::from_fen_char(c).ok_or_else(invalid)?;
+        let pocket = match unit.get_side() {
+            Side::Black => &mut black,
+            Side::White => &mut white,
+        };
+
+        if !pocket.add(&unit) {
+            return Err(invalid());
+        }
+    }
+
+    board.set_crazyhouse(true);
+    board.set_pocket(&Side::Black, black);
+    board.set_pocket(&Side::White, white);
+
+    Ok(())
+}
+
+/// Sets the checks given by White and Black from the last field of a Three-check FEN, E.g. "+1+0"
+fn parse_checks(board: &mut Board, checks: &str) -> Result<(), FenError> {
+    let invalid = || FenError::InvalidChecks(checks.to_owned());
+    let (white, black) = checks
+        .strip_prefix('+')
+        .and_then(|checks| checks.split_once('+'))
+        .ok_or_else(invalid)?;
+
+    board.set_three_check(true);
+    board.set_checks(&Side::White, white.parse().map_err(|_| invalid())?);
+    board.set_checks(&Side::Black, black.parse().map_err(|_| invalid())?);
+
+    Ok(())
+}
+
+/// Resets the `Moved` status of the Kings and Rooks based on the castling field of the FEN
+///
+/// * `KQkq` the outermost Rook on the wing of the King, as in X-FEN
+/// * `A-H`, `a-h` the Rook on the column, as in Shredder-FEN
+///
+/// Switches the [`Board`] to the Chess960 castling rules, when the castling differs from the standard one
+fn parse_castling(board: &mut Board, castling: &str) -> Result<(), FenError> {
+    if castling == "-" {
+        return Ok(());
+    }
+
+    let invalid = || FenError::InvalidCastling(castling.to_owned());
+
+    for c in castling.chars() {
+        let side = if c.is_ascii_uppercase() {
+            Side::White
+        } else {
+            Side::Black
+        };
+        let row = match side {
+            Side::Black => 0,
+            Side::White => BOARD_SIZE - 1,
+        };
+
+        let king_pos = board
+            .get_king_pos(&side)
+            .filter(|king_pos| king_pos.y == row)
+            .ok_or_else(invalid)?;
+        let rook_pos = match c.to_ascii_uppercase() {
+            'K' | 'Q' => board.find_outer_rook(c, &king_pos),
+            'A'..='H' => Some(Pos::new(c.to_ascii_uppercase() as i8 - b'A' as i8, row)),
+            _ => None,
+        }
+        .filter(|rook_pos| *rook_pos != king_pos)
+        .ok_or_else(invalid)?;
+
+        match board.get_unit(&rook_pos) {
+            Some(Unit::Rook(rook_side, true)) if rook_side == side => {
+                board.set_unit(Unit::King(side, false), king_pos);
+                board.set_unit(Unit::Rook(side, false), rook_pos);
+            }
+            _ => return Err(invalid()),
+        }
+
+        if !matches!(c, 'K' | 'Q' | 'k' | 'q')
+            || king_pos.x != 4
+            || (rook_pos.x != 0 && rook_pos.x != BOARD_SIZE - 1)
+        {
+            board.set_chess960(true);
+        }
+    }
+
+    Ok(())
+}
+
+/// Sets the En Passant [`Pos`] of the [`Board`] from the fourth field of the FEN
+fn parse_en_passant(board: &mut Board, en_passant: &str) -> Result<(), FenError> {
+    if en_passant == "-" {
+        return Ok(());
+    }
+
+    let invalid = || FenError::InvalidEnPassant(en_passant.to_owned());
+
+    let pos = parse_pos(en_passant).ok_or_else(invalid)?;
+    let row = match board.get_current_turn() {
+        Side::White => 2,
+        Side::Black => BOARD_SIZE - 3,
+    };
+
+    if pos.y != row {
+        return Err(invalid());
+    }
+
+    board.set_en_passant_pos(Some(pos));
+
+    Ok(())
+}
+
+/// Produces a [`Pos`] from its lowercase chess notation, E.g. "e3", see [`Pos::from_str`](std::str::FromStr)
+///
+/// Returns `None` instead of panicking when the notation is invalid
+pub(crate) fn parse_pos(s: &str) -> Option<Pos> {
+    if !s.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+//==================================================
+//=== FEN: Game
+//==================================================
+
+impl Game {
+    /// Creates a [`Game`] from Forsyth–Edwards Notation
+    ///
+    /// See also [`Board::from_fen`]
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        Ok(Game::from_board(Board::from_fen(fen)?))
+    }
+
+    /// Produces the Forsyth–Edwards Notation of the current position
+    pub fn to_fen(&self) -> String {
+        self.board_state.to_fen()
+    }
+}
+
+//==================================================
+//=== FEN: Error
+//==================================================
+
+/// Errors produced while parsing Forsyth–Edwards Notation
+#[derive(Clone, Debug, PartialEq)]
+pub enum FenError {
+    FieldCount(usize),
+    RowCount(usize),
+    RowLength(i8),
+    InvalidUnit(char),
+    InvalidPawn(String),
+    KingCount(Side, usize),
+    InvalidSide(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidHalfmoveClock(String),
+    InvalidFullmoveNumber(String),
+    InvalidCheck(Side),
+    InvalidPocket(String),
+    InvalidChecks(String),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FieldCount(count) => {
+                write!(f, "FEN needs 4 or 6 fields, found {}", count)
+            }
+            Self::RowCount(count) => write!(f, "FEN needs 8 rows, found {}", count),
+            Self::RowLength(row) => write!(f, "Row {} does not have 8 squares", row),
+            Self::InvalidUnit(c) => write!(f, "Invalid unit: '{}'", c),
+            Self::InvalidPawn(pos) => write!(f, "Pawn can't stand on {}", pos),
+            Self::KingCount(side, count) => {
+                write!(f, "{} needs exactly 1 King, found {}", side, count)
+            }
+            Self::InvalidSide(side) => write!(f, "Invalid side to move: '{}'", side),
+            Self::InvalidCastling(castling) => {
+                write!(f, "Invalid castling rights: '{}'", castling)
+            }
+            Self::InvalidEnPassant(pos) => write!(f, "Invalid en passant square: '{}'", pos),
+            Self::InvalidHalfmoveClock(clock) => write!(f, "Invalid halfmove clock: '{}'", clock),
+            Self::InvalidFullmoveNumber(number) => {
+                write!(f, "Invalid fullmove number: '{}'", number)
+            }
+            Self::InvalidCheck(side) => {
+                write!(f, "{} is in check, but it is not its turn", side)
+            }
+            Self::InvalidPocket(pocket) => write!(f, "Invalid pocket: '{}'", pocket),
+            Self::InvalidChecks(checks) => write!(f, "Invalid checks: '{}'", checks),
+        }
+    }
+}
+
+impl error::Error for FenError {}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_fen {
+    use super::*;
+    use crate::game::GameState;
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+    #[test]
+    fn test_start_position() {
+        assert_eq!(Board::from_fen(START_FEN).unwrap(), Board::default());
+        assert_eq!(Board::default().to_fen(), START_FEN);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for fen in [
+            KIWIPETE,
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        ] {
+            assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn test_optional_clocks() {
+        let board = Board::from_fen("8/8/8/8/8/8/8/K1k5 b - -").unwrap();
+
+        assert_eq!(board.get_current_turn(), Side::Black);
+        assert_eq!(board.to_fen(), "8/8/8/8/8/8/8/K1k5 b - - 0 1");
+    }
+
+    #[test]
+    fn test_after_steps() {
+        let mut board = Board::default();
+
+        assert!(board.test_step(&Pos::from_notation("E2"), &Pos::from_notation("E4")));
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+
+        assert!(board.test_step(&Pos::from_notation("G8"), &Pos::from_notation("F6")));
+        assert!(board.test_step(&Pos::from_notation("E1"), &Pos::from_notation("E2")));
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPPKPPP/RNBQ1BNR b kq - 2 2"
+        );
+
+        assert!(board.test_step(&Pos::from_notation("H8"), &Pos::from_notation("G8")));
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkbr1/pppppppp/5n2/8/4P3/8/PPPPKPPP/RNBQ1BNR w q - 3 3"
+        );
+    }
+
+    #[test]
+    fn test_castling_rights() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kq - 0 1").unwrap();
+
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("E1")),
+            Some(Unit::King(Side::White, false))
+        );
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("H1")),
+            Some(Unit::Rook(Side::White, false))
+        );
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("A1")),
+            Some(Unit::Rook(Side::White, true))
+        );
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("H8")),
+            Some(Unit::Rook(Side::Black, true))
+        );
+        assert_eq!(
+            board.get_unit(&Pos::from_notation("A8")),
+            Some(Unit::Rook(Side::Black, false))
+        );
+
+        let mut board = board;
+        assert!(!board
+            .clone()
+            .test_step(&Pos::from_notation("E1"), &Pos::from_notation("C1")));
+        assert!(board.test_step(&Pos::from_notation("E1"), &Pos::from_notation("G1")));
+        assert!(!board
+            .clone()
+            .test_step(&Pos::from_notation("E8"), &Pos::from_notation("G8")));
+        assert!(board.test_step(&Pos::from_notation("E8"), &Pos::from_notation("C8")));
+    }
+
+    #[test]
+    fn test_en_passant() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3")
+                .unwrap();
+
+        assert_eq!(board.get_en_passant_pos(), Some(Pos::from_notation("F6")));
+        assert!(!board
+            .clone()
+            .test_step(&Pos::from_notation("E5"), &Pos::from_notation("D6")));
+        assert!(board.test_step(&Pos::from_notation("E5"), &Pos::from_notation("F6")));
+        assert_eq!(board.get_unit(&Pos::from_notation("F5")), None);
+    }
+
+    #[test]
+    fn test_three_check_counters() {
+        let fen = "rnbqkbnr/ppp2ppp/8/1B1pp3/4P3/8/PPPP1PPP/RNBQK1NR b KQkq - 1 3 +1+0";
+        let board = Board::from_fen(fen).unwrap();
+
+        assert!(board.is_three_check());
+        assert_eq!(board.get_checks(&Side::White), 1);
+        assert_eq!(board.get_checks(&Side::Black), 0);
+        assert_eq!(board.to_fen(), fen);
+
+        // The counters follow the placement fields without the clocks too
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - +0+2").unwrap();
+        assert_eq!(board.get_checks(&Side::Black), 2);
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1 +0+2");
+
+        // The counters are part of the position
+        let other = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1 +0+1").unwrap();
+        assert_ne!(board.get_hash(), other.get_hash());
+        assert_eq!(board.get_hash(), board.compute_hash());
+    }
+
+    #[test]
+    fn test_parse_pos() {
+        assert_eq!(parse_pos("e3"), Some(Pos::from_notation("E3")));
+        for s in ["E3", "e9", "i3", "e", "e3 ", ""] {
+            assert_eq!(parse_pos(s), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_game_from_fen() {
+        let game = Game::from_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+
+        let game = Game::from_fen(KIWIPETE).unwrap();
+        assert_eq!(game.get_game_state(), GameState::Playing);
+        assert_eq!(game.to_fen(), KIWIPETE);
+    }
+
+    #[test]
+    fn test_errors() {
+        let tests = [
+            ("8/8/8/8/8/8/8/K1k5 w -", FenError::FieldCount(3)),
+            ("8/8/8/8/8/8/K1k5 w - - 0 1", FenError::RowCount(7)),
+            ("8/8/8/8/8/8/9/K1k5 w - - 0 1", FenError::RowLength(2)),
+            ("8/8/8/8/8/8/7/K1k5 w - - 0 1", FenError::RowLength(2)),
+            (
+                "99999999999999999/8/8/8/8/8/8/K1k5 w - - 0 1",
+                FenError::RowLength(8),
+            ),
+            ("08/8/8/8/8/8/8/K1k5 w - - 0 1", FenError::RowLength(8)),
+            ("8/8/8/8/8/8/8/K1k4x w - - 0 1", FenError::InvalidUnit('x')),
+            (
+                "P7/8/8/8/8/8/8/K1k5 w - - 0 1",
+                FenError::InvalidPawn("a8".into()),
+            ),
+            (
+                "8/8/8/8/8/8/8/K7 w - - 0 1",
+                FenError::KingCount(Side::Black, 0),
+            ),
+            (
+                "8/8/8/8/8/8/8/K1k5 x - - 0 1",
+                FenError::InvalidSide("x".into()),
+            ),
+            (
+                "8/8/8/8/8/8/8/K1k5 w K - 0 1",
+                FenError::InvalidCastling("K".into()),
+            ),
+            (
+                "8/8/8/8/8/8/8/K1k5 w - e4 0 1",
+                FenError::InvalidEnPassant("e4".into()),
+            ),
+            (
+                "8/8/8/8/8/8/8/K1k5 w - - x 1",
+                FenError::InvalidHalfmoveClock("x".into()),
+            ),
+            (
+                "8/8/8/8/8/8/8/K1k5 w - - 0 0",
+                FenError::InvalidFullmoveNumber("0".into()),
+            ),
+            (
+                "8/8/8/8/8/8/8/Kk6 w - - 0 1",
+                FenError::InvalidCheck(Side::Black),
+            ),
+            (
+                "8/8/8/8/8/8/8/K1k5 w - - 0 1 +1",
+                FenError::InvalidChecks("+1".into()),
+            ),
+            (
+                "8/8/8/8/8/8/8/K1k5 w - - 0 1 +1+x",
+                FenError::InvalidChecks("+1+x".into()),
+            ),
+        ];
+
+        for (fen, error) in tests {
+            assert_eq!(Board::from_fen(fen), Err(error));
+        }
+    }
+}
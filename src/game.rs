@@ -1,32 +1,70 @@
+// Standard Crate
+use std::fmt;
+use std::sync::Arc;
+
 // Chess Crate
-use super::chess::Board;
+use super::chess::{Board, MoveImage};
+use super::clock::Clock;
+use super::moves::{Move, MoveError};
 use super::pos::Pos;
-use super::unit::Side;
+use super::unit::{Side, Unit};
+use super::variant::{Standard, Variant};
+
+/// Number of steps without capture or Pawn step, after which a side can claim a draw
+const FIFTY_MOVE_RULE: u32 = 100;
+
+/// Number of steps without capture or Pawn step, after which the game ends in a draw
+const SEVENTY_FIVE_MOVE_RULE: u32 = 150;
+
+/// Number of repetitions of the same position, after which a side can claim a draw
+const THREEFOLD_REPETITION: usize = 3;
+
+/// Number of repetitions of the same position, after which the game ends in a draw
+const FIVEFOLD_REPETITION: usize = 5;
 
 //==================================================
 //=== Game
 //==================================================
 
-/// * `board_state` current state of the board
-/// * `current_turn` which side to take the next move, either [`Side::Black`] or [`Side::White`]
+/// * `board_state` current state of the board, including which side takes the next move
 /// * `unit_pos` position of the unit we want to move
 /// * `target_pos` target position where we want to move
+/// * `promotion` unit type the Pawn promotes to, see [is_promotion_pending](#method.is_promotion_pending)
+#[derive(Debug)]
 pub struct Game {
     game_state: GameState,
+    end_reason: Option<EndReason>,
+    draw_offer: Option<Side>,
+    clock: Option<Clock>,
+    variant: Arc<dyn Variant>,
+    start_board: Board,
+    history: Vec<MoveImage>,
+    positions: Vec<u64>,
+    ply: usize,
+    adjudicating: bool,
     pub board_state: Board,
-    current_turn: Side,
     pub unit_pos: Option<Pos>,
     pub target_pos: Option<Pos>,
+    pub promotion: Option<Unit>,
 }
 
 impl Default for Game {
     fn default() -> Self {
         Self {
             game_state: GameState::Playing,
+            end_reason: None,
+            draw_offer: None,
+            clock: None,
+            variant: Arc::new(Standard),
+            start_board: Board::new(),
+            history: Vec::new(),
+            positions: vec![position_key(&Board::new())],
+            ply: 0,
+            adjudicating: true,
             board_state: Board::new(),
-            current_turn: Side::White,
             unit_pos: None,
             target_pos: None,
+            promotion: None,
         }
     }
 }
@@ -37,49 +75,1339 @@ impl Game {
         Self::default()
     }
 
+    /// Creates a new Game from the given [`Board`]
+    ///
+    /// The side in the current turn of the `board_state` takes the first move
+    pub fn from_board(board_state: Board) -> Self {
+        let mut game = Self {
+            start_board: board_state.clone(),
+            positions: vec![position_key(&board_state)],
+            board_state,
+            ..Default::default()
+        };
+        game.test_game_ending();
+
+        game
+    }
+
+    /// Creates a new Game played by the rules of the [`Variant`], from its start position
+    pub fn with_variant<V: Variant + 'static>(variant: V) -> Self {
+        let board_state = variant.get_start_board();
+
+        Self::from_board_variant(board_state, variant)
+    }
+
+    /// Creates a new Game from the given [`Board`], played by the rules of the [`Variant`]
+    ///
+    /// The [`Variant`] switches on the rules the `board_state` keeps, see [`Variant::set_up_board`]
+    pub fn from_board_variant<V: Variant + 'static>(mut board_state: Board, variant: V) -> Self {
+        variant.set_up_board(&mut board_state);
+
+        let mut game = Self {
+            variant: Arc::new(variant),
+            start_board: board_state.clone(),
+            positions: vec![position_key(&board_state)],
+            board_state,
+            ..Default::default()
+        };
+        game.test_game_ending();
+
+        game
+    }
+
+    /// Gives back the rules the game is played by, see [`Variant`]
+    pub fn get_variant(&self) -> &dyn Variant {
+        self.variant.as_ref()
+    }
+
+    /// Gives back a shared handle of the [`Variant`], E.g. for the [`Engine`](crate::engine::Engine)
+    pub(crate) fn share_variant(&self) -> Arc<dyn Variant> {
+        Arc::clone(&self.variant)
+    }
+
+    /// Sets whether the game ends by itself on Insufficient Material, Fivefold Repetition and the Seventy-Five-Move Rule
+    ///
+    /// Off for the [`Uci`](crate::uci::Uci), the GUI adjudicates those draws
+    pub(crate) fn set_adjudicating(&mut self, adjudicating: bool) {
+        self.adjudicating = adjudicating;
+        self.test_game_ending();
+    }
+
+    /// Gives back the legal [`Move`]s of the side in the current turn, allowed by the [`Variant`]
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = self.board_state.legal_moves(&self.get_current_turn());
+        moves.retain(|chess_move| self.variant.test_move(&self.board_state, chess_move));
+
+        moves
+    }
+
     /// Gives back the current game state
     pub fn get_game_state(&self) -> GameState {
         self.game_state
     }
 
+    /// Gives back why the game ended, `None` while the game is still in progress
+    pub fn get_end_reason(&self) -> Option<EndReason> {
+        self.end_reason
+    }
+
+    /// Gives back the winner and the reason of the finished game, `None` while the game is still in progress
+    pub fn get_outcome(&self) -> Option<Outcome> {
+        match (self.game_state, self.end_reason) {
+            (GameState::Ending(winner), Some(reason)) => Some(Outcome { winner, reason }),
+            _ => None,
+        }
+    }
+
+    /// Ends the game, `side` gives up and the other side wins
+    ///
+    /// Returns false when the game already ended
+    pub fn resign(&mut self, side: Side) -> bool {
+        if let GameState::Ending(_) = self.game_state {
+            return false;
+        }
+
+        self.end_game(Some(side.oppose()), EndReason::Resignation);
+        true
+    }
+
+    /// Ends the game, `side` left the game and the other side wins
+    ///
+    /// Returns false when the game already ended
+    pub fn abandon(&mut self, side: Side) -> bool {
+        if let GameState::Ending(_) = self.game_state {
+            return false;
+        }
+
+        self.end_game(Some(side.oppose()), EndReason::Abandonment);
+        true
+    }
+
+    /// Gives back the [`Clock`] of the game, `None` when the game is played without time
+    pub fn get_clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// Attaches the `clock` to the game, the clock of the side in the current turn starts
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = Some(clock);
+        self.sync_clock();
+    }
+
+    /// Ends the game when the time of the side in the current turn ran out
+    ///
+    /// The other side wins, or it is a draw when the other side can't checkmate
+    ///
+    /// Needs to be called regularly while a [`Clock`] is attached, returns true when the time ran out
+    pub fn test_flag_fall(&mut self) -> bool {
+        if let GameState::Ending(_) = self.game_state {
+            return false;
+        }
+
+        let current_turn = self.get_current_turn();
+        match &self.clock {
+            Some(clock) if clock.is_flagged(current_turn) => {}
+            _ => return false,
+        }
+
+        let winner = Some(current_turn.oppose())
+            .filter(|winner| self.board_state.test_mating_material(winner));
+        self.end_game(winner, EndReason::Timeout);
+
+        true
+    }
+
+    /// Gives back the side which offered a draw, see [offer_draw](#method.offer_draw)
+    pub fn get_draw_offer(&self) -> Option<Side> {
+        self.draw_offer
+    }
+
+    /// `side` offers a draw to the other side, see [accept_draw](#method.accept_draw) and [decline_draw](#method.decline_draw)
+    ///
+    /// The offer gets declined when the other side makes a move instead
+    ///
+    /// Returns false when the game already ended or a draw offer is pending
+    pub fn offer_draw(&mut self, side: Side) -> bool {
+        if let GameState::Ending(_) = self.game_state {
+            return false;
+        }
+
+        if self.draw_offer.is_some() {
+            return false;
+        }
+
+        self.draw_offer = Some(side);
+        true
+    }
+
+    /// Ends the game in a draw when a draw offer is pending
+    ///
+    /// Returns false when there is no draw offer
+    pub fn accept_draw(&mut self) -> bool {
+        if self.draw_offer.is_none() {
+            return false;
+        }
+
+        self.end_game(None, EndReason::AgreedDraw);
+        true
+    }
+
+    /// Drops the pending draw offer, the game goes on
+    ///
+    /// Returns false when there is no draw offer
+    pub fn decline_draw(&mut self) -> bool {
+        self.draw_offer.take().is_some()
+    }
+
+    /// Gives back the draw the side in the current turn can claim
+    ///
+    /// * [`EndReason::ThreefoldRepetition`] the current position appeared 3 times
+    /// * [`EndReason::FiftyMoveRule`] 50 moves of both sides without capture or Pawn step
+    pub fn get_claimable_draw(&self) -> Option<EndReason> {
+        if let GameState::Ending(_) = self.game_state {
+            return None;
+        }
+
+        if self.get_repetition_count() >= THREEFOLD_REPETITION {
+            Some(EndReason::ThreefoldRepetition)
+        } else if self.board_state.get_halfmove_clock() >= FIFTY_MOVE_RULE {
+            Some(EndReason::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    /// Ends the game in a draw when the side in the current turn can claim one
+    ///
+    /// Returns false when there is no draw to claim, see [get_claimable_draw](#method.get_claimable_draw)
+    pub fn claim_draw(&mut self) -> bool {
+        match self.get_claimable_draw() {
+            Some(end_reason) => {
+                self.end_game(None, end_reason);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gives back how many times the current position appeared since the start of the game
+    pub fn get_repetition_count(&self) -> usize {
+        let position = &self.positions[self.ply];
+
+        self.positions[..=self.ply]
+            .iter()
+            .filter(|other| *other == position)
+            .count()
+    }
+
     /// Gives back which side have to take move in the current turn
     pub fn get_current_turn(&self) -> Side {
-        self.current_turn
+        self.board_state.get_current_turn()
+    }
+
+    /// Gives back the [`Board`] the game started from
+    pub fn get_start_board(&self) -> &Board {
+        &self.start_board
+    }
+
+    /// Gives back the [`MoveImage`] of every [`Move`] played since the start of the game
+    ///
+    /// Taken back [`Move`]s are not included, see [redo](#method.redo)
+    pub fn history(&self) -> &[MoveImage] {
+        &self.history[..self.ply]
     }
 
-    // TODO! Game Ending -> CheckMate/StaleMate
+    /// Gives back the keys of the positions since the start of the game, the last one is the current position
+    pub(crate) fn get_position_keys(&self) -> &[u64] {
+        &self.positions[..=self.ply]
+    }
+
+    /// Gives back the number of [`Move`]s played since the start of the game
+    pub fn get_ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Takes back the last [`Move`]
+    ///
+    /// Returns false when there is no [`Move`] to take back
+    pub fn undo(&mut self) -> bool {
+        if self.ply == 0 {
+            return false;
+        }
+
+        self.ply -= 1;
+        self.board_state.unmake_move(&self.history[self.ply]);
+        self.draw_offer = None;
+        self.clear_selection();
+        self.test_game_ending();
+        self.sync_clock();
+
+        true
+    }
 
-    /// Mutates the `board_state` + `current_turn` based on the value of `unit_pos` and `target_pos`
+    /// Plays again the last [`Move`] taken back by [undo](#method.undo)
     ///
-    /// Needs to be called after `unit_pos` or `target_pos` changes value.
-    pub fn game_controller(&mut self) {
+    /// Returns false when there is no [`Move`] to play again
+    pub fn redo(&mut self) -> bool {
+        if self.ply == self.history.len() {
+            return false;
+        }
+
+        let chess_move = self.history[self.ply].chess_move;
+        match self.board_state.make_move_image(&chess_move) {
+            Some(move_image) => self.history[self.ply] = move_image,
+            None => return false,
+        }
+
+        self.ply += 1;
+        self.draw_offer = None;
+        self.clear_selection();
+        self.test_game_ending();
+        self.sync_clock();
+
+        true
+    }
+
+    /// Takes back or plays again [`Move`]s until `ply` [`Move`]s are played since the start of the game
+    ///
+    /// Returns false when `ply` is out of the history
+    pub fn jump_to(&mut self, ply: usize) -> bool {
+        if ply > self.history.len() {
+            return false;
+        }
+
+        while self.ply > ply {
+            self.undo();
+        }
+
+        while self.ply < ply {
+            if !self.redo() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Checks if the step from `unit_pos` to `target_pos` promotes a Pawn and waits for a `promotion` choice
+    pub fn is_promotion_pending(&self) -> bool {
+        match (self.unit_pos, self.target_pos, self.promotion) {
+            (Some(unit_pos), Some(target_pos), None) => self
+                .board_state
+                .legal_moves_from(&unit_pos)
+                .iter()
+                .any(|chess_move| chess_move.target_pos == target_pos && chess_move.is_promotion()),
+            _ => false,
+        }
+    }
+
+    /// Resets `unit_pos`, `target_pos` and `promotion`
+    pub fn clear_selection(&mut self) {
+        self.unit_pos = None;
+        self.target_pos = None;
+        self.promotion = None;
+    }
+
+    /// Mutates the `board_state` based on the value of `unit_pos`, `target_pos` and `promotion`
+    ///
+    /// Needs to be called after `unit_pos`, `target_pos` or `promotion` changes value.
+    ///
+    /// Gives back the [`MoveOutcome`] when a [`Move`] was played, or the reason when the selection or the step failed
+    pub fn game_controller(&mut self) -> Result<Option<MoveOutcome>, MoveError> {
+        if let GameState::Ending(_) = self.game_state {
+            self.clear_selection();
+            return Ok(None);
+        }
+
         match (self.unit_pos, self.target_pos) {
             (Some(unit_pos), None) => {
-                if let Some(unit) = self.board_state.get_unit(&unit_pos) {
-                    if unit.get_side() != self.current_turn {
-                        self.unit_pos = None;
-                        println!("Not your turn!");
+                let selection = match self.board_state.try_get_unit(&unit_pos) {
+                    Ok(Some(unit)) if unit.get_side() != self.get_current_turn() => {
+                        Err(MoveError::WrongSide(unit.get_side()))
                     }
-                } else {
+                    Ok(Some(_)) => Ok(()),
+                    Ok(None) => Err(MoveError::EmptySquare(unit_pos)),
+                    Err(error) => Err(error),
+                };
+
+                if let Err(error) = selection {
                     self.unit_pos = None;
-                    println!("Can't move with an empty square!");
+                    return Err(error);
                 }
+
+                Ok(None)
             }
             (Some(unit_pos), Some(target_pos)) => {
-                if self.board_state.test_step(&unit_pos, &target_pos) {
-                    self.current_turn.swap();
-                    println!("{} moves next!", self.current_turn);
+                // Wait for the Promotion Choice
+                if self.is_promotion_pending() {
+                    return Ok(None);
                 }
-                self.unit_pos = None;
-                self.target_pos = None;
+
+                let outcome = self.make_move(&unit_pos, &target_pos, self.promotion);
+                self.clear_selection();
+
+                outcome.map(Some)
             }
-            _ => {}
+            _ => Ok(None),
+        }
+    }
+
+    /// Plays the step from `unit_pos` to `target_pos` of the side in the current turn
+    ///
+    /// Pawns reaching the last row promote to the type of `promotion`, or to a Queen when it is `None`
+    ///
+    /// Fails with the reason when the step can't be played, see [`MoveError`]
+    pub fn make_move(
+        &mut self,
+        unit_pos: &Pos,
+        target_pos: &Pos,
+        promotion: Option<Unit>,
+    ) -> Result<MoveOutcome, MoveError> {
+        if let GameState::Ending(_) = self.game_state {
+            return Err(MoveError::GameOver);
+        }
+
+        let promotion = promotion.unwrap_or(Unit::QUEEN);
+        let chess_move = self
+            .board_state
+            .try_find_move(unit_pos, target_pos, &promotion)?;
+        if !self.variant.test_move(&self.board_state, &chess_move) {
+            return Err(MoveError::IllegalMove(*unit_pos, *target_pos));
+        }
+
+        self.play_found_move(chess_move)
+    }
+
+    /// Drops a [`Unit`] with the type of `unit` from the pocket of the side in the current turn onto `target_pos`
+    ///
+    /// Works only on a Crazyhouse [`Board`], see [`Board::set_crazyhouse`]
+    ///
+    /// Fails with the reason when the drop can't be played, see [`MoveError`]
+    pub fn make_drop(&mut self, unit: &Unit, target_pos: &Pos) -> Result<MoveOutcome, MoveError> {
+        if let GameState::Ending(_) = self.game_state {
+            return Err(MoveError::GameOver);
+        }
+
+        let chess_move = self.board_state.try_find_drop(unit, target_pos)?;
+        if !self.variant.test_move(&self.board_state, &chess_move) {
+            return Err(MoveError::IllegalDrop(chess_move.unit, *target_pos));
         }
+
+        self.play_found_move(chess_move)
     }
+
+    /// Plays the legal [`Move`] found by [make_move](#method.make_move) or [make_drop](#method.make_drop)
+    fn play_found_move(&mut self, chess_move: Move) -> Result<MoveOutcome, MoveError> {
+        let (unit_pos, target_pos) = (chess_move.unit_pos, chess_move.target_pos);
+
+        // The time of the Move ends here, it counts only in time
+        if let Some(clock) = &mut self.clock {
+            clock.stop();
+        }
+        if self.test_flag_fall() {
+            return Err(MoveError::GameOver);
+        }
+
+        let Some(move_image) = self.board_state.make_move_image(&chess_move) else {
+            self.sync_clock();
+            return Err(MoveError::IllegalMove(unit_pos, target_pos));
+        };
+
+        // A new Move drops the taken back Moves
+        self.history.truncate(self.ply);
+        self.history.push(move_image);
+        self.positions.truncate(self.ply + 1);
+        self.positions.push(position_key(&self.board_state));
+        self.ply += 1;
+
+        // The flag was still up, the clock of the other side starts
+        if let Some(clock) = &mut self.clock {
+            clock.press();
+        }
+
+        // Moving instead of answering declines the draw offer
+        if self.draw_offer == Some(chess_move.unit.get_side().oppose()) {
+            self.draw_offer = None;
+        }
+        self.test_game_ending();
+        self.sync_clock();
+
+        Ok(MoveOutcome {
+            chess_move,
+            check: self
+                .board_state
+                .test_checked_status(&self.get_current_turn()),
+            game_state: self.game_state,
+        })
+    }
+
+    /// Plays a [`Move`] given by [`Board::legal_moves`]
+    ///
+    /// Returns false when the [`Move`] could not be played, see [make_move](#method.make_move)
+    pub fn play_move(&mut self, chess_move: &Move) -> bool {
+        if chess_move.is_drop() {
+            return self
+                .make_drop(&chess_move.unit, &chess_move.target_pos)
+                .is_ok();
+        }
+
+        self.make_move(
+            &chess_move.unit_pos,
+            &chess_move.target_pos,
+            chess_move.promotion,
+        )
+        .is_ok()
+    }
+
+    /// Sets `game_state` to [`GameState::Ending`] when the game can't go on
+    ///
+    /// * Ending of the [`Variant`] -> its [`Outcome`]
+    /// * CheckMate -> `Ending(Some(winner))`
+    /// * StaleMate -> `Ending(None)`
+    /// * Insufficient Material, Fivefold Repetition, Seventy-Five-Move Rule -> `Ending(None)`, unless adjudicating is off
+    /// * Otherwise -> `Playing`
+    fn test_game_ending(&mut self) {
+        let current_turn = self.get_current_turn();
+
+        let (winner, end_reason) = if let Some(outcome) =
+            self.variant.test_ending(&self.board_state, self.history())
+        {
+            (outcome.winner, outcome.reason)
+        } else if self.legal_moves().is_empty() {
+            if self.board_state.test_checked_status(&current_turn) {
+                (Some(current_turn.oppose()), EndReason::Checkmate)
+            } else {
+                (None, EndReason::Stalemate)
+            }
+        } else if self.adjudicating && self.variant.test_insufficient_material(&self.board_state) {
+            (None, EndReason::InsufficientMaterial)
+        } else if self.adjudicating && self.get_repetition_count() >= FIVEFOLD_REPETITION {
+            (None, EndReason::FivefoldRepetition)
+        } else if self.adjudicating
+            && self.board_state.get_halfmove_clock() >= SEVENTY_FIVE_MOVE_RULE
+        {
+            (None, EndReason::SeventyFiveMoveRule)
+        } else {
+            self.game_state = GameState::Playing;
+            self.end_reason = None;
+            return;
+        };
+
+        self.end_game(winner, end_reason);
+    }
+
+    /// Sets `game_state` to [`GameState::Ending`] with the `winner` and the `end_reason`
+    pub(crate) fn end_game(&mut self, winner: Option<Side>, end_reason: EndReason) {
+        self.game_state = GameState::Ending(winner);
+        self.end_reason = Some(end_reason);
+        self.draw_offer = None;
+        self.clear_selection();
+
+        if let Some(clock) = &mut self.clock {
+            clock.stop();
+        }
+    }
+
+    /// Gives the [`Clock`] turn to the side in the current turn, it runs only while the game is in progress
+    fn sync_clock(&mut self) {
+        let current_turn = self.get_current_turn();
+        let playing = self.game_state == GameState::Playing;
+
+        if let Some(clock) = &mut self.clock {
+            if clock.get_turn() != current_turn {
+                clock.set_turn(current_turn);
+            }
+
+            if playing {
+                clock.start();
+            } else {
+                clock.stop();
+            }
+        }
+    }
+}
+
+/// Produces the key of the position on the [`Board`] for the repetition table, see [`Board::get_hash`]
+///
+/// Positions repeat when the units, the side in the current turn, the castling rights and the En Passant captures are the same
+fn position_key(board: &Board) -> u64 {
+    let en_passant_key = board.get_en_passant_key();
+
+    // A pinned Pawn can't capture En Passant
+    if en_passant_key != 0
+        && !board
+            .legal_moves(&board.get_current_turn())
+            .iter()
+            .any(|chess_move| chess_move.is_en_passant())
+    {
+        return board.get_hash() ^ en_passant_key;
+    }
+
+    board.get_hash()
 }
 
-#[derive(Clone, Copy, Debug)]
+/// The result of a [`Move`] played by [`Game::make_move`]
+///
+/// * `chess_move` the [`Move`] played
+/// * `check` the side in the next turn is in check
+/// * `game_state` state of the game after the [`Move`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoveOutcome {
+    pub chess_move: Move,
+    pub check: bool,
+    pub game_state: GameState,
+}
+
+/// * `Playing` the game is still in progress
+/// * `Ending` the game is over, holds the winner [`Side`] or `None` on a draw
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GameState {
     Playing,
     Ending(Option<Side>),
 }
+
+/// The result of a finished [`Game`], see [`Game::get_outcome`]
+///
+/// * `winner` the winner [`Side`], `None` on a draw
+/// * `reason` why the game ended, see [`EndReason`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outcome {
+    pub winner: Option<Side>,
+    pub reason: EndReason,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.winner {
+            Some(winner) => write!(f, "{} won by {}!", winner, self.reason),
+            None => write!(f, "Draw by {}!", self.reason),
+        }
+    }
+}
+
+/// * `Checkmate` the King of the side in the current turn is in check and has no legal step
+/// * `Stalemate` the side in the current turn has no legal step
+/// * `Resignation` a side gave up, see [`Game::resign`]
+/// * `Timeout` a side ran out of time
+/// * `AgreedDraw` both sides agreed to a draw, see [`Game::offer_draw`]
+/// * `InsufficientMaterial` neither side can checkmate, see [`Board::test_insufficient_material`]
+/// * `ThreefoldRepetition` the same position appeared 3 times, claimed by a side
+/// * `FivefoldRepetition` the same position appeared 5 times
+/// * `FiftyMoveRule` 50 moves without capture or Pawn step, claimed by a side
+/// * `SeventyFiveMoveRule` 75 moves without capture or Pawn step
+/// * `Abandonment` a side left the game, see [`Game::abandon`]
+/// * `Unknown` the game ended off the board, E.g. the result of a PGN game without a decisive move
+/// * `Variant` a win condition of the [`Variant`], holds its description, E.g. "King of the Hill"
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EndReason {
+    Checkmate,
+    Stalemate,
+    Resignation,
+    Timeout,
+    AgreedDraw,
+    InsufficientMaterial,
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    FiftyMoveRule,
+    SeventyFiveMoveRule,
+    Abandonment,
+    Unknown,
+    Variant(&'static str),
+}
+
+impl fmt::Display for EndReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Self::Checkmate => "CheckMate",
+            Self::Stalemate => "StaleMate",
+            Self::Resignation => "Resignation",
+            Self::Timeout => "Timeout",
+            Self::AgreedDraw => "Agreement",
+            Self::InsufficientMaterial => "Insufficient Material",
+            Self::ThreefoldRepetition => "Threefold Repetition",
+            Self::FivefoldRepetition => "Fivefold Repetition",
+            Self::FiftyMoveRule => "Fifty-Move Rule",
+            Self::SeventyFiveMoveRule => "Seventy-Five-Move Rule",
+            Self::Abandonment => "Abandonment",
+            Self::Unknown => "Unknown Reason",
+            Self::Variant(reason) => *reason,
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_game {
+    use super::*;
+    use crate::clock::{ManualTime, TimeControl};
+    use crate::moves::MoveKind;
+    use crate::unit::Unit;
+    use std::time::Duration;
+
+    /// Creates a [`Game`] from the given `units`, `current_turn` takes the first move
+    fn setup(units: &[(Unit, &str)], current_turn: Side) -> Game {
+        let mut board_state = Board::empty();
+        for (unit, pos) in units {
+            board_state.set_unit(*unit, Pos::from_notation(pos));
+        }
+        board_state.set_current_turn(current_turn);
+
+        Game::from_board(board_state)
+    }
+
+    /// Plays the given steps through the `game_controller`
+    fn play(game: &mut Game, steps: &[(&str, &str)]) {
+        for (unit_pos, target_pos) in steps {
+            game.unit_pos = Some(Pos::from_notation(unit_pos));
+            let _ = game.game_controller();
+            game.target_pos = Some(Pos::from_notation(target_pos));
+            let _ = game.game_controller();
+        }
+    }
+
+    #[test]
+    fn test_playing() {
+        let mut game = Game::new();
+        play(&mut game, &[("E2", "E4"), ("E7", "E5")]);
+
+        assert_eq!(game.get_game_state(), GameState::Playing);
+        assert_eq!(game.get_current_turn(), Side::White);
+    }
+
+    #[test]
+    fn test_fools_mate() {
+        let mut game = Game::new();
+        play(
+            &mut game,
+            &[("F2", "F3"), ("E7", "E5"), ("G2", "G4"), ("D8", "H4")],
+        );
+
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::Black)));
+    }
+
+    #[test]
+    fn test_back_rank_mate() {
+        let mut game = setup(
+            &[
+                (Unit::King(Side::Black, true), "G8"),
+                (Unit::Pawn(Side::Black, false), "F7"),
+                (Unit::Pawn(Side::Black, false), "G7"),
+                (Unit::Pawn(Side::Black, false), "H7"),
+                (Unit::King(Side::White, true), "G1"),
+                (Unit::Rook(Side::White, true), "A1"),
+            ],
+            Side::White,
+        );
+        play(&mut game, &[("A1", "A8")]);
+
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+    }
+
+    #[test]
+    fn test_back_rank_escape() {
+        let mut game = setup(
+            &[
+                (Unit::King(Side::Black, true), "G8"),
+                (Unit::Pawn(Side::Black, false), "F7"),
+                (Unit::Pawn(Side::Black, false), "G7"),
+                (Unit::Rook(Side::Black, true), "D7"),
+                (Unit::King(Side::White, true), "G1"),
+                (Unit::Rook(Side::White, true), "A1"),
+            ],
+            Side::White,
+        );
+        play(&mut game, &[("A1", "A8")]);
+
+        // Rook D7 -> D8 blocks and King G8 -> H7 escapes
+        assert_eq!(game.get_game_state(), GameState::Playing);
+    }
+
+    #[test]
+    fn test_smothered_mate() {
+        let mut game = setup(
+            &[
+                (Unit::King(Side::Black, true), "H8"),
+                (Unit::Rook(Side::Black, true), "G8"),
+                (Unit::Pawn(Side::Black, false), "G7"),
+                (Unit::Pawn(Side::Black, false), "H7"),
+                (Unit::King(Side::White, true), "G1"),
+                (Unit::Knight(Side::White), "G5"),
+            ],
+            Side::White,
+        );
+        play(&mut game, &[("G5", "F7")]);
+
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+    }
+
+    #[test]
+    fn test_queen_stalemate() {
+        let mut game = setup(
+            &[
+                (Unit::King(Side::Black, true), "H8"),
+                (Unit::King(Side::White, true), "F7"),
+                (Unit::Queen(Side::White), "G1"),
+            ],
+            Side::White,
+        );
+        play(&mut game, &[("G1", "G6")]);
+
+        assert_eq!(game.get_game_state(), GameState::Ending(None));
+    }
+
+    #[test]
+    fn test_pawn_stalemate() {
+        let mut game = setup(
+            &[
+                (Unit::King(Side::Black, true), "F8"),
+                (Unit::King(Side::White, true), "E6"),
+                (Unit::Pawn(Side::White, true), "F7"),
+            ],
+            Side::White,
+        );
+        play(&mut game, &[("E6", "F6")]);
+
+        assert_eq!(game.get_game_state(), GameState::Ending(None));
+    }
+
+    #[test]
+    fn test_pinned_stalemate() {
+        // Black Rook gets pinned by the White Queen, Black King has no free square
+        let mut game = setup(
+            &[
+                (Unit::King(Side::Black, true), "A8"),
+                (Unit::Rook(Side::Black, true), "B7"),
+                (Unit::King(Side::White, true), "A6"),
+                (Unit::Knight(Side::White), "D7"),
+                (Unit::Queen(Side::White), "D1"),
+            ],
+            Side::White,
+        );
+        play(&mut game, &[("D1", "D5")]);
+
+        assert_eq!(game.get_game_state(), GameState::Ending(None));
+    }
+
+    #[test]
+    fn test_promotion_pending() {
+        let mut game = setup(
+            &[
+                (Unit::King(Side::Black, true), "H8"),
+                (Unit::King(Side::White, true), "E1"),
+                (Unit::Pawn(Side::White, true), "A7"),
+            ],
+            Side::White,
+        );
+        play(&mut game, &[("A7", "A8")]);
+
+        assert!(game.is_promotion_pending());
+        assert_eq!(game.get_current_turn(), Side::White);
+
+        game.promotion = Some(Unit::KNIGHT);
+        assert!(game.game_controller().unwrap().is_some());
+
+        assert!(!game.is_promotion_pending());
+        assert_eq!(
+            game.board_state.get_unit(&Pos::from_notation("A8")),
+            Some(Unit::Knight(Side::White))
+        );
+        assert_eq!(game.get_current_turn(), Side::Black);
+    }
+
+    #[test]
+    fn test_promotion_cancel() {
+        let mut game = setup(
+            &[
+                (Unit::King(Side::Black, true), "H8"),
+                (Unit::King(Side::White, true), "E1"),
+                (Unit::Pawn(Side::White, true), "A7"),
+            ],
+            Side::White,
+        );
+        play(&mut game, &[("A7", "A8")]);
+        game.clear_selection();
+
+        assert!(!game.is_promotion_pending());
+        assert_eq!(
+            game.board_state.get_unit(&Pos::from_notation("A7")),
+            Some(Unit::Pawn(Side::White, true))
+        );
+    }
+
+    #[test]
+    fn test_under_promotion_stalemate() {
+        // Promoting to Queen would be StaleMate, Rook keeps the game going
+        let units = [
+            (Unit::King(Side::White, true), "A8"),
+            (Unit::King(Side::Black, true), "C7"),
+            (Unit::Pawn(Side::Black, true), "G2"),
+        ];
+
+        let mut game = setup(&units, Side::Black);
+        play(&mut game, &[("G2", "G1")]);
+        game.promotion = Some(Unit::QUEEN);
+        let outcome = game.game_controller().unwrap().unwrap();
+        assert_eq!(outcome.game_state, GameState::Ending(None));
+
+        let mut game = setup(&units, Side::Black);
+        play(&mut game, &[("G2", "G1")]);
+        game.promotion = Some(Unit::ROOK);
+        let outcome = game.game_controller().unwrap().unwrap();
+        assert_eq!(outcome.game_state, GameState::Playing);
+    }
+
+    #[test]
+    fn test_ending_locks_game() {
+        let mut game = Game::new();
+        play(
+            &mut game,
+            &[("F2", "F3"), ("E7", "E5"), ("G2", "G4"), ("D8", "H4")],
+        );
+        play(&mut game, &[("E2", "E4")]);
+
+        assert!(game
+            .board_state
+            .get_unit(&Pos::from_notation("E4"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut game = Game::new();
+        play(&mut game, &[("E2", "E4"), ("E7", "E5"), ("G1", "F3")]);
+        let board_state = game.board_state.clone();
+
+        assert!(game.undo());
+        assert!(game.undo());
+        assert_eq!(game.get_ply(), 1);
+        assert_eq!(game.history().len(), 1);
+        assert_eq!(game.get_current_turn(), Side::Black);
+        assert!(game
+            .board_state
+            .get_unit(&Pos::from_notation("E7"))
+            .is_some());
+
+        assert!(game.redo());
+        assert!(game.redo());
+        assert!(!game.redo());
+        assert_eq!(game.board_state, board_state);
+
+        assert!(game.jump_to(0));
+        assert_eq!(game.board_state, Board::new());
+        assert!(!game.undo());
+
+        assert!(game.jump_to(3));
+        assert_eq!(game.board_state, board_state);
+        assert!(!game.jump_to(4));
+    }
+
+    #[test]
+    fn test_undo_drops_redo() {
+        let mut game = Game::new();
+        play(&mut game, &[("E2", "E4"), ("E7", "E5")]);
+
+        game.undo();
+        play(&mut game, &[("C7", "C5")]);
+
+        assert!(!game.redo());
+        assert_eq!(game.history().len(), 2);
+        assert_eq!(
+            game.history()[1].chess_move.target_pos,
+            Pos::from_notation("C5")
+        );
+    }
+
+    #[test]
+    fn test_undo_checkmate() {
+        let mut game = Game::new();
+        play(
+            &mut game,
+            &[("F2", "F3"), ("E7", "E5"), ("G2", "G4"), ("D8", "H4")],
+        );
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::Black)));
+
+        assert!(game.undo());
+        assert_eq!(game.get_game_state(), GameState::Playing);
+        assert_eq!(game.get_current_turn(), Side::Black);
+
+        assert!(game.redo());
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::Black)));
+    }
+
+    #[test]
+    fn test_make_move() {
+        let mut game = Game::new();
+
+        let outcome = game
+            .make_move(&Pos::from_notation("E2"), &Pos::from_notation("E4"), None)
+            .unwrap();
+        assert_eq!(outcome.chess_move.kind, MoveKind::DoubleStep);
+        assert!(!outcome.check);
+        assert_eq!(outcome.game_state, GameState::Playing);
+
+        assert_eq!(
+            game.make_move(&Pos::from_notation("D2"), &Pos::from_notation("D4"), None),
+            Err(MoveError::WrongSide(Side::White))
+        );
+        assert_eq!(
+            game.make_move(&Pos::from_notation("D5"), &Pos::from_notation("D4"), None),
+            Err(MoveError::EmptySquare(Pos::from_notation("D5")))
+        );
+        assert_eq!(
+            game.make_move(&Pos::from_notation("D7"), &Pos::new(3, 8), None),
+            Err(MoveError::OffBoard(Pos::new(3, 8)))
+        );
+        assert_eq!(
+            game.make_move(&Pos::from_notation("D7"), &Pos::from_notation("D3"), None),
+            Err(MoveError::IllegalMove(
+                Pos::from_notation("D7"),
+                Pos::from_notation("D3")
+            ))
+        );
+        assert_eq!(game.get_ply(), 1);
+    }
+
+    #[test]
+    fn test_game_controller_result() {
+        let mut game = Game::new();
+
+        game.unit_pos = Some(Pos::from_notation("E7"));
+        assert_eq!(
+            game.game_controller(),
+            Err(MoveError::WrongSide(Side::Black))
+        );
+        assert_eq!(game.unit_pos, None);
+
+        game.unit_pos = Some(Pos::from_notation("E2"));
+        assert_eq!(game.game_controller(), Ok(None));
+
+        game.target_pos = Some(Pos::from_notation("E5"));
+        assert_eq!(
+            game.game_controller(),
+            Err(MoveError::IllegalMove(
+                Pos::from_notation("E2"),
+                Pos::from_notation("E5")
+            ))
+        );
+
+        game.unit_pos = Some(Pos::from_notation("E2"));
+        game.target_pos = Some(Pos::from_notation("E4"));
+        let outcome = game.game_controller().unwrap().unwrap();
+        assert_eq!(outcome.chess_move.kind, MoveKind::DoubleStep);
+    }
+
+    #[test]
+    fn test_make_move_check() {
+        let mut game = Game::new();
+        play(&mut game, &[("E2", "E4"), ("F7", "F6"), ("D1", "H5")]);
+
+        assert_eq!(
+            game.make_move(&Pos::from_notation("A7"), &Pos::from_notation("A6"), None),
+            Err(MoveError::LeavesKingInCheck(
+                Pos::from_notation("A7"),
+                Pos::from_notation("A6")
+            ))
+        );
+
+        let outcome = game
+            .make_move(&Pos::from_notation("G7"), &Pos::from_notation("G6"), None)
+            .unwrap();
+        assert!(!outcome.check);
+
+        let outcome = game
+            .make_move(&Pos::from_notation("H5"), &Pos::from_notation("G6"), None)
+            .unwrap();
+        assert!(outcome.check);
+        assert!(outcome.chess_move.is_capture());
+    }
+
+    #[test]
+    fn test_make_move_game_over() {
+        let mut game = Game::new();
+        play(
+            &mut game,
+            &[("F2", "F3"), ("E7", "E5"), ("G2", "G4"), ("D8", "H4")],
+        );
+
+        assert_eq!(
+            game.make_move(&Pos::from_notation("E2"), &Pos::from_notation("E4"), None),
+            Err(MoveError::GameOver)
+        );
+    }
+
+    /// Plays the given moves written in SAN
+    fn play_san(game: &mut Game, moves: &[&str]) {
+        for san in moves {
+            game.play_san(san).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_threefold_repetition() {
+        let mut game = Game::new();
+        play_san(&mut game, &["e4", "Nf6", "Nf3", "Ng8", "Ng1", "Nf6", "Nf3"]);
+        assert_eq!(game.get_claimable_draw(), None);
+        assert!(!game.claim_draw());
+
+        // The En Passant square of "e4" can't be captured, so the positions are the same
+        play_san(&mut game, &["Ng8", "Ng1"]);
+        assert_eq!(game.get_repetition_count(), 3);
+        assert_eq!(
+            game.get_claimable_draw(),
+            Some(EndReason::ThreefoldRepetition)
+        );
+        assert_eq!(game.get_game_state(), GameState::Playing);
+
+        assert!(game.claim_draw());
+        assert_eq!(game.get_game_state(), GameState::Ending(None));
+        assert_eq!(game.get_end_reason(), Some(EndReason::ThreefoldRepetition));
+    }
+
+    #[test]
+    fn test_fivefold_repetition() {
+        let mut game = Game::new();
+        for _ in 0..4 {
+            assert_eq!(game.get_game_state(), GameState::Playing);
+            play_san(&mut game, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+        }
+
+        assert_eq!(game.get_game_state(), GameState::Ending(None));
+        assert_eq!(game.get_end_reason(), Some(EndReason::FivefoldRepetition));
+        assert_eq!(game.get_claimable_draw(), None);
+
+        assert!(game.undo());
+        assert_eq!(game.get_game_state(), GameState::Playing);
+        assert_eq!(game.get_end_reason(), None);
+    }
+
+    #[test]
+    fn test_fifty_move_rule() {
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 98 60").unwrap();
+        play_san(&mut game, &["Ra2"]);
+        assert_eq!(game.get_claimable_draw(), None);
+
+        play_san(&mut game, &["Kd8"]);
+        assert_eq!(game.get_claimable_draw(), Some(EndReason::FiftyMoveRule));
+        assert!(game.claim_draw());
+        assert_eq!(game.get_end_reason(), Some(EndReason::FiftyMoveRule));
+    }
+
+    #[test]
+    fn test_seventy_five_move_rule() {
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 149 80").unwrap();
+        play_san(&mut game, &["Ra2"]);
+
+        assert_eq!(game.get_game_state(), GameState::Ending(None));
+        assert_eq!(game.get_end_reason(), Some(EndReason::SeventyFiveMoveRule));
+
+        // CheckMate goes before the Seventy-Five-Move Rule
+        let mut game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 149 80").unwrap();
+        play_san(&mut game, &["Ra8#"]);
+
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+        assert_eq!(game.get_end_reason(), Some(EndReason::Checkmate));
+    }
+
+    #[test]
+    fn test_insufficient_material() {
+        let mut game = Game::from_fen("4k3/8/8/8/2N5/8/3r4/4K3 w - - 0 1").unwrap();
+        play_san(&mut game, &["Nxd2"]);
+
+        assert_eq!(game.get_game_state(), GameState::Ending(None));
+        assert_eq!(game.get_end_reason(), Some(EndReason::InsufficientMaterial));
+
+        // Without adjudicating the game goes on
+        game.set_adjudicating(false);
+        assert_eq!(game.get_game_state(), GameState::Playing);
+        assert!(game.play_move(&game.legal_moves()[0]));
+    }
+
+    #[test]
+    fn test_resign() {
+        let mut game = Game::new();
+        play_san(&mut game, &["e4"]);
+
+        assert!(game.resign(Side::Black));
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+        assert_eq!(
+            game.get_outcome(),
+            Some(Outcome {
+                winner: Some(Side::White),
+                reason: EndReason::Resignation,
+            })
+        );
+        assert_eq!(
+            game.get_outcome().unwrap().to_string(),
+            "White won by Resignation!"
+        );
+
+        assert!(!game.resign(Side::White));
+        assert!(!game.offer_draw(Side::White));
+    }
+
+    #[test]
+    fn test_abandon() {
+        let mut game = Game::new();
+
+        assert!(game.abandon(Side::White));
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::Black)));
+        assert_eq!(game.get_end_reason(), Some(EndReason::Abandonment));
+    }
+
+    #[test]
+    fn test_draw_offer() {
+        let mut game = Game::new();
+        assert!(!game.accept_draw());
+        assert!(!game.decline_draw());
+
+        assert!(game.offer_draw(Side::White));
+        assert!(!game.offer_draw(Side::Black));
+        assert_eq!(game.get_draw_offer(), Some(Side::White));
+
+        assert!(game.decline_draw());
+        assert_eq!(game.get_draw_offer(), None);
+        assert_eq!(game.get_game_state(), GameState::Playing);
+
+        assert!(game.offer_draw(Side::White));
+        assert!(game.accept_draw());
+        assert_eq!(
+            game.get_outcome(),
+            Some(Outcome {
+                winner: None,
+                reason: EndReason::AgreedDraw,
+            })
+        );
+        assert_eq!(game.get_draw_offer(), None);
+    }
+
+    #[test]
+    fn test_draw_offer_expires() {
+        let mut game = Game::new();
+
+        // The offer stays while the side which offered makes its move
+        assert!(game.offer_draw(Side::White));
+        play_san(&mut game, &["e4"]);
+        assert_eq!(game.get_draw_offer(), Some(Side::White));
+
+        play_san(&mut game, &["e5"]);
+        assert_eq!(game.get_draw_offer(), None);
+        assert!(!game.accept_draw());
+    }
+
+    /// Attaches a [`Clock`] with `seconds` for each side, the returned [`ManualTime`] moves its time
+    fn setup_clock(game: &mut Game, seconds: u64) -> ManualTime {
+        let time = ManualTime::new();
+        let time_control = TimeControl::sudden_death(Duration::from_secs(seconds));
+        game.set_clock(Clock::with_time_source(time_control, time.clone()));
+
+        time
+    }
+
+    #[test]
+    fn test_clock() {
+        let mut game = Game::new();
+        let time = setup_clock(&mut game, 60);
+
+        time.advance(Duration::from_secs(10));
+        play_san(&mut game, &["e4"]);
+        time.advance(Duration::from_secs(20));
+
+        let clock = game.get_clock().unwrap();
+        assert_eq!(clock.get_remaining(Side::White), Duration::from_secs(50));
+        assert_eq!(clock.get_remaining(Side::Black), Duration::from_secs(40));
+        assert!(!game.test_flag_fall());
+
+        // Taking back the Move gives the turn back to White
+        assert!(game.undo());
+        time.advance(Duration::from_secs(5));
+        let clock = game.get_clock().unwrap();
+        assert_eq!(clock.get_remaining(Side::White), Duration::from_secs(45));
+        assert_eq!(clock.get_remaining(Side::Black), Duration::from_secs(40));
+    }
+
+    #[test]
+    fn test_flag_fall() {
+        let mut game = Game::new();
+        let time = setup_clock(&mut game, 60);
+
+        play_san(&mut game, &["e4"]);
+        time.advance(Duration::from_secs(60));
+        assert!(game.test_flag_fall());
+        assert_eq!(
+            game.get_outcome(),
+            Some(Outcome {
+                winner: Some(Side::White),
+                reason: EndReason::Timeout,
+            })
+        );
+        assert!(!game.get_clock().unwrap().is_running());
+    }
+
+    #[test]
+    fn test_flag_fall_make_move() {
+        let mut game = Game::new();
+        let time = setup_clock(&mut game, 60);
+
+        time.advance(Duration::from_secs(61));
+        assert_eq!(
+            game.make_move(&Pos::from_notation("E2"), &Pos::from_notation("E4"), None),
+            Err(MoveError::GameOver)
+        );
+        assert_eq!(game.get_end_reason(), Some(EndReason::Timeout));
+        assert_eq!(game.get_ply(), 0);
+    }
+
+    #[test]
+    fn test_clock_illegal_move() {
+        let mut game = Game::new();
+        let time = setup_clock(&mut game, 60);
+        time.advance(Duration::from_secs(10));
+
+        // A Move from an empty square fails on the Board, the clock of White keeps running
+        let board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let chess_move = board.parse_lan("e4e5").unwrap();
+        assert_eq!(
+            game.play_found_move(chess_move),
+            Err(MoveError::IllegalMove(
+                Pos::from_notation("E4"),
+                Pos::from_notation("E5")
+            ))
+        );
+
+        time.advance(Duration::from_secs(10));
+        let clock = game.get_clock().unwrap();
+        assert_eq!(clock.get_turn(), Side::White);
+        assert!(clock.is_running());
+        assert_eq!(clock.get_remaining(Side::White), Duration::from_secs(40));
+        assert_eq!(clock.get_remaining(Side::Black), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_flag_fall_insufficient_material() {
+        let mut game = setup(
+            &[
+                (Unit::King(Side::White, true), "E1"),
+                (Unit::Queen(Side::White), "D1"),
+                (Unit::King(Side::Black, true), "E8"),
+                (Unit::Knight(Side::Black), "B8"),
+            ],
+            Side::White,
+        );
+        let time = setup_clock(&mut game, 60);
+
+        time.advance(Duration::from_secs(60));
+        assert!(game.test_flag_fall());
+        assert_eq!(
+            game.get_outcome(),
+            Some(Outcome {
+                winner: None,
+                reason: EndReason::Timeout,
+            })
+        );
+    }
+}
@@ -1,10 +1,48 @@
+mod bitboard;
+mod book;
 mod chess;
+mod chess960;
+mod clock;
+mod crazyhouse;
+mod engine;
+mod eval;
+mod fen;
 mod game;
+mod moves;
+mod perft;
+mod pgn;
 mod pos;
+mod san;
+mod tablebase;
+mod uci;
 mod unit;
+mod variant;
+mod xboard;
+mod zobrist;
 
 pub mod prelude {
-    pub use crate::game::{Game, GameState};
+    pub use crate::book::{Book, BookEntry, BookError, BookRng, PolyglotKeys};
+    pub use crate::chess::{Board, MoveImage};
+    pub use crate::chess960::{CHESS960_COUNT, CHESS960_STANDARD};
+    pub use crate::clock::{
+        Clock, Increment, ManualTime, RealTime, Stage, TimeControl, TimeSource,
+    };
+    pub use crate::crazyhouse::{Pocket, POCKET_LIMIT};
+    pub use crate::engine::{Engine, Limit, SearchResult};
+    pub use crate::eval::{
+        EvalParams, Evaluation, ParamsError, ParamsErrorKind, Score, Term, TermScore, Weight,
+    };
+    pub use crate::fen::{FenError, START_FEN};
+    pub use crate::game::{EndReason, Game, GameState, MoveOutcome, Outcome};
+    pub use crate::moves::{Move, MoveError, MoveKind};
+    pub use crate::pgn::{PgnError, PgnErrorKind, PgnGame};
     pub use crate::pos::Pos;
+    pub use crate::san::SanError;
+    pub use crate::tablebase::{Tablebase, TablebaseError, TablebaseMove, Wdl};
+    pub use crate::uci::Uci;
     pub use crate::unit::{Side, Unit};
+    pub use crate::variant::{
+        Crazyhouse, KingOfTheHill, RacingKings, Standard, ThreeCheck, Variant,
+    };
+    pub use crate::xboard::Xboard;
 }
@@ -0,0 +1,151 @@
+// Standard Crate
+use std::{error, fmt};
+
+// Chess Crate
+use super::pos::Pos;
+use super::unit::{Side, Unit};
+
+//==================================================
+//=== Move
+//==================================================
+
+/// A legal move on the [`Board`](crate::chess::Board)
+///
+/// * `unit` the [`Unit`] which takes the move
+/// * `unit_pos` position of the unit before the move, the same as `target_pos` on a Drop
+/// * `target_pos` position of the unit after the move, the square of the Rook on a Chess960 Castle
+/// * `captured` the [`Unit`] taken by the move
+/// * `promotion` the [`Unit`] the Pawn promotes to
+/// * `kind` special rules involved in the move, see [`MoveKind`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Move {
+    pub unit: Unit,
+    pub unit_pos: Pos,
+    pub target_pos: Pos,
+    pub captured: Option<Unit>,
+    pub promotion: Option<Unit>,
+    pub kind: MoveKind,
+}
+
+impl Move {
+    /// Checks if the [`Move`] takes an enemy [`Unit`]
+    pub fn is_capture(&self) -> bool {
+        self.captured.is_some()
+    }
+
+    /// Checks if the [`Move`] is a Castle
+    pub fn is_castle(&self) -> bool {
+        matches!(self.kind, MoveKind::CastleLeft | MoveKind::CastleRight)
+    }
+
+    /// Checks if the [`Move`] is an En Passant capture
+    pub fn is_en_passant(&self) -> bool {
+        self.kind == MoveKind::EnPassant
+    }
+
+    /// Checks if the [`Move`] promotes a Pawn
+    pub fn is_promotion(&self) -> bool {
+        self.promotion.is_some()
+    }
+
+    /// Checks if the [`Move`] drops a [`Unit`] from the [`Pocket`](crate::crazyhouse::Pocket) onto the board
+    pub fn is_drop(&self) -> bool {
+        self.kind == MoveKind::Drop
+    }
+
+    /// Gives back the [`Pos`] of the captured [`Unit`], which differs from `target_pos` on En Passant
+    pub fn get_captured_pos(&self) -> Pos {
+        match self.kind {
+            MoveKind::EnPassant => Pos::new(self.target_pos.x, self.unit_pos.y),
+            _ => self.target_pos,
+        }
+    }
+
+    /// Gives back where the [`Unit`] stands after the [`Move`], which differs from `target_pos` on a Chess960 Castle
+    ///
+    /// E.g. B1 -> A1 on a Chess960 [`Board`](crate::chess::Board) castles the King to C1
+    pub fn get_unit_target(&self) -> Pos {
+        match self.kind {
+            MoveKind::CastleLeft => Pos::new(2, self.unit_pos.y),
+            MoveKind::CastleRight => Pos::new(6, self.unit_pos.y),
+            _ => self.target_pos,
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            MoveKind::Drop => write!(f, "{} @ {}", self.unit, self.target_pos),
+            _ => write!(f, "{} {} -> {}", self.unit, self.unit_pos, self.target_pos),
+        }
+    }
+}
+
+/// * `Step` regular step or capture
+/// * `DoubleStep` Pawn steps 2 squares from its starting row
+/// * `EnPassant` Pawn captures the enemy Pawn next to it, E.g. E5 -> D6 takes D5
+/// * `CastleLeft` King and Rook castle towards the A column, E.g. E1 -> C1, the King ends on C and the Rook on D
+/// * `CastleRight` King and Rook castle towards the H column, E.g. E1 -> G1, the King ends on G and the Rook on F
+/// * `Drop` a [`Unit`] from the Crazyhouse pocket is put onto an empty square, E.g. N@F3
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MoveKind {
+    Step,
+    DoubleStep,
+    EnPassant,
+    CastleLeft,
+    CastleRight,
+    Drop,
+}
+
+//==================================================
+//=== Move: Error
+//==================================================
+
+/// Errors produced while looking up or playing a [`Move`]
+///
+/// * `OffBoard` the [`Pos`] is not on the board
+/// * `InvalidPos` the notation does not describe a [`Pos`], E.g. "D55"
+/// * `EmptySquare` there is no [`Unit`] to move at the [`Pos`]
+/// * `WrongSide` the [`Unit`] of the given [`Side`] can't move in the current turn
+/// * `IllegalMove` the [`Unit`] can't step from the first [`Pos`] to the second one
+/// * `LeavesKingInCheck` the step from the first [`Pos`] to the second one leaves the own King in check
+/// * `EmptyPocket` there is no [`Unit`] of the type in the pocket of the side in the current turn
+/// * `IllegalDrop` the [`Unit`] can't be dropped onto the [`Pos`], E.g. a Pawn onto the first or the last row
+/// * `GameOver` the game already ended
+#[derive(Clone, Debug, PartialEq)]
+pub enum MoveError {
+    OffBoard(Pos),
+    InvalidPos(String),
+    EmptySquare(Pos),
+    WrongSide(Side),
+    IllegalMove(Pos, Pos),
+    LeavesKingInCheck(Pos, Pos),
+    EmptyPocket(Unit),
+    IllegalDrop(Unit, Pos),
+    GameOver,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OffBoard(pos) => write!(f, "Pos {} is not on the board", pos),
+            Self::InvalidPos(pos) => write!(f, "Invalid position: '{}'", pos),
+            Self::EmptySquare(pos) => write!(f, "Can't move with the empty square {}", pos),
+            Self::WrongSide(side) => write!(f, "Not your turn, {}!", side),
+            Self::IllegalMove(unit_pos, target_pos) => {
+                write!(f, "Illegal move: {} -> {}", unit_pos, target_pos)
+            }
+            Self::LeavesKingInCheck(unit_pos, target_pos) => {
+                write!(f, "Can't move into check: {} -> {}", unit_pos, target_pos)
+            }
+            Self::EmptyPocket(unit) => write!(f, "No {} in the pocket", unit.get_name()),
+            Self::IllegalDrop(unit, pos) => {
+                write!(f, "Illegal drop: {} -> {}", unit.get_name(), pos)
+            }
+            Self::GameOver => write!(f, "The game is over"),
+        }
+    }
+}
+
+impl error::Error for MoveError {}
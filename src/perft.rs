@@ -0,0 +1,137 @@
+// Chess Crate
+use super::chess::Board;
+use super::moves::Move;
+
+//==================================================
+//=== Perft
+//==================================================
+
+impl Board {
+    /// Counts the move paths of the side in the current turn with `depth` moves
+    ///
+    /// Every Castle, En Passant and promotion choice counts, E.g. 20 paths from the start position with depth 1
+    pub fn perft(&self, depth: u32) -> u64 {
+        self.clone().perft_count(depth)
+    }
+
+    /// Counts the move paths with `depth` moves for each legal [`Move`] of the side in the current turn
+    ///
+    /// The counts add up to [perft](#method.perft), a wrong count shows which [`Move`] to look into
+    pub fn perft_divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        let mut board = self.clone();
+
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        board
+            .legal_moves(&board.get_current_turn())
+            .into_iter()
+            .map(|chess_move| {
+                let move_image = board.apply_move(&chess_move);
+                let count = board.perft_count(depth - 1);
+                board.unmake_move(&move_image);
+
+                (chess_move, count)
+            })
+            .collect()
+    }
+
+    /// Counts the move paths by making and taking back the [`Move`]s on the [`Board`]
+    fn perft_count(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.legal_moves(&self.get_current_turn());
+
+        // Bulk Counting
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        let mut count = 0;
+        for chess_move in moves {
+            let move_image = self.apply_move(&chess_move);
+            count += self.perft_count(depth - 1);
+            self.unmake_move(&move_image);
+        }
+
+        count
+    }
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_perft {
+    use crate::chess::Board;
+    use crate::fen::START_FEN;
+
+    /// Positions and node counts from the Chess Programming Wiki
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+    const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+    const POSITION_4_MIRRORED: &str =
+        "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+    const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+    const POSITION_6: &str =
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+    fn assert_perft(fen: &str, counts: &[u64]) {
+        let board = Board::from_fen(fen).unwrap();
+
+        for (depth, count) in counts.iter().enumerate() {
+            assert_eq!(
+                board.perft(depth as u32 + 1),
+                *count,
+                "{} depth {}",
+                fen,
+                depth + 1
+            );
+        }
+    }
+
+    #[test]
+    fn test_start_position() {
+        assert_perft(START_FEN, &[20, 400, 8_902, 197_281]);
+    }
+
+    #[test]
+    fn test_kiwipete() {
+        assert_perft(KIWIPETE, &[48, 2_039, 97_862]);
+    }
+
+    #[test]
+    fn test_position_3() {
+        assert_perft(POSITION_3, &[14, 191, 2_812, 43_238, 674_624]);
+    }
+
+    #[test]
+    fn test_position_4() {
+        assert_perft(POSITION_4, &[6, 264, 9_467, 422_333]);
+        assert_perft(POSITION_4_MIRRORED, &[6, 264, 9_467, 422_333]);
+    }
+
+    #[test]
+    fn test_position_5() {
+        assert_perft(POSITION_5, &[44, 1_486, 62_379]);
+    }
+
+    #[test]
+    fn test_position_6() {
+        assert_perft(POSITION_6, &[46, 2_079, 89_890]);
+    }
+
+    #[test]
+    fn test_divide() {
+        let board = Board::from_fen(KIWIPETE).unwrap();
+        let divide = board.perft_divide(2);
+
+        assert_eq!(divide.len(), 48);
+        assert_eq!(divide.iter().map(|(_, count)| count).sum::<u64>(), 2_039);
+        assert!(board.perft_divide(0).is_empty());
+    }
+}
@@ -0,0 +1,739 @@
+// Standard Crate
+use std::iter::Peekable;
+use std::str::Chars;
+use std::{error, fmt};
+
+// Chess Crate
+use super::chess::Board;
+use super::fen::FenError;
+use super::game::{EndReason, Game, GameState};
+use super::moves::Move;
+use super::unit::Side;
+
+/// Tags of the Seven Tag Roster with their default values
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+/// Game termination markers
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+/// Maximal length of a movetext line
+const LINE_LENGTH: usize = 80;
+
+//==================================================
+//=== PGN: Game
+//==================================================
+
+/// A game parsed from Portable Game Notation
+///
+/// * `tags` tag pairs in the order of the tag section, E.g. ("White", "Morphy")
+/// * `start_board` position before the first move, set by the "FEN" tag
+/// * `moves` moves of the main line, variations are skipped
+/// * `result` game termination marker, E.g. "1-0"
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub start_board: Board,
+    pub moves: Vec<Move>,
+    pub result: String,
+}
+
+impl PgnGame {
+    /// Parses every game of a Portable Game Notation text
+    ///
+    /// Comments, NAGs and variations are skipped
+    pub fn parse_all(pgn: &str) -> Result<Vec<Self>, PgnError> {
+        let mut reader = Reader::new(pgn);
+        let mut games = Vec::new();
+
+        while let Some(game) = reader.read_game()? {
+            games.push(game);
+        }
+
+        Ok(games)
+    }
+
+    /// Gives back the value of the tag with the given `name`, E.g. "White"
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Replays the `moves` through a new [`Game`]
+    ///
+    /// A `result` the moves don't reach ends the game anyway, the "Termination" tag tells the [`EndReason`]:
+    /// "time forfeit" -> `Timeout`, "abandoned" -> `Abandonment`, otherwise `Unknown`, E.g. a resignation
+    pub fn to_game(&self) -> Game {
+        let mut game = Game::from_board(self.start_board.clone());
+        for chess_move in &self.moves {
+            game.play_move(chess_move);
+        }
+
+        let winner = match self.result.as_str() {
+            "1-0" => Some(Side::White),
+            "0-1" => Some(Side::Black),
+            "1/2-1/2" => None,
+            _ => return game,
+        };
+
+        if game.get_game_state() == GameState::Playing {
+            let reason = match self.get_tag("Termination") {
+                Some("time forfeit") => EndReason::Timeout,
+                Some("abandoned") => EndReason::Abandonment,
+                _ => EndReason::Unknown,
+            };
+            game.end_game(winner, reason);
+        }
+
+        game
+    }
+}
+
+impl Game {
+    /// Creates a [`Game`] from the first game of a Portable Game Notation text
+    ///
+    /// See also [`PgnGame::parse_all`]
+    pub fn from_pgn(pgn: &str) -> Result<Self, PgnError> {
+        let mut reader = Reader::new(pgn);
+
+        match reader.read_game()? {
+            Some(game) => Ok(game.to_game()),
+            None => Err(reader.error(PgnErrorKind::NoGame)),
+        }
+    }
+
+    /// Produces the Portable Game Notation of the [`Game`]
+    ///
+    /// Every tag of the Seven Tag Roster is unknown, see [to_pgn_with_tags](#method.to_pgn_with_tags)
+    pub fn to_pgn(&self) -> String {
+        self.to_pgn_with_tags(&[])
+    }
+
+    /// Produces the Portable Game Notation of the [`Game`] with the given `tags`, E.g. ("White", "Morphy")
+    ///
+    /// The "Result" tag is always derived from the [`GameState`]
+    pub fn to_pgn_with_tags(&self, tags: &[(&str, &str)]) -> String {
+        let result = match self.get_game_state() {
+            GameState::Playing => "*",
+            GameState::Ending(Some(Side::White)) => "1-0",
+            GameState::Ending(Some(Side::Black)) => "0-1",
+            GameState::Ending(None) => "1/2-1/2",
+        };
+
+        let mut pgn = String::new();
+
+        // Seven Tag Roster
+        for (name, default) in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => result,
+                _ => tags
+                    .iter()
+                    .find(|(tag, _)| *tag == name)
+                    .map_or(default, |(_, value)| *value),
+            };
+            push_tag(&mut pgn, name, value);
+        }
+
+        // Starting Position
+        let start_board = self.get_start_board();
+        if start_board.is_crazyhouse() {
+            push_tag(&mut pgn, "Variant", "Crazyhouse");
+        } else if start_board.is_chess960() {
+            push_tag(&mut pgn, "Variant", "Chess960");
+        }
+        if *start_board != Board::new() {
+            push_tag(&mut pgn, "SetUp", "1");
+            push_tag(&mut pgn, "FEN", &start_board.to_fen());
+        }
+
+        // Supplemental Tags
+        for (name, value) in tags {
+            if !SEVEN_TAG_ROSTER.iter().any(|(tag, _)| tag == name)
+                && !matches!(*name, "SetUp" | "FEN" | "Variant")
+            {
+                push_tag(&mut pgn, name, value);
+            }
+        }
+        pgn.push('\n');
+
+        // Movetext
+        let mut tokens = Vec::new();
+        let mut board = start_board.clone();
+        for (ply, (move_image, san)) in self.history().iter().zip(self.get_san_moves()).enumerate()
+        {
+            // Move Number stays on the same line as its move
+            let number = match board.get_current_turn() {
+                Side::White => format!("{}. ", board.get_fullmove_number()),
+                Side::Black if ply == 0 => format!("{}... ", board.get_fullmove_number()),
+                Side::Black => String::new(),
+            };
+
+            tokens.push(number + &san);
+            board.make_move(&move_image.chess_move);
+        }
+        tokens.push(result.to_owned());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + token.len() >= LINE_LENGTH {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        pgn.push_str(&line);
+        pgn.push('\n');
+
+        pgn
+    }
+}
+
+/// Appends a tag pair to the tag section, E.g. [White "Morphy"]
+fn push_tag(pgn: &mut String, name: &str, value: &str) {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    pgn.push_str(&format!("[{} \"{}\"]\n", name, value));
+}
+
+//==================================================
+//=== PGN: Reader
+//==================================================
+
+/// Reads the games of a Portable Game Notation text one by one
+///
+/// * `line` current line of the text, starting from 1
+/// * `line_start` true when no character was read yet from the current line
+struct Reader<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    line_start: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn new(pgn: &'a str) -> Self {
+        Self {
+            chars: pgn.chars().peekable(),
+            line: 1,
+            line_start: true,
+        }
+    }
+
+    /// Produces a [`PgnError`] on the current line
+    fn error(&self, kind: PgnErrorKind) -> PgnError {
+        PgnError {
+            line: self.line,
+            kind,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        self.line_start = c == Some('\n');
+        if self.line_start {
+            self.line += 1;
+        }
+
+        c
+    }
+
+    /// Skips whitespaces and escaped lines starting with '%'
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '%' && self.line_start {
+                self.read_until('\n');
+            } else if c.is_whitespace() {
+                self.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Reads the characters until `end`, which gets consumed
+    ///
+    /// Returns `None` when the text ends before `end`
+    fn read_until(&mut self, end: char) -> Option<String> {
+        let mut s = String::new();
+
+        while let Some(c) = self.next() {
+            if c == end {
+                return Some(s);
+            }
+            s.push(c);
+        }
+
+        None
+    }
+
+    /// Reads a symbol token, E.g. "Nf3", "12." or "1-0"
+    fn read_symbol(&mut self) -> String {
+        let mut symbol = String::new();
+
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || "{};()[]$".contains(c) {
+                break;
+            }
+            symbol.push(c);
+            self.next();
+        }
+
+        symbol
+    }
+
+    /// Reads a tag pair, E.g. [White "Morphy"]
+    fn read_tag(&mut self) -> Result<(String, String), PgnError> {
+        self.next();
+        self.skip_whitespace();
+
+        let name = self.read_symbol();
+        let invalid = |reader: &Self| reader.error(PgnErrorKind::InvalidTag(name.clone()));
+
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(invalid(self));
+        }
+
+        self.skip_whitespace();
+        if self.next() != Some('"') {
+            return Err(invalid(self));
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('"') => break,
+                Some('\\') => match self.next() {
+                    Some(c) => value.push(c),
+                    None => return Err(invalid(self)),
+                },
+                Some('\n') | None => return Err(invalid(self)),
+                Some(c) => value.push(c),
+            }
+        }
+
+        self.skip_whitespace();
+        if self.next() != Some(']') {
+            return Err(invalid(self));
+        }
+
+        Ok((name, value))
+    }
+
+    /// Skips a recursive annotation variation, E.g. "(1... e5 (1... c5))"
+    fn skip_variation(&mut self) -> Result<(), PgnError> {
+        let line = self.line;
+        let mut depth = 0;
+
+        while let Some(c) = self.next() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                '{' => {
+                    self.read_until('}');
+                }
+                ';' => {
+                    self.read_until('\n');
+                }
+                _ => {}
+            }
+
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+
+        Err(PgnError {
+            line,
+            kind: PgnErrorKind::UnterminatedVariation,
+        })
+    }
+
+    /// Reads the next game, returns `None` when the text has no more games
+    fn read_game(&mut self) -> Result<Option<PgnGame>, PgnError> {
+        let mut tags = Vec::new();
+        let mut start_board = Board::new();
+
+        // Tag Section
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('[') {
+                break;
+            }
+
+            let (name, value) = self.read_tag()?;
+            if name == "FEN" {
+                start_board = Board::from_fen(&value)
+                    .map_err(|error| self.error(PgnErrorKind::InvalidFen(error)))?;
+            }
+            tags.push((name, value));
+        }
+
+        // Variant rules, the FEN tells Chess960 only when a King or a Rook stands off its standard square
+        let variant = tags
+            .iter()
+            .find(|(name, _)| name == "Variant")
+            .map(|(_, value)| value.to_ascii_lowercase());
+        match variant.as_deref() {
+            Some("chess960" | "fischerandom") => start_board.set_chess960(true),
+            Some("crazyhouse") => start_board.set_crazyhouse(true),
+            _ => {}
+        }
+
+        // Movetext Section
+        let mut board = start_board.clone();
+        let mut moves = Vec::new();
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                None if tags.is_empty() && moves.is_empty() => return Ok(None),
+                None | Some('[') => return Err(self.error(PgnErrorKind::MissingResult)),
+                Some('{') => {
+                    let line = self.line;
+                    self.next();
+                    if self.read_until('}').is_none() {
+                        return Err(PgnError {
+                            line,
+                            kind: PgnErrorKind::UnterminatedComment,
+                        });
+                    }
+                }
+                Some(';') => {
+                    self.read_until('\n');
+                }
+                Some('(') => self.skip_variation()?,
+                Some('$') => {
+                    self.next();
+                    let nag = self.read_symbol();
+                    if nag.is_empty() || !nag.chars().all(|c| c.is_ascii_digit()) {
+                        return Err(self.error(PgnErrorKind::UnexpectedToken(format!("${}", nag))));
+                    }
+                }
+                Some(c) if "})]".contains(c) => {
+                    self.next();
+                    return Err(self.error(PgnErrorKind::UnexpectedToken(c.to_string())));
+                }
+                Some(_) => {
+                    let symbol = self.read_symbol();
+
+                    if RESULTS.contains(&symbol.as_str()) {
+                        return Ok(Some(PgnGame {
+                            tags,
+                            start_board,
+                            moves,
+                            result: symbol,
+                        }));
+                    }
+
+                    // Move Number Indication, E.g. "12." or "12...e5"
+                    let digits = symbol.trim_start_matches(|c: char| c.is_ascii_digit());
+                    let san = match digits.strip_prefix('.') {
+                        Some(san) => san.trim_start_matches('.'),
+                        None => &symbol,
+                    };
+                    if san.is_empty() || san == "e.p." {
+                        continue;
+                    }
+
+                    let chess_move = board.parse_san(san).map_err(|_| {
+                        self.error(PgnErrorKind::IllegalMove(
+                            board.get_fullmove_number(),
+                            board.get_current_turn(),
+                            san.to_owned(),
+                        ))
+                    })?;
+                    board.make_move(&chess_move);
+                    moves.push(chess_move);
+                }
+            }
+        }
+    }
+}
+
+//==================================================
+//=== PGN: Error
+//==================================================
+
+/// Error produced while parsing Portable Game Notation
+///
+/// * `line` line of the text where the error was found, starting from 1
+/// * `kind` what went wrong, see [`PgnErrorKind`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnError {
+    pub line: usize,
+    pub kind: PgnErrorKind,
+}
+
+/// * `NoGame` the text does not contain any game
+/// * `InvalidTag` the tag pair is malformed, holds the tag name
+/// * `InvalidFen` the "FEN" tag holds an invalid position
+/// * `IllegalMove` the move is not legal or not valid SAN, holds the move number, the moving side and the move
+/// * `UnterminatedComment` a comment misses its closing brace
+/// * `UnterminatedVariation` a variation misses its closing parenthesis
+/// * `UnexpectedToken` a token which can't stand in the movetext
+/// * `MissingResult` the movetext misses the game termination marker
+#[derive(Clone, Debug, PartialEq)]
+pub enum PgnErrorKind {
+    NoGame,
+    InvalidTag(String),
+    InvalidFen(FenError),
+    IllegalMove(u32, Side, String),
+    UnterminatedComment,
+    UnterminatedVariation,
+    UnexpectedToken(String),
+    MissingResult,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: ", self.line)?;
+
+        match &self.kind {
+            PgnErrorKind::NoGame => write!(f, "No game found"),
+            PgnErrorKind::InvalidTag(name) => write!(f, "Invalid tag: '{}'", name),
+            PgnErrorKind::InvalidFen(error) => write!(f, "Invalid FEN tag: {}", error),
+            PgnErrorKind::IllegalMove(number, Side::White, san) => {
+                write!(f, "Illegal move: {}. {}", number, san)
+            }
+            PgnErrorKind::IllegalMove(number, Side::Black, san) => {
+                write!(f, "Illegal move: {}... {}", number, san)
+            }
+            PgnErrorKind::UnterminatedComment => write!(f, "Comment is not closed"),
+            PgnErrorKind::UnterminatedVariation => write!(f, "Variation is not closed"),
+            PgnErrorKind::UnexpectedToken(token) => write!(f, "Unexpected token: '{}'", token),
+            PgnErrorKind::MissingResult => write!(f, "Game termination marker is missing"),
+        }
+    }
+}
+
+impl error::Error for PgnError {}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_pgn {
+    use super::*;
+    use crate::fen::START_FEN;
+
+    const OPERA_GAME: &str = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[Round "?"]
+[White "Paul Morphy"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 d6 3. d4 Bg4 4. dxe5 Bxf3 5. Qxf3 dxe5 6. Bc4 Nf6 7. Qb3 Qe7
+8. Nc3 c6 9. Bg5 b5 10. Nxb5 cxb5 11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7
+14. Rd1 Qe6 15. Bxd7+ Nxd7 16. Qb8+ Nxb8 17. Rd8# 1-0
+"#;
+
+    #[test]
+    fn test_write_opera_game() {
+        let game = Game::from_pgn(OPERA_GAME).unwrap();
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+
+        let pgn = game.to_pgn_with_tags(&[
+            ("Event", "Paris"),
+            ("Site", "Paris FRA"),
+            ("Date", "1858.??.??"),
+            ("White", "Paul Morphy"),
+            ("Black", "Duke Karl / Count Isouard"),
+        ]);
+        assert_eq!(pgn, OPERA_GAME);
+    }
+
+    #[test]
+    fn test_write_playing() {
+        let mut game = Game::new();
+        game.play_san("e4").unwrap();
+
+        assert_eq!(
+            game.to_pgn(),
+            "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+             [White \"?\"]\n[Black \"?\"]\n[Result \"*\"]\n\n1. e4 *\n"
+        );
+    }
+
+    #[test]
+    fn test_write_from_fen() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12";
+        let mut game = Game::from_fen(fen).unwrap();
+        game.play_san("Kd7").unwrap();
+
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 12\"]\n"));
+        assert!(pgn.ends_with("\n12... Kd7 *\n"));
+
+        let game = Game::from_pgn(&pgn).unwrap();
+        assert_eq!(game.get_start_board(), &Board::from_fen(fen).unwrap());
+        assert_eq!(game.get_ply(), 1);
+    }
+
+    #[test]
+    fn test_write_chess960() {
+        let mut game = Game::new_chess960(crate::chess960::CHESS960_STANDARD).unwrap();
+        for san in ["e4", "e5", "Nf3", "Nc6", "Bc4", "Bc5", "O-O"] {
+            game.play_san(san).unwrap();
+        }
+
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("[Variant \"Chess960\"]\n[SetUp \"1\"]\n"));
+        assert!(pgn.ends_with("4. O-O *\n"));
+
+        let other = Game::from_pgn(&pgn).unwrap();
+        assert!(other.get_start_board().is_chess960());
+        assert_eq!(other.to_fen(), game.to_fen());
+    }
+
+    #[test]
+    fn test_write_crazyhouse() {
+        let mut game = Game::with_variant(crate::variant::Crazyhouse);
+        for san in ["e4", "d5", "exd5", "Qxd5", "Nc3", "Qa5", "P@d5"] {
+            game.play_san(san).unwrap();
+        }
+
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("[Variant \"Crazyhouse\"]\n"));
+        assert!(pgn.ends_with("4. P@d5 *\n"));
+
+        let other = Game::from_pgn(&pgn).unwrap();
+        assert!(other.get_start_board().is_crazyhouse());
+        assert_eq!(other.to_fen(), game.to_fen());
+    }
+
+    #[test]
+    fn test_write_resigned() {
+        let mut game = Game::new();
+        for san in ["e4", "e5", "Qh5", "Nc6"] {
+            game.play_san(san).unwrap();
+        }
+        game.resign(Side::White);
+
+        let pgn = game.to_pgn();
+        assert!(pgn.ends_with("2. Qh5 Nc6 0-1\n"));
+
+        let other = Game::from_pgn(&pgn).unwrap();
+        assert_eq!(other.get_game_state(), GameState::Ending(Some(Side::Black)));
+        assert_eq!(other.get_end_reason(), Some(EndReason::Unknown));
+        assert_eq!(other.to_pgn(), pgn);
+
+        // The Termination tag keeps the reason
+        let pgn = pgn.replace("\n\n", "\n[Termination \"time forfeit\"]\n\n");
+        let other = Game::from_pgn(&pgn).unwrap();
+        assert_eq!(other.get_end_reason(), Some(EndReason::Timeout));
+
+        let other = Game::from_pgn("[Result \"1/2-1/2\"]\n\n1. e4 e5 1/2-1/2\n").unwrap();
+        assert_eq!(other.get_game_state(), GameState::Ending(None));
+    }
+
+    #[test]
+    fn test_parse_annotations() {
+        let pgn = r#"% Exported by hand
+[Event "Annotated"]
+[Annotator "A \"quoted\" name"]
+
+1. e4 $1 {Best by test} e5 (1... c5 2. Nf3 (2. c3) d6) 2. Nf3!? ; King's Knight
+2... Nc6 3.Bb5 a6 {Ruy Lopez
+spanning lines} 1/2-1/2
+"#;
+        let games = PgnGame::parse_all(pgn).unwrap();
+        assert_eq!(games.len(), 1);
+
+        let game = &games[0];
+        assert_eq!(game.get_tag("Annotator"), Some("A \"quoted\" name"));
+        assert_eq!(game.moves.len(), 6);
+        assert_eq!(game.result, "1/2-1/2");
+        assert_eq!(
+            game.to_game().to_fen(),
+            "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 0 4"
+        );
+    }
+
+    #[test]
+    fn test_parse_multiple_games() {
+        let pgn = format!(
+            "{}\n[Event \"Fool's Mate\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n\n*\n",
+            OPERA_GAME
+        );
+        let games = PgnGame::parse_all(&pgn).unwrap();
+
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[0].moves.len(), 33);
+        assert_eq!(games[1].get_tag("Event"), Some("Fool's Mate"));
+        assert_eq!(
+            games[1].to_game().get_game_state(),
+            GameState::Ending(Some(Side::Black))
+        );
+        assert!(games[2].moves.is_empty());
+        assert_eq!(games[2].start_board.to_fen(), START_FEN);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |pgn: &str| PgnGame::parse_all(pgn).unwrap_err();
+
+        assert_eq!(
+            error("[Event \"?\"]\n\n1. e4 e5\n2. Ke3 *\n"),
+            PgnError {
+                line: 4,
+                kind: PgnErrorKind::IllegalMove(2, Side::White, String::from("Ke3")),
+            }
+        );
+        assert_eq!(
+            error("1. e4 e5 2. Nf3 Nf6\n3. Nc3 Nxe5 *").kind,
+            PgnErrorKind::IllegalMove(3, Side::Black, String::from("Nxe5"))
+        );
+        assert_eq!(
+            error("[Event \"?\"\n1. e4 *"),
+            PgnError {
+                line: 2,
+                kind: PgnErrorKind::InvalidTag(String::from("Event")),
+            }
+        );
+        assert_eq!(
+            error("1. e4\n{ never closed\n\n e5 *"),
+            PgnError {
+                line: 2,
+                kind: PgnErrorKind::UnterminatedComment,
+            }
+        );
+        assert_eq!(
+            error("1. e4 (1. d4 e5 *"),
+            PgnError {
+                line: 1,
+                kind: PgnErrorKind::UnterminatedVariation,
+            }
+        );
+        assert_eq!(
+            error("1. e4 e5\n\n[Event \"?\"]\n1. d4 *").kind,
+            PgnErrorKind::MissingResult
+        );
+        assert!(matches!(
+            error("[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n*").kind,
+            PgnErrorKind::InvalidFen(FenError::KingCount(..))
+        ));
+        assert_eq!(
+            Game::from_pgn("  \n").unwrap_err().kind,
+            PgnErrorKind::NoGame
+        );
+    }
+}
@@ -2,8 +2,12 @@
 use std::{
     fmt,
     ops::{Add, Sub},
+    str::FromStr,
 };
 
+// Chess Crate
+use super::moves::MoveError;
+
 const BOARD_SIZE: i8 = 8;
 const ASCII_UPPERCASE_A: i8 = 65;
 const ASCII_ZERO: i8 = 48;
@@ -13,7 +17,7 @@ const ASCII_ZERO: i8 = 48;
 //==================================================
 
 /// Used to position units on the board
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Pos {
     pub x: i8,
     pub y: i8,
@@ -37,18 +41,26 @@ impl Pos {
     /// * `x` out of bounds
     /// * `y` out of bounds
     pub const fn new(x: i8, y: i8) -> Self {
-        let pos = Self { x, y };
+        Self { x, y }
+    }
 
-        pos
+    /// Creates a new [`Pos`] from its notation, E.g. "D5" -> (4,5) -> (3,3)
+    ///
+    /// Panics when...
+    /// * `notation` is invalid
+    /// * [`Pos`] would be out of bounds
+    ///
+    /// See [`TryFrom`] for the variant without panic
+    pub fn from_notation(notation: &str) -> Self {
+        match notation.parse() {
+            Ok(pos) => pos,
+            Err(error) => panic!("Can't convert from &str: {} - {}", notation, error),
+        }
     }
 
     /// Gives back true if [`Pos`] is bounded by `BOARD_SIZE`
     pub fn is_onboard(&self) -> bool {
-        if (0..BOARD_SIZE).contains(&self.x) && (0..BOARD_SIZE).contains(&self.y) {
-            true
-        } else {
-            false
-        }
+        (0..BOARD_SIZE).contains(&self.x) && (0..BOARD_SIZE).contains(&self.y)
     }
 
     /// E.g. D4 -> D5
@@ -224,40 +236,38 @@ impl Pos {
             return positions;
         }
 
-        let num;
-        let step_pos;
         let calc_x = pos.x - self.x;
         let calc_y = pos.y - self.y;
 
-        match (calc_x, calc_y) {
-            (x, 0) if x != 0 => {
-                num = x.abs();
-                step_pos = Pos {
+        let (num, step_pos) = match (calc_x, calc_y) {
+            (x, 0) if x != 0 => (
+                x.abs(),
+                Pos {
                     x: x / x.abs(),
                     y: 0,
-                }
-            }
+                },
+            ),
 
-            (0, y) if y != 0 => {
-                num = y.abs();
-                step_pos = Pos {
+            (0, y) if y != 0 => (
+                y.abs(),
+                Pos {
                     x: 0,
                     y: y / y.abs(),
-                }
-            }
+                },
+            ),
 
-            (x, y) if x.abs() == y.abs() && x != 0 && y != 0 => {
-                num = x.abs();
-                step_pos = Pos {
+            (x, y) if x.abs() == y.abs() && x != 0 && y != 0 => (
+                x.abs(),
+                Pos {
                     x: x / x.abs(),
                     y: y / y.abs(),
-                }
-            }
+                },
+            ),
 
             _ => {
                 return positions;
             }
-        }
+        };
 
         let mut pos = *self;
         for _ in 1..num {
@@ -268,6 +278,16 @@ impl Pos {
         positions
     }
 
+    /// Gives back the chess notation of [`Pos`], E.g. (3,3) -> "d5"
+    ///
+    /// See also [from_notation](#method.from_notation), [`FromStr`] and [`TryFrom`] for the other way
+    pub fn to_notation(&self) -> String {
+        let col = (ASCII_UPPERCASE_A + self.x) as u8 as char;
+        let row = BOARD_SIZE - self.y;
+
+        format!("{}{}", col.to_ascii_lowercase(), row)
+    }
+
     /// Gives back the absolute value variant of [`Pos`]
     pub fn abs(&self) -> Self {
         Self {
@@ -282,12 +302,6 @@ impl Pos {
     }
 }
 
-impl Default for Pos {
-    fn default() -> Self {
-        Self { x: 0, y: 0 }
-    }
-}
-
 impl Add for Pos {
     type Output = Pos;
     fn add(self, rhs: Self) -> Self {
@@ -310,23 +324,30 @@ impl Sub for Pos {
 
 impl fmt::Display for Pos {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
+        if self.is_onboard() {
+            write!(f, "{}", self.to_notation())
+        } else {
+            write!(f, "({}, {})", self.x, self.y)
+        }
     }
 }
 
-impl From<&str> for Pos {
+impl FromStr for Pos {
+    type Err = MoveError;
+
     /// E.g. "D5" -> (4,5) -> (3,3)
     ///
-    /// Panic when...
-    /// * `&str` argument is invalid
+    /// Fails when...
+    /// * `&str` argument is not a column letter and a row digit
     /// * [`Pos`] would be out of bounds
-    fn from(s: &str) -> Self {
-        if s.len() != 2 && !s.is_ascii() {
-            panic!("Can't convert from &str: {} - Length != 2 or Not ASCII", s);
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        if bytes.len() != 2 || !bytes[0].is_ascii_alphabetic() || !bytes[1].is_ascii_digit() {
+            return Err(MoveError::InvalidPos(s.to_owned()));
         }
 
-        let col = s.to_ascii_uppercase().as_bytes()[0] as i8;
-        let row = s.to_ascii_uppercase().as_bytes()[1] as i8;
+        let col = bytes[0].to_ascii_uppercase() as i8;
+        let row = bytes[1] as i8;
 
         // ASCII Space -> Array Space
         let pos = Self {
@@ -335,10 +356,19 @@ impl From<&str> for Pos {
         };
 
         if !pos.is_onboard() {
-            panic!("Can't convert from &str: {} - Pos Not On Board", pos);
+            return Err(MoveError::OffBoard(pos));
         }
 
-        pos
+        Ok(pos)
+    }
+}
+
+impl TryFrom<&str> for Pos {
+    type Error = MoveError;
+
+    /// E.g. "D5" -> (4,5) -> (3,3), the same as [`FromStr`]
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
     }
 }
 
@@ -363,114 +393,183 @@ impl From<(i8, i8)> for Pos {
 
 #[cfg(test)]
 mod tests_pos {
+    use crate::moves::MoveError;
     use crate::pos::Pos;
 
     #[test]
     fn test_up() {
-        assert_eq!(Pos::from("D5"), Pos::from("D4").up());
+        assert_eq!(Pos::from_notation("D5"), Pos::from_notation("D4").up());
     }
 
     #[test]
     fn test_up_left() {
-        assert_eq!(Pos::from("C5"), Pos::from("D4").up_left());
+        assert_eq!(Pos::from_notation("C5"), Pos::from_notation("D4").up_left());
     }
 
     #[test]
     fn test_up_right() {
-        assert_eq!(Pos::from("E5"), Pos::from("D4").up_right());
+        assert_eq!(
+            Pos::from_notation("E5"),
+            Pos::from_notation("D4").up_right()
+        );
     }
 
     #[test]
     fn test_down() {
-        assert_eq!(Pos::from("D3"), Pos::from("D4").down());
+        assert_eq!(Pos::from_notation("D3"), Pos::from_notation("D4").down());
     }
 
     #[test]
     fn test_down_left() {
-        assert_eq!(Pos::from("C3"), Pos::from("D4").down_left());
+        assert_eq!(
+            Pos::from_notation("C3"),
+            Pos::from_notation("D4").down_left()
+        );
     }
 
     #[test]
     fn test_down_right() {
-        assert_eq!(Pos::from("E3"), Pos::from("D4").down_right());
+        assert_eq!(
+            Pos::from_notation("E3"),
+            Pos::from_notation("D4").down_right()
+        );
     }
 
     #[test]
     fn test_left() {
-        assert_eq!(Pos::from("C4"), Pos::from("D4").left());
+        assert_eq!(Pos::from_notation("C4"), Pos::from_notation("D4").left());
     }
 
     #[test]
     fn test_right() {
-        assert_eq!(Pos::from("E4"), Pos::from("D4").right());
+        assert_eq!(Pos::from_notation("E4"), Pos::from_notation("D4").right());
     }
 
     #[test]
     fn test_checked() {
-        assert_eq!(Pos::from("D8"), Pos::from("D8").bounded_up());
-        assert_eq!(Pos::from("H4"), Pos::from("H4").bounded_right());
+        assert_eq!(
+            Pos::from_notation("D8"),
+            Pos::from_notation("D8").bounded_up()
+        );
+        assert_eq!(
+            Pos::from_notation("H4"),
+            Pos::from_notation("H4").bounded_right()
+        );
     }
 
     #[test]
     fn test_non_checked() {
-        assert_eq!(Pos::new(3, -1), Pos::from("D8").up());
-        assert_eq!(Pos::new(8, 4), Pos::from("H4").right());
+        assert_eq!(Pos::new(3, -1), Pos::from_notation("D8").up());
+        assert_eq!(Pos::new(8, 4), Pos::from_notation("H4").right());
     }
 
     //===========
 
     #[test]
     fn test_to1() {
-        assert_eq!(Pos::from("D2").to(&Pos::from("D2")), vec![]);
+        assert_eq!(
+            Pos::from_notation("D2").to(&Pos::from_notation("D2")),
+            vec![]
+        );
     }
 
     #[test]
     fn test_to2() {
-        assert_eq!(Pos::from("D2").to(&Pos::from("E4")), vec![]);
+        assert_eq!(
+            Pos::from_notation("D2").to(&Pos::from_notation("E4")),
+            vec![]
+        );
     }
 
     #[test]
     fn test_to3() {
         assert_eq!(
-            Pos::from("D5").to(&Pos::from("G8")),
-            vec!["E6".into(), "F7".into()]
+            Pos::from_notation("D5").to(&Pos::from_notation("G8")),
+            vec![Pos::from_notation("E6"), Pos::from_notation("F7")]
         );
     }
 
     #[test]
     fn test_to4() {
         assert_eq!(
-            Pos::from("H1").to(&Pos::from("A1")),
+            Pos::from_notation("H1").to(&Pos::from_notation("A1")),
             vec![
-                "G1".into(),
-                "F1".into(),
-                "E1".into(),
-                "D1".into(),
-                "C1".into(),
-                "B1".into(),
+                Pos::from_notation("G1"),
+                Pos::from_notation("F1"),
+                Pos::from_notation("E1"),
+                Pos::from_notation("D1"),
+                Pos::from_notation("C1"),
+                Pos::from_notation("B1"),
             ]
         );
     }
 
     //===========
 
+    #[test]
+    fn test_to_notation() {
+        assert_eq!(Pos::new(3, 3).to_notation(), "d5");
+        assert_eq!(Pos::from_notation("A1").to_notation(), "a1");
+        assert_eq!(Pos::from_notation("h8").to_notation(), "h8");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Pos::new(3, 3).to_string(), "d5");
+        assert_eq!(Pos::new(-1, 8).to_string(), "(-1, 8)");
+    }
+
     #[test]
     fn test_fromstr1() {
-        assert_eq!(Pos::from("D5"), Pos::new(3, 3));
+        assert_eq!(Pos::from_notation("D5"), Pos::new(3, 3));
     }
 
     #[test]
     fn test_fromstr2() {
-        assert_eq!(Pos::from("E6"), Pos::new(4, 2));
+        assert_eq!(Pos::from_notation("E6"), Pos::new(4, 2));
     }
 
     #[test]
     fn test_fromstr3() {
-        assert_eq!(Pos::from("F7"), Pos::new(5, 1));
+        assert_eq!(Pos::from_notation("F7"), Pos::new(5, 1));
     }
 
     #[test]
     fn test_fromstr4() {
-        assert_eq!(Pos::from("G8"), Pos::new(6, 0));
+        assert_eq!(Pos::from_notation("G8"), Pos::new(6, 0));
+    }
+
+    #[test]
+    fn test_try_from() {
+        assert_eq!(Pos::try_from("D5"), Ok(Pos::new(3, 3)));
+        assert_eq!(
+            Pos::try_from("z9"),
+            Err(MoveError::OffBoard(Pos::new(25, -1)))
+        );
+        assert_eq!(
+            Pos::try_from("A"),
+            Err(MoveError::InvalidPos(String::from("A")))
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("d5".parse(), Ok(Pos::new(3, 3)));
+        assert_eq!(
+            "D9".parse::<Pos>(),
+            Err(MoveError::OffBoard(Pos::new(3, -1)))
+        );
+        assert_eq!(
+            "I1".parse::<Pos>(),
+            Err(MoveError::OffBoard(Pos::new(8, 7)))
+        );
+        assert_eq!(
+            "D55".parse::<Pos>(),
+            Err(MoveError::InvalidPos(String::from("D55")))
+        );
+        assert_eq!(
+            "5D".parse::<Pos>(),
+            Err(MoveError::InvalidPos(String::from("5D")))
+        );
     }
 }
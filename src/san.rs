@@ -0,0 +1,522 @@
+// Standard Crate
+use std::{error, fmt};
+
+// Chess Crate
+use super::chess::Board;
+use super::fen::parse_pos;
+use super::game::Game;
+use super::moves::{Move, MoveKind};
+use super::pos::Pos;
+use super::unit::{eq_unit_type, Unit};
+
+/// Suffix of the En Passant captures
+const EN_PASSANT: &str = "e.p.";
+
+/// Separates the [`Unit`] and the target of a Crazyhouse drop, E.g. "N@f3"
+const DROP: char = '@';
+
+//==================================================
+//=== SAN: Move
+//==================================================
+
+impl Move {
+    /// Produces the Standard Algebraic Notation of the [`Move`] on the [`Board`] it is played on
+    ///
+    /// E.g. "Nbd7", "exd6 e.p.", "O-O-O", "e8=N+", "Qh4#", "P@f7+"
+    pub fn to_san(&self, board: &Board) -> String {
+        let san = encode(board, self);
+
+        if self.is_en_passant() {
+            format!("{} {}", san, EN_PASSANT)
+        } else {
+            san
+        }
+    }
+}
+
+impl Board {
+    /// Finds the legal [`Move`] of the side in the current turn written in Standard Algebraic Notation
+    ///
+    /// Check, mate and annotation suffixes are optional, E.g. "Qh4#", "e4!?", "exd6 e.p."
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        decode(self, san)
+    }
+}
+
+impl Game {
+    /// Plays a [`Move`] written in Standard Algebraic Notation, see [`Board::parse_san`]
+    pub fn play_san(&mut self, san: &str) -> Result<Move, SanError> {
+        let chess_move = self.board_state.parse_san(san)?;
+        if !self.play_move(&chess_move) {
+            return Err(SanError::Illegal(san.to_owned()));
+        }
+
+        Ok(chess_move)
+    }
+
+    /// Gives back the Standard Algebraic Notation of every [`Move`] played since the start of the game
+    ///
+    /// En Passant captures have no suffix, as in PGN
+    pub fn get_san_moves(&self) -> Vec<String> {
+        let mut board = self.get_start_board().clone();
+
+        self.history()
+            .iter()
+            .map(|move_image| {
+                let san = encode(&board, &move_image.chess_move);
+                board.make_move(&move_image.chess_move);
+                san
+            })
+            .collect()
+    }
+}
+
+//==================================================
+//=== LAN: Move
+//==================================================
+
+impl Move {
+    /// Produces the coordinate notation of the [`Move`] used by the UCI and XBoard protocols
+    ///
+    /// E.g. "e2e4", "e1g1" for a Castle, "e7e8q" for a promotion
+    ///
+    /// The King takes its own Rook on a Chess960 Castle, E.g. "e1h1", drops are written as in SAN, E.g. "N@f3"
+    pub fn to_lan(&self) -> String {
+        if self.is_drop() {
+            return encode_drop(self);
+        }
+
+        let promotion = self
+            .promotion
+            .map(|unit| unit.get_fen_char().to_ascii_lowercase().to_string())
+            .unwrap_or_default();
+
+        format!("{}{}{}", self.unit_pos, self.target_pos, promotion)
+    }
+}
+
+impl Board {
+    /// Finds the legal [`Move`] of the side in the current turn written in coordinate notation, see [`Move::to_lan`]
+    ///
+    /// A Pawn reaching the last row needs the promotion letter, E.g. "e7e8q"
+    pub fn parse_lan(&self, lan: &str) -> Result<Move, SanError> {
+        if lan.contains(DROP) {
+            return decode_drop(self, lan);
+        }
+
+        let (unit_pos, target_pos, promotion) = match (
+            lan.get(0..2).and_then(parse_pos),
+            lan.get(2..4).and_then(parse_pos),
+            lan.get(4..),
+        ) {
+            (Some(unit_pos), Some(target_pos), Some(promotion)) if promotion.len() <= 1 => {
+                (unit_pos, target_pos, promotion.chars().next())
+            }
+            _ => return Err(SanError::Invalid(lan.to_owned())),
+        };
+
+        self.legal_moves_from(&unit_pos)
+            .into_iter()
+            .find(|chess_move| {
+                chess_move.target_pos == target_pos
+                    && chess_move
+                        .promotion
+                        .map(|unit| unit.get_fen_char().to_ascii_lowercase())
+                        == promotion.map(|promotion| promotion.to_ascii_lowercase())
+            })
+            .ok_or_else(|| SanError::Illegal(lan.to_owned()))
+    }
+}
+
+//==================================================
+//=== SAN: Notation
+//==================================================
+
+/// Produces the Standard Algebraic Notation of a legal [`Move`] on the [`Board`]
+///
+/// E.g. "Nbd7", "exd6", "O-O-O", "e8=N+", "Qh4#", "P@f7+"
+pub(crate) fn encode(board: &Board, chess_move: &Move) -> String {
+    let mut san = match chess_move.kind {
+        MoveKind::CastleLeft => String::from("O-O-O"),
+        MoveKind::CastleRight => String::from("O-O"),
+        MoveKind::Drop => encode_drop(chess_move),
+        _ => encode_step(board, chess_move),
+    };
+
+    // Check and Mate
+    let mut board = board.clone();
+    if board.make_move(chess_move) {
+        let side = board.get_current_turn();
+
+        if board.test_checked_status(&side) {
+            if board.test_valid_step_available(&side) {
+                san.push('+');
+            } else {
+                san.push('#');
+            }
+        }
+    }
+
+    san
+}
+
+/// Produces the notation of a drop, the [`Unit`] type and the target, E.g. "N@f3", "P@e4"
+fn encode_drop(chess_move: &Move) -> String {
+    format!(
+        "{}{}{}",
+        chess_move.unit.get_fen_char().to_ascii_uppercase(),
+        DROP,
+        chess_move.target_pos.to_notation()
+    )
+}
+
+/// Produces the notation of a [`Move`] which is not a Castle or a drop, without check suffix
+fn encode_step(board: &Board, chess_move: &Move) -> String {
+    let mut san = String::new();
+    let unit_notation = chess_move.unit_pos.to_notation();
+
+    if let Unit::Pawn(..) = chess_move.unit {
+        if chess_move.is_capture() {
+            san.push_str(&unit_notation[..1]);
+        }
+    } else {
+        san.push(chess_move.unit.get_fen_char().to_ascii_uppercase());
+
+        // Disambiguation
+        let others: Vec<Pos> = board
+            .legal_moves(&chess_move.unit.get_side())
+            .iter()
+            .filter(|other| {
+                other.target_pos == chess_move.target_pos
+                    && !other.is_drop()
+                    && other.unit_pos != chess_move.unit_pos
+                    && eq_unit_type(&other.unit, &chess_move.unit)
+            })
+            .map(|other| other.unit_pos)
+            .collect();
+
+        if !others.is_empty() {
+            if others.iter().all(|pos| pos.x != chess_move.unit_pos.x) {
+                san.push_str(&unit_notation[..1]);
+            } else if others.iter().all(|pos| pos.y != chess_move.unit_pos.y) {
+                san.push_str(&unit_notation[1..]);
+            } else {
+                san.push_str(&unit_notation);
+            }
+        }
+    }
+
+    if chess_move.is_capture() {
+        san.push('x');
+    }
+
+    san.push_str(&chess_move.target_pos.to_notation());
+
+    if let Some(promotion) = chess_move.promotion {
+        san.push('=');
+        san.push(promotion.get_fen_char().to_ascii_uppercase());
+    }
+
+    san
+}
+
+/// Finds the legal [`Move`] on the [`Board`] written in Standard Algebraic Notation
+///
+/// Check, mate, annotation and En Passant suffixes are ignored, E.g. "Qh4#", "e4!?", "exd6 e.p."
+pub(crate) fn decode(board: &Board, notation: &str) -> Result<Move, SanError> {
+    let invalid = || SanError::Invalid(notation.to_owned());
+
+    let san = notation.trim();
+    let san = san.strip_suffix(EN_PASSANT).unwrap_or(san).trim_end();
+    let san = san.trim_end_matches(['+', '#', '!', '?']);
+    let moves = board.legal_moves(&board.get_current_turn());
+
+    // Castle
+    let castle = match san {
+        "O-O" | "0-0" => Some(MoveKind::CastleRight),
+        "O-O-O" | "0-0-0" => Some(MoveKind::CastleLeft),
+        _ => None,
+    };
+    if let Some(kind) = castle {
+        return moves
+            .into_iter()
+            .find(|chess_move| chess_move.kind == kind)
+            .ok_or_else(|| SanError::Illegal(notation.to_owned()));
+    }
+
+    // Drop
+    if san.contains(DROP) {
+        return decode_drop(board, san);
+    }
+
+    let mut chars: Vec<char> = san.chars().filter(|c| !matches!(c, 'x' | '=')).collect();
+
+    // Promotion
+    let promotion = match chars.last() {
+        Some(c) if matches!(c, 'Q' | 'R' | 'B' | 'N') => {
+            let promotion = Unit::from_fen_char(*c);
+            chars.pop();
+            promotion
+        }
+        _ => None,
+    };
+
+    // Target
+    if chars.len() < 2 {
+        return Err(invalid());
+    }
+    let target: String = chars.split_off(chars.len() - 2).into_iter().collect();
+    let target_pos = parse_pos(&target).ok_or_else(invalid)?;
+
+    // Unit Type
+    let unit = match chars.first() {
+        Some(c) if matches!(c, 'K' | 'Q' | 'R' | 'B' | 'N') => {
+            let unit = Unit::from_fen_char(*c).ok_or_else(invalid)?;
+            chars.remove(0);
+            unit
+        }
+        _ => Unit::PAWN,
+    };
+
+    // Disambiguation
+    let mut col = None;
+    let mut row = None;
+    for c in chars {
+        match c {
+            'a'..='h' if col.is_none() => col = Some(c),
+            '1'..='8' if row.is_none() => row = Some(c),
+            _ => return Err(invalid()),
+        }
+    }
+
+    let mut candidates = moves.into_iter().filter(|chess_move| {
+        let unit_notation = chess_move.unit_pos.to_notation();
+
+        chess_move.target_pos == target_pos
+            && !chess_move.is_drop()
+            && eq_unit_type(&chess_move.unit, &unit)
+            && col.is_none_or(|col| unit_notation.starts_with(col))
+            && row.is_none_or(|row| unit_notation.ends_with(row))
+            && match (chess_move.promotion, promotion) {
+                (Some(unit1), Some(unit2)) => eq_unit_type(&unit1, &unit2),
+                (None, None) => true,
+                _ => false,
+            }
+    });
+
+    match (candidates.next(), candidates.next()) {
+        (Some(chess_move), None) => Ok(chess_move),
+        (Some(_), Some(_)) => Err(SanError::Ambiguous(notation.to_owned())),
+        _ => Err(SanError::Illegal(notation.to_owned())),
+    }
+}
+
+/// Finds the legal drop on the [`Board`] written as the [`Unit`] type and the target, E.g. "N@f3"
+///
+/// The Pawn letter is optional, E.g. "@e4"
+fn decode_drop(board: &Board, notation: &str) -> Result<Move, SanError> {
+    let invalid = || SanError::Invalid(notation.to_owned());
+
+    let (unit, target) = notation.split_once(DROP).ok_or_else(invalid)?;
+    let mut chars = unit.chars();
+    let unit = match (chars.next(), chars.next()) {
+        (None, _) => Unit::PAWN,
+        (Some(c), None) if "PNBRQ".contains(c.to_ascii_uppercase()) => {
+            Unit::from_fen_char(c).ok_or_else(invalid)?
+        }
+        _ => return Err(invalid()),
+    };
+    let target_pos = parse_pos(target).ok_or_else(invalid)?;
+
+    board
+        .try_find_drop(&unit, &target_pos)
+        .map_err(|_| SanError::Illegal(notation.to_owned()))
+}
+
+//==================================================
+//=== SAN: Error
+//==================================================
+
+/// Errors produced while parsing Standard Algebraic Notation
+///
+/// * `Invalid` the notation is malformed
+/// * `Ambiguous` more than one legal move fits the notation
+/// * `Illegal` no legal move fits the notation
+#[derive(Clone, Debug, PartialEq)]
+pub enum SanError {
+    Invalid(String),
+    Ambiguous(String),
+    Illegal(String),
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(san) => write!(f, "Invalid notation: '{}'", san),
+            Self::Ambiguous(san) => write!(f, "Ambiguous move: '{}'", san),
+            Self::Illegal(san) => write!(f, "Illegal move: '{}'", san),
+        }
+    }
+}
+
+impl error::Error for SanError {}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_san {
+    use super::*;
+    use crate::unit::Side;
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+    /// Gives back the SAN of the only legal [`Move`] from `unit_pos` to `target_pos`
+    fn to_san(fen: &str, unit_pos: &str, target_pos: &str) -> String {
+        let board = Board::from_fen(fen).unwrap();
+        let chess_move = board
+            .legal_moves_from(&Pos::from_notation(unit_pos))
+            .into_iter()
+            .find(|chess_move| chess_move.target_pos == Pos::from_notation(target_pos))
+            .unwrap();
+
+        chess_move.to_san(&board)
+    }
+
+    #[test]
+    fn test_disambiguation() {
+        let fen = "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1";
+        assert_eq!(to_san(fen, "B1", "D2"), "Nbd2");
+
+        let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+        assert_eq!(to_san(fen, "A1", "A3"), "R1a3");
+
+        let fen = "4k3/8/8/2N5/8/2N3N1/8/4K3 w - - 0 1";
+        assert_eq!(to_san(fen, "C3", "E4"), "Nc3e4");
+        assert_eq!(to_san(fen, "C5", "E4"), "N5e4");
+        assert_eq!(to_san(fen, "G3", "E4"), "Nge4");
+    }
+
+    #[test]
+    fn test_special_moves() {
+        let fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+        assert_eq!(to_san(fen, "E5", "D6"), "exd6 e.p.");
+
+        let fen = "3k4/8/8/8/8/8/8/R3K3 w Q - 0 1";
+        assert_eq!(to_san(fen, "E1", "C1"), "O-O-O+");
+
+        let board = Board::from_fen("8/4P1k1/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let chess_move = board.parse_san("e8=N").unwrap();
+        assert_eq!(chess_move.promotion, Some(Unit::Knight(Side::White)));
+        assert_eq!(chess_move.to_san(&board), "e8=N+");
+    }
+
+    #[test]
+    fn test_mate() {
+        let mut game = Game::new();
+        for san in ["f3", "e5", "g4"] {
+            game.play_san(san).unwrap();
+        }
+
+        let chess_move = game.board_state.parse_san("Qh4").unwrap();
+        assert_eq!(chess_move.to_san(&game.board_state), "Qh4#");
+
+        game.play_san("Qh4#").unwrap();
+        assert_eq!(game.get_san_moves(), ["f3", "e5", "g4", "Qh4#"]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/R3K2R w KQ d6 0 1").unwrap();
+
+        assert!(board.parse_san("exd6 e.p.").unwrap().is_en_passant());
+        assert!(board.parse_san("exd6").unwrap().is_en_passant());
+        assert_eq!(board.parse_san("0-0-0").unwrap().kind, MoveKind::CastleLeft);
+        assert_eq!(
+            board.parse_san("O-O+!").unwrap().kind,
+            MoveKind::CastleRight
+        );
+        assert_eq!(
+            board.parse_san("Rab1").unwrap().unit_pos,
+            Pos::from_notation("A1")
+        );
+
+        let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.parse_san("b8Q").unwrap().promotion,
+            Some(Unit::Queen(Side::White))
+        );
+        assert_eq!(
+            board.parse_san("b8"),
+            Err(SanError::Illegal(String::from("b8")))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.parse_san("Nd2"),
+            Err(SanError::Ambiguous(String::from("Nd2")))
+        );
+        assert_eq!(
+            board.parse_san("Nbd3"),
+            Err(SanError::Illegal(String::from("Nbd3")))
+        );
+        assert_eq!(
+            board.parse_san("Nz9"),
+            Err(SanError::Invalid(String::from("Nz9")))
+        );
+        assert_eq!(board.parse_san(""), Err(SanError::Invalid(String::new())));
+        assert_eq!(
+            board.parse_san("O-O"),
+            Err(SanError::Illegal(String::from("O-O")))
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let board = Board::from_fen(KIWIPETE).unwrap();
+
+        for chess_move in board.legal_moves(&Side::White) {
+            let san = chess_move.to_san(&board);
+            assert_eq!(board.parse_san(&san), Ok(chess_move), "{}", san);
+        }
+    }
+
+    #[test]
+    fn test_lan() {
+        let board = Board::from_fen("4k3/1P6/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+
+        let chess_move = board.parse_lan("e1c1").unwrap();
+        assert!(chess_move.is_castle());
+        assert_eq!(chess_move.to_lan(), "e1c1");
+
+        let chess_move = board.parse_lan("b7b8n").unwrap();
+        assert_eq!(chess_move.promotion, Some(Unit::Knight(Side::White)));
+        assert_eq!(chess_move.to_lan(), "b7b8n");
+
+        assert_eq!(
+            board.parse_lan("b7b8"),
+            Err(SanError::Illegal(String::from("b7b8")))
+        );
+        assert_eq!(
+            board.parse_lan("e1e3"),
+            Err(SanError::Illegal(String::from("e1e3")))
+        );
+        assert_eq!(
+            board.parse_lan("e9e8"),
+            Err(SanError::Invalid(String::from("e9e8")))
+        );
+        assert_eq!(
+            board.parse_lan("a1a2qq"),
+            Err(SanError::Invalid(String::from("a1a2qq")))
+        );
+
+        for chess_move in board.legal_moves(&Side::White) {
+            assert_eq!(board.parse_lan(&chess_move.to_lan()), Ok(chess_move));
+        }
+    }
+}
@@ -0,0 +1,2452 @@
+// Standard Crate
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::ops::Neg;
+use std::path::Path;
+use std::{error, fmt};
+
+// External Crate
+use memmap2::Mmap;
+
+// Chess Crate
+use super::chess::Board;
+use super::moves::Move;
+use super::unit::{eq_unit_type, Side, Unit};
+
+/// Most [`Unit`]s on a [`Board`] the Syzygy format can hold
+const MAX_UNITS: usize = 7;
+
+/// First bytes of a WDL table, the `.rtbw` files
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+
+/// First bytes of a DTZ table, the `.rtbz` files
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+/// Header flag of a table which stores both sides, each has its own order of the units
+const SPLIT_FLAG: u8 = 1;
+
+/// Header flag of a table with Pawns, it is split into 4 parts by the column of the leading Pawn
+const PAWNS_FLAG: u8 = 2;
+
+/// Flag of a DTZ table storing the positions of the second side in the current turn
+const STM_FLAG: u8 = 1;
+
+/// Flag of a DTZ table which maps the stored values through a map for each result
+const MAPPED_FLAG: u8 = 2;
+
+/// Flag of a DTZ table storing the wins in plies, otherwise in full moves
+const WIN_PLIES_FLAG: u8 = 4;
+
+/// Flag of a DTZ table storing the losses in plies, otherwise in full moves
+const LOSS_PLIES_FLAG: u8 = 8;
+
+/// Flag of a DTZ table with 16-bit maps
+const WIDE_FLAG: u8 = 16;
+
+/// Flag of a table part where every position holds the same value
+const SINGLE_VALUE_FLAG: u8 = 128;
+
+/// Letters of the [`Unit`] types in the table names, indexed by the Syzygy type - 1
+const UNIT_LETTERS: [char; 6] = ['P', 'N', 'B', 'R', 'Q', 'K'];
+
+/// Index of the DTZ map of each [`Wdl`], indexed by the [`Wdl`] value + 2
+const WDL_MAPS: [usize; 5] = [1, 3, 0, 2, 0];
+
+//==================================================
+//=== Tablebase
+//==================================================
+
+/// Syzygy endgame tablebases, read from the `.rtbw` and `.rtbz` files of a directory
+///
+/// The WDL tables give back the result of a position, the DTZ tables the plies to the next capture or Pawn step,
+/// together they give back the perfect [`Move`], see [get_best_move](#method.get_best_move)
+///
+/// * `indices` the tables for calculating the index of a position
+/// * `wdl_tables` WDL tables by their name, E.g. `KQvK`
+/// * `dtz_tables` DTZ tables by their name
+#[derive(Debug)]
+pub struct Tablebase {
+    indices: Indices,
+    wdl_tables: HashMap<String, Table>,
+    dtz_tables: HashMap<String, Table>,
+}
+
+impl Tablebase {
+    /// Opens every table in the directory at `dir`, the files are memory mapped
+    ///
+    /// Files named after the units of each side, E.g. `KQvK.rtbw`, are opened, other files are skipped
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, TablebaseError> {
+        let mut tablebase = Self {
+            indices: Indices::new(),
+            wdl_tables: HashMap::new(),
+            dtz_tables: HashMap::new(),
+        };
+
+        let entries = fs::read_dir(dir).map_err(|error| TablebaseError::Io(error.to_string()))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|error| TablebaseError::Io(error.to_string()))?
+                .path();
+
+            let (Some(name), Some(extension)) = (
+                path.file_stem().and_then(|name| name.to_str()),
+                path.extension().and_then(|extension| extension.to_str()),
+            ) else {
+                continue;
+            };
+            let kind = match extension {
+                "rtbw" => TableKind::Wdl,
+                "rtbz" => TableKind::Dtz,
+                _ => continue,
+            };
+            let Some(material) = Material::parse(name) else {
+                continue;
+            };
+
+            let file = File::open(&path).map_err(|error| TablebaseError::Io(error.to_string()))?;
+            // SAFETY: The tables are only read, they are not expected to change while they are open
+            let mmap = unsafe { Mmap::map(&file) }
+                .map_err(|error| TablebaseError::Io(error.to_string()))?;
+
+            let table = Table::parse(mmap, &tablebase.indices, material, kind)
+                .ok_or_else(|| TablebaseError::InvalidTable(kind.file_name(name)))?;
+            match kind {
+                TableKind::Wdl => tablebase.wdl_tables.insert(name.to_string(), table),
+                TableKind::Dtz => tablebase.dtz_tables.insert(name.to_string(), table),
+            };
+        }
+
+        Ok(tablebase)
+    }
+
+    /// Gives back the most [`Unit`]s of the opened tables, Boards with more can't be probed
+    pub fn get_max_units(&self) -> usize {
+        self.wdl_tables
+            .values()
+            .map(|table| table.layout.material.get_unit_count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Gives back the result of the position for the side in the current turn
+    ///
+    /// Fails when the [`Board`] has too many [`Unit`]s, castling rights, variant rules
+    /// or a table of the position or of a capture is missing
+    pub fn probe_wdl(&self, board: &Board) -> Result<Wdl, TablebaseError> {
+        self.test_board(board)?;
+
+        self.search(board, false).map(|(wdl, _)| wdl)
+    }
+
+    /// Gives back the plies to the next capture or Pawn step with perfect play, DTZ for short
+    ///
+    /// Positive when the side in the current turn wins, negative when it loses, 0 on a draw,
+    /// cursed wins and blessed losses are 100 plies further, E.g. 101 or -101
+    ///
+    /// Fails like [probe_wdl](#method.probe_wdl)
+    pub fn probe_dtz(&self, board: &Board) -> Result<i32, TablebaseError> {
+        self.test_board(board)?;
+
+        self.dtz(board)
+    }
+
+    /// Gives back every legal [`Move`] of the side in the current turn with its result, in the order of the [`Board`]
+    ///
+    /// Fails like [probe_wdl](#method.probe_wdl)
+    pub fn get_moves(&self, board: &Board) -> Result<Vec<TablebaseMove>, TablebaseError> {
+        self.test_board(board)?;
+
+        let side = board.get_current_turn();
+        let mut moves = Vec::new();
+
+        for chess_move in board.legal_moves(&side) {
+            let mut next = board.clone();
+            next.apply_move(&chess_move);
+
+            let wdl = -self.search(&next, false)?.0;
+            let dtz = if chess_move.is_capture() || eq_unit_type(&chess_move.unit, &Unit::PAWN) {
+                before_zeroing(wdl)
+            } else if !next.test_valid_step_available(&side.oppose())
+                && next.test_checked_status(&side.oppose())
+            {
+                1
+            } else {
+                let dtz = -self.dtz(&next)?;
+                dtz + dtz.signum()
+            };
+
+            moves.push(TablebaseMove {
+                chess_move,
+                wdl,
+                dtz,
+            });
+        }
+
+        Ok(moves)
+    }
+
+    /// Gives back the [`Move`] which reaches the best result the fastest, the DTZ-optimal one
+    ///
+    /// * Win: the winning [`Move`] with the lowest DTZ
+    /// * Draw: a drawing [`Move`]
+    /// * Loss: the losing [`Move`] which delays the loss the longest, the lowest DTZ
+    ///
+    /// Returns `None` when the side in the current turn has no legal [`Move`], fails like [probe_wdl](#method.probe_wdl)
+    pub fn get_best_move(&self, board: &Board) -> Result<Option<TablebaseMove>, TablebaseError> {
+        let moves = self.get_moves(board)?;
+
+        Ok(moves
+            .into_iter()
+            .reduce(|best, next| match next.cmp_result(&best) {
+                Ordering::Greater => next,
+                _ => best,
+            }))
+    }
+
+    /// Fails when the [`Board`] can't be found in the tables
+    fn test_board(&self, board: &Board) -> Result<(), TablebaseError> {
+        let unit_count = board.get_bitboards().get_occupied().count() as usize;
+        if unit_count > self.get_max_units().max(2) {
+            return Err(TablebaseError::TooManyUnits(unit_count));
+        }
+
+        if board.get_castling_rights().iter().any(|right| *right) {
+            return Err(TablebaseError::Castling);
+        }
+
+        if board.is_crazyhouse() || board.is_three_check() {
+            return Err(TablebaseError::Variant);
+        }
+
+        Ok(())
+    }
+
+    /// Gives back the result of the position and whether a capture or Pawn step reaches it
+    ///
+    /// The tables don't store the positions where a capture wins, nor the En Passant captures,
+    /// so the captures are searched first, with `zeroing` the Pawn steps too
+    fn search(&self, board: &Board, zeroing: bool) -> Result<(Wdl, bool), TablebaseError> {
+        let moves = board.legal_moves(&board.get_current_turn());
+        let mut best = Wdl::Loss;
+        let mut count = 0;
+
+        for chess_move in &moves {
+            if !(chess_move.is_capture() || zeroing && eq_unit_type(&chess_move.unit, &Unit::PAWN))
+            {
+                continue;
+            }
+            count += 1;
+
+            let mut next = board.clone();
+            next.apply_move(chess_move);
+
+            let wdl = -self.search(&next, false)?.0;
+            if wdl > best {
+                best = wdl;
+
+                if wdl == Wdl::Win {
+                    return Ok((wdl, true));
+                }
+            }
+        }
+
+        // Every legal Move was searched, the stored value may be wrong
+        let searched = count > 0 && count == moves.len();
+        let wdl = match searched {
+            true => best,
+            false => self.probe_table_wdl(board)?,
+        };
+
+        if best >= wdl {
+            return Ok((best, best > Wdl::Draw || searched));
+        }
+
+        Ok((wdl, false))
+    }
+
+    /// Gives back the DTZ of the position, see [probe_dtz](#method.probe_dtz)
+    fn dtz(&self, board: &Board) -> Result<i32, TablebaseError> {
+        let (wdl, zeroing) = self.search(board, true)?;
+
+        // The DTZ tables don't store the draws, nor the positions where a capture or Pawn step is the best
+        if wdl == Wdl::Draw {
+            return Ok(0);
+        }
+        if zeroing {
+            return Ok(before_zeroing(wdl));
+        }
+
+        if let Some(dtz) = self.probe_table_dtz(board, wdl)? {
+            let cursed = matches!(wdl, Wdl::CursedWin | Wdl::BlessedLoss);
+            return Ok((dtz + if cursed { 100 } else { 0 }) * wdl.signum());
+        }
+
+        // The table stores the other side in the current turn, the best Move gives back the DTZ
+        let side = board.get_current_turn();
+        let mut best = i32::MAX;
+
+        for chess_move in board.legal_moves(&side) {
+            let zeroing = chess_move.is_capture() || eq_unit_type(&chess_move.unit, &Unit::PAWN);
+
+            let mut next = board.clone();
+            next.apply_move(&chess_move);
+
+            let mut dtz = match zeroing {
+                true => -before_zeroing(self.search(&next, false)?.0),
+                false => -self.dtz(&next)?,
+            };
+
+            // Checkmate counts as a capture
+            if dtz == 1
+                && next.test_checked_status(&side.oppose())
+                && !next.test_valid_step_available(&side.oppose())
+            {
+                best = 1;
+            }
+
+            if !zeroing {
+                dtz += dtz.signum();
+            }
+
+            if dtz < best && dtz.signum() == wdl.signum() {
+                best = dtz;
+            }
+        }
+
+        // No legal Move, checkmate
+        Ok(if best == i32::MAX { -1 } else { best })
+    }
+
+    /// Gives back the result stored in the WDL table of the position
+    fn probe_table_wdl(&self, board: &Board) -> Result<Wdl, TablebaseError> {
+        let units = collect_units(board);
+        if units.len() == 2 {
+            return Ok(Wdl::Draw);
+        }
+
+        let (table, flipped) = find_table(&self.wdl_tables, &units, TableKind::Wdl)?;
+        let black_to_move = board.get_current_turn() == Side::Black;
+        let (file, side, index) =
+            table
+                .layout
+                .encode(&self.indices, &units, black_to_move, flipped);
+
+        let value = table.pairs[file][side]
+            .decompress(&table.mmap, index)
+            .ok_or_else(|| TablebaseError::InvalidTable(table.get_file_name()))?;
+
+        Ok(Wdl::from_value(value as i32 - 2))
+    }
+
+    /// Gives back the DTZ stored in the DTZ table of the position, without the sign of the `wdl`
+    ///
+    /// Returns `None` when the table stores the other side in the current turn
+    fn probe_table_dtz(&self, board: &Board, wdl: Wdl) -> Result<Option<i32>, TablebaseError> {
+        let units = collect_units(board);
+        let (table, flipped) = find_table(&self.dtz_tables, &units, TableKind::Dtz)?;
+        let black_to_move = board.get_current_turn() == Side::Black;
+        let (file, side, index) =
+            table
+                .layout
+                .encode(&self.indices, &units, black_to_move, flipped);
+
+        let pairs = &table.pairs[file][0];
+        let material = &table.layout.material;
+        if (pairs.flags & STM_FLAG) as usize != side
+            && (!material.is_symmetric() || material.has_pawns())
+        {
+            return Ok(None);
+        }
+
+        pairs
+            .decompress(&table.mmap, index)
+            .and_then(|value| table.map_dtz(pairs, value, wdl))
+            .map(Some)
+            .ok_or_else(|| TablebaseError::InvalidTable(table.get_file_name()))
+    }
+}
+
+/// Gives back the DTZ of a position whose best [`Move`] is a capture or a Pawn step
+fn before_zeroing(wdl: Wdl) -> i32 {
+    match wdl {
+        Wdl::Win => 1,
+        Wdl::CursedWin => 101,
+        Wdl::Draw => 0,
+        Wdl::BlessedLoss => -101,
+        Wdl::Loss => -1,
+    }
+}
+
+/// Gives back the Syzygy code and square of each [`Unit`] on the [`Board`]
+///
+/// The codes go 1-6 from Pawn to King for White, +8 for Black, the squares `8 * rank + column` from A1 = 0 to H8 = 63
+fn collect_units(board: &Board) -> Vec<(u8, u8)> {
+    let mut units = Vec::with_capacity(MAX_UNITS);
+
+    for (y, row) in board.get_squares().iter().enumerate() {
+        for (x, unit) in row.iter().enumerate() {
+            if let Some(unit) = unit {
+                units.push((unit_code(unit), ((7 - y) * 8 + x) as u8));
+            }
+        }
+    }
+
+    units
+}
+
+/// Gives back the Syzygy code of the `unit`, 1-6 from Pawn to King for White, +8 for Black
+fn unit_code(unit: &Unit) -> u8 {
+    let code = match unit {
+        Unit::Pawn(..) => 1,
+        Unit::Knight(_) => 2,
+        Unit::Bishop(_) => 3,
+        Unit::Rook(..) => 4,
+        Unit::Queen(_) => 5,
+        Unit::King(..) => 6,
+    };
+
+    match unit.get_side() {
+        Side::White => code,
+        Side::Black => code + 8,
+    }
+}
+
+/// Gives back the table of the `units` and whether the sides are switched in it,
+/// the tables are named after the stronger side first
+fn find_table<'a>(
+    tables: &'a HashMap<String, Table>,
+    units: &[(u8, u8)],
+    kind: TableKind,
+) -> Result<(&'a Table, bool), TablebaseError> {
+    let material = Material::from_units(units);
+
+    if let Some(table) = tables.get(&material.get_name()) {
+        return Ok((table, false));
+    }
+    if let Some(table) = tables.get(&material.flip().get_name()) {
+        return Ok((table, true));
+    }
+
+    let name = match material.is_stronger() {
+        true => material.get_name(),
+        false => material.flip().get_name(),
+    };
+    Err(TablebaseError::MissingTable(kind.file_name(&name)))
+}
+
+//==================================================
+//=== Tablebase: Result
+//==================================================
+
+/// The result of a position with perfect play, for the side in the current turn
+///
+/// The fifty-move rule turns a cursed win into a draw, and saves a blessed loss
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Wdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+impl Wdl {
+    /// Produces a [`Wdl`] from its value, -2 for a loss to 2 for a win
+    fn from_value(value: i32) -> Self {
+        match value {
+            ..=-2 => Self::Loss,
+            -1 => Self::BlessedLoss,
+            0 => Self::Draw,
+            1 => Self::CursedWin,
+            _ => Self::Win,
+        }
+    }
+
+    /// Gives back the value of the [`Wdl`], -2 for a loss to 2 for a win
+    fn get_value(&self) -> i32 {
+        *self as i32 - 2
+    }
+
+    /// Gives back -1 for a loss, 0 for a draw, 1 for a win
+    fn signum(&self) -> i32 {
+        self.get_value().signum()
+    }
+}
+
+impl Neg for Wdl {
+    type Output = Self;
+
+    /// The same result for the other side
+    fn neg(self) -> Self {
+        Self::from_value(-self.get_value())
+    }
+}
+
+impl fmt::Display for Wdl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Loss => write!(f, "Loss"),
+            Self::BlessedLoss => write!(f, "Blessed Loss"),
+            Self::Draw => write!(f, "Draw"),
+            Self::CursedWin => write!(f, "Cursed Win"),
+            Self::Win => write!(f, "Win"),
+        }
+    }
+}
+
+/// A legal [`Move`] with its result, see [`Tablebase::get_moves`]
+///
+/// * `chess_move` the [`Move`] of the side in the current turn
+/// * `wdl` the result after the [`Move`], for the side which makes it
+/// * `dtz` the DTZ before the [`Move`] when it is made, see [`Tablebase::probe_dtz`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TablebaseMove {
+    pub chess_move: Move,
+    pub wdl: Wdl,
+    pub dtz: i32,
+}
+
+impl TablebaseMove {
+    /// Compares the results, a faster win or a slower loss is better, see [`Tablebase::get_best_move`]
+    ///
+    /// The DTZ of a loss is negative, so the lower DTZ is better for both
+    fn cmp_result(&self, other: &Self) -> Ordering {
+        self.wdl
+            .cmp(&other.wdl)
+            .then_with(|| other.dtz.cmp(&self.dtz))
+    }
+}
+
+//==================================================
+//=== Tablebase: Table
+//==================================================
+
+/// Kind of a table file
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TableKind {
+    Wdl,
+    Dtz,
+}
+
+impl TableKind {
+    /// Gives back the file name of the table `name`, E.g. `KQvK.rtbw`
+    fn file_name(&self, name: &str) -> String {
+        match self {
+            Self::Wdl => format!("{}.rtbw", name),
+            Self::Dtz => format!("{}.rtbz", name),
+        }
+    }
+}
+
+/// A memory mapped table file
+///
+/// * `layout` the order of the units in the index, by column of the leading Pawn and side in the current turn
+/// * `pairs` the compressed values, in the same order as the `layout`
+/// * `map` offset of the DTZ maps, see [`PairsData::map_idx`]
+/// * `mmap` the bytes of the file
+#[derive(Debug)]
+struct Table {
+    layout: Layout,
+    pairs: Vec<Vec<PairsData>>,
+    map: usize,
+    mmap: Mmap,
+}
+
+impl Table {
+    /// Reads the header of the table in `mmap`, returns `None` when the file is not a valid table of the `material`
+    fn parse(mmap: Mmap, indices: &Indices, material: Material, kind: TableKind) -> Option<Self> {
+        let data = &mmap[..];
+        let (layout, mut pos) = Layout::parse(data, indices, material, kind)?;
+
+        let mut pairs = Vec::with_capacity(layout.groups.len());
+        for groups in &layout.groups {
+            let mut file_pairs = Vec::with_capacity(groups.len());
+            for group in groups {
+                file_pairs.push(PairsData::parse(data, &mut pos, group.get_size())?);
+            }
+            pairs.push(file_pairs);
+        }
+
+        // The DTZ maps
+        let map = pos;
+        if kind == TableKind::Dtz {
+            for file_pairs in &mut pairs {
+                let pairs = &mut file_pairs[0];
+                if pairs.flags & MAPPED_FLAG == 0 {
+                    continue;
+                }
+
+                if pairs.flags & WIDE_FLAG != 0 {
+                    pos += pos & 1;
+                    for map_idx in &mut pairs.map_idx {
+                        *map_idx = (pos - map) / 2 + 1;
+                        pos += 2 * read_u16(data, pos)? as usize + 2;
+                    }
+                } else {
+                    for map_idx in &mut pairs.map_idx {
+                        *map_idx = pos - map + 1;
+                        pos += *data.get(pos)? as usize + 1;
+                    }
+                }
+            }
+            pos += pos & 1;
+        }
+
+        for pairs in pairs.iter_mut().flatten() {
+            pairs.sparse_index = pos;
+            pos += pairs.sparse_index_size * 6;
+        }
+        for pairs in pairs.iter_mut().flatten() {
+            pairs.block_length = pos;
+            pos += pairs.block_length_size * 2;
+        }
+        for pairs in pairs.iter_mut().flatten() {
+            pos = (pos + 0x3F) & !0x3F;
+            pairs.data = pos;
+            pos += pairs.blocks * pairs.block_size;
+        }
+
+        Some(Self {
+            layout,
+            pairs,
+            map,
+            mmap,
+        })
+    }
+
+    /// Gives back the file name of the table, E.g. `KQvK.rtbw`
+    fn get_file_name(&self) -> String {
+        self.layout.kind.file_name(&self.layout.material.get_name())
+    }
+
+    /// Turns the stored `value` of a DTZ table into plies, returns `None` when the map is out of the file
+    fn map_dtz(&self, pairs: &PairsData, value: u16, wdl: Wdl) -> Option<i32> {
+        let mut value = value as usize;
+
+        if pairs.flags & MAPPED_FLAG != 0 {
+            let map_idx = pairs.map_idx[WDL_MAPS[(wdl.get_value() + 2) as usize]];
+            value = match pairs.flags & WIDE_FLAG != 0 {
+                true => read_u16(&self.mmap, self.map + 2 * (map_idx + value))? as usize,
+                false => *self.mmap.get(self.map + map_idx + value)? as usize,
+            };
+        }
+
+        let moves = match wdl {
+            Wdl::Win => pairs.flags & WIN_PLIES_FLAG == 0,
+            Wdl::Loss => pairs.flags & LOSS_PLIES_FLAG == 0,
+            _ => true,
+        };
+
+        Some(
+            if moves {
+                value as i32 * 2
+            } else {
+                value as i32
+            } + 1,
+        )
+    }
+}
+
+//==================================================
+//=== Tablebase: Layout
+//==================================================
+
+/// The units of a table, in the order of the index
+///
+/// * `material` the units of each side, the stronger side first
+/// * `kind` WDL or DTZ
+/// * `groups` order of the units by column of the leading Pawn, A-D, and side in the current turn,
+///   a WDL table stores both sides unless they have the same units
+#[derive(Clone, Debug)]
+struct Layout {
+    material: Material,
+    kind: TableKind,
+    groups: Vec<Vec<Groups>>,
+}
+
+impl Layout {
+    /// Reads the order of the units from the header of a table,
+    /// gives back the [`Layout`] and the offset of the compressed data
+    fn parse(
+        data: &[u8],
+        indices: &Indices,
+        material: Material,
+        kind: TableKind,
+    ) -> Option<(Self, usize)> {
+        let magic = match kind {
+            TableKind::Wdl => WDL_MAGIC,
+            TableKind::Dtz => DTZ_MAGIC,
+        };
+        if data.get(0..4)? != magic {
+            return None;
+        }
+
+        let flags = *data.get(4)?;
+        if (flags & PAWNS_FLAG != 0) != material.has_pawns()
+            || (flags & SPLIT_FLAG != 0) == material.is_symmetric()
+        {
+            return None;
+        }
+
+        let unit_count = material.get_unit_count();
+        let both_pawns = material.has_pawns() && material.get_pawn_counts()[1] > 0;
+        let sides = match kind {
+            TableKind::Wdl if !material.is_symmetric() => 2,
+            _ => 1,
+        };
+        let files = if material.has_pawns() { 4 } else { 1 };
+
+        let mut pos = 5;
+        let mut groups = Vec::with_capacity(files);
+        for file in 0..files {
+            let order = *data.get(pos)?;
+            let remaining = match both_pawns {
+                true => *data.get(pos + 1)?,
+                false => 0xFF,
+            };
+            let orders = [[order & 0xF, remaining & 0xF], [order >> 4, remaining >> 4]];
+            pos += 1 + both_pawns as usize;
+
+            let mut pieces = [[0; MAX_UNITS]; 2];
+            for (idx, piece) in data.get(pos..pos + unit_count)?.iter().enumerate() {
+                pieces[0][idx] = piece & 0xF;
+                pieces[1][idx] = piece >> 4;
+            }
+            pos += unit_count;
+
+            let mut file_groups = Vec::with_capacity(sides);
+            for side in 0..sides {
+                if Material::from_pieces(&pieces[side][..unit_count]) != material {
+                    return None;
+                }
+
+                file_groups.push(Groups::new(
+                    indices,
+                    &material,
+                    pieces[side],
+                    orders[side],
+                    file,
+                )?);
+            }
+            groups.push(file_groups);
+        }
+        pos += pos & 1;
+
+        Some((
+            Self {
+                material,
+                kind,
+                groups,
+            },
+            pos,
+        ))
+    }
+
+    /// Gives back where the position of the `units` is stored: the column of the leading Pawn, the side and the index
+    ///
+    /// `flipped` the sides of the units are switched in the table, see [`find_table`]
+    fn encode(
+        &self,
+        indices: &Indices,
+        units: &[(u8, u8)],
+        black_to_move: bool,
+        flipped: bool,
+    ) -> (usize, usize, u64) {
+        // A table of the same units on both sides only stores White in the current turn
+        let flip = flipped || (self.material.is_symmetric() && black_to_move);
+        let (flip_color, flip_squares) = if flip { (8, 56) } else { (0, 0) };
+        let side = (flip ^ black_to_move) as usize;
+
+        let mut squares = [0; MAX_UNITS];
+        let mut pieces = [0; MAX_UNITS];
+        let mut size = 0;
+        let mut lead_count = 0;
+        let mut file = 0;
+
+        // The leading Pawns come first, the one closest to the edge and the first row leads
+        let lead = match self.material.has_pawns() {
+            true => Some(self.groups[0][0].pieces[0] ^ flip_color),
+            false => None,
+        };
+        if let Some(lead) = lead {
+            for (piece, square) in units.iter().filter(|(piece, _)| *piece == lead) {
+                squares[size] = (square ^ flip_squares) as usize;
+                pieces[size] = piece ^ flip_color;
+                size += 1;
+            }
+            lead_count = size;
+
+            let leading = (0..lead_count)
+                .max_by_key(|idx| indices.map_pawns[squares[*idx]])
+                .unwrap_or(0);
+            squares.swap(0, leading);
+            file = edge_distance(squares[0] & 7);
+        }
+
+        for (piece, square) in units.iter().filter(|(piece, _)| Some(*piece) != lead) {
+            squares[size] = (square ^ flip_squares) as usize;
+            pieces[size] = piece ^ flip_color;
+            size += 1;
+        }
+
+        let groups = &self.groups[file][side % self.groups[file].len()];
+
+        // The same order of the units as in the table
+        for idx in lead_count..size.saturating_sub(1) {
+            if let Some(next) = (idx + 1..size).find(|next| groups.pieces[idx] == pieces[*next]) {
+                pieces.swap(idx, next);
+                squares.swap(idx, next);
+            }
+        }
+
+        // The leading unit goes onto the A-D columns
+        if squares[0] & 7 > 3 {
+            for square in &mut squares[..size] {
+                *square ^= 7;
+            }
+        }
+
+        let mut index;
+        if lead.is_some() {
+            index = indices.lead_pawn_idx[lead_count][squares[0]];
+
+            squares[1..lead_count].sort_by_key(|square| indices.map_pawns[*square]);
+            for (idx, square) in squares.iter().enumerate().take(lead_count).skip(1) {
+                index += indices.binomial[idx][indices.map_pawns[*square]];
+            }
+        } else {
+            // The leading unit goes onto the 1-4 rows, then below the A1-H8 diagonal
+            if squares[0] >> 3 > 3 {
+                for square in &mut squares[..size] {
+                    *square ^= 56;
+                }
+            }
+
+            for idx in 0..groups.group_len[0] {
+                match off_diagonal(squares[idx]).cmp(&0) {
+                    Ordering::Equal => continue,
+                    Ordering::Greater => {
+                        for square in &mut squares[idx..size] {
+                            *square = transpose(*square);
+                        }
+                    }
+                    Ordering::Less => {}
+                }
+                break;
+            }
+
+            index = match self.material.has_unique_pieces() {
+                true => indices.encode_unique(&squares),
+                false => indices.map_kk[indices.map_a1d1d4[squares[0]]][squares[1]],
+            };
+        }
+
+        // The other groups, their units in ascending order
+        index *= groups.group_idx[0];
+        let mut start = groups.group_len[0];
+        let mut remaining_pawns = lead.is_some() && self.material.get_pawn_counts()[1] > 0;
+
+        let mut group = 1;
+        while groups.group_len[group] != 0 {
+            let end = start + groups.group_len[group];
+            squares[start..end].sort_unstable();
+
+            let mut n = 0;
+            for idx in start..end {
+                let square = squares[idx];
+                let adjust = squares[..start]
+                    .iter()
+                    .filter(|other| square > **other)
+                    .count();
+                let offset = if remaining_pawns { 8 } else { 0 };
+                n += indices.binomial[idx - start + 1][square - adjust - offset];
+            }
+
+            remaining_pawns = false;
+            index += n * groups.group_idx[group];
+            start = end;
+            group += 1;
+        }
+
+        (file, side, index)
+    }
+}
+
+/// The groups of the units of a table part, the units of a group share the same type and side
+///
+/// * `pieces` Syzygy codes of the units in the order of the index, see [`collect_units`]
+/// * `group_len` number of units in each group, 0 ends the list
+/// * `group_idx` factor of each group in the index, the one after the last group is the size of the table part
+#[derive(Clone, Debug)]
+struct Groups {
+    pieces: [u8; MAX_UNITS],
+    group_len: [usize; MAX_UNITS + 1],
+    group_idx: [u64; MAX_UNITS + 1],
+}
+
+impl Groups {
+    /// Groups the `pieces`, `order` gives the place of the leading group and the remaining Pawns among the groups
+    fn new(
+        indices: &Indices,
+        material: &Material,
+        pieces: [u8; MAX_UNITS],
+        order: [u8; 2],
+        file: usize,
+    ) -> Option<Self> {
+        let has_pawns = material.has_pawns();
+        let both_pawns = has_pawns && material.get_pawn_counts()[1] > 0;
+        let mut group_len = [0; MAX_UNITS + 1];
+        let mut group_idx = [0; MAX_UNITS + 1];
+
+        // The leading group: the leading Pawns, 3 units when a unit is the only one of its kind, otherwise the Kings
+        let mut first_len: i32 = match (has_pawns, material.has_unique_pieces()) {
+            (true, _) => 0,
+            (false, true) => 3,
+            (false, false) => 2,
+        };
+
+        let mut groups = 0;
+        group_len[0] = 1;
+        for idx in 1..material.get_unit_count() {
+            first_len -= 1;
+            if first_len > 0 || pieces[idx] == pieces[idx - 1] {
+                group_len[groups] += 1;
+            } else {
+                groups += 1;
+                group_len[groups] = 1;
+            }
+        }
+        groups += 1;
+
+        let mut next = if both_pawns { 2 } else { 1 };
+        let mut free = 64 - group_len[0] - if both_pawns { group_len[1] } else { 0 };
+        let mut size: u64 = 1;
+
+        let mut place = 0;
+        while next < groups || place == order[0] || place == order[1] {
+            let factor = if place == order[0] {
+                group_idx[0] = size;
+                match (has_pawns, material.has_unique_pieces()) {
+                    (true, _) => *indices.lead_pawns_size.get(group_len[0])?.get(file)?,
+                    (false, true) => 31332,
+                    (false, false) => 462,
+                }
+            } else if place == order[1] {
+                group_idx[1] = size;
+                indices.binomial.get(group_len[1])?[48 - group_len[0]]
+            } else {
+                group_idx[next] = size;
+                let factor = indices.binomial.get(group_len[next])?[free];
+                free -= group_len[next];
+                next += 1;
+                factor
+            };
+
+            size = size.checked_mul(factor)?;
+            place += 1;
+        }
+        group_idx[groups] = size;
+
+        Some(Self {
+            pieces,
+            group_len,
+            group_idx,
+        })
+    }
+
+    /// Gives back the number of positions in the table part
+    fn get_size(&self) -> u64 {
+        let groups = self.group_len.iter().position(|len| *len == 0).unwrap_or(0);
+        self.group_idx[groups]
+    }
+}
+
+//==================================================
+//=== Tablebase: Material
+//==================================================
+
+/// The units of the two sides of a table, the stronger side first
+///
+/// * `counts` number of units of each side and type, indexed by the Syzygy type - 1, see [`unit_code`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Material {
+    counts: [[u8; 6]; 2],
+}
+
+impl Material {
+    /// Produces a [`Material`] from a table name, E.g. `KQvK`, the units of each side start with the King
+    fn parse(name: &str) -> Option<Self> {
+        let (first, second) = name.split_once('v')?;
+        let mut counts = [[0; 6]; 2];
+
+        for (side, units) in [first, second].into_iter().enumerate() {
+            if !units.starts_with('K') || units.len() > MAX_UNITS {
+                return None;
+            }
+
+            for letter in units.chars() {
+                let kind = UNIT_LETTERS.iter().position(|other| *other == letter)?;
+                counts[side][kind] += 1;
+            }
+            if counts[side][5] != 1 {
+                return None;
+            }
+        }
+
+        let material = Self { counts };
+        match material.get_unit_count() <= MAX_UNITS {
+            true => Some(material),
+            false => None,
+        }
+    }
+
+    /// Produces a [`Material`] from the codes of the units, see [`collect_units`]
+    fn from_pieces(pieces: &[u8]) -> Self {
+        let mut counts = [[0; 6]; 2];
+
+        for piece in pieces {
+            let kind = (piece & 7) as usize;
+            if (1..=6).contains(&kind) {
+                counts[(piece >> 3 & 1) as usize][kind - 1] += 1;
+            }
+        }
+
+        Self { counts }
+    }
+
+    /// Produces a [`Material`] from the units of a [`Board`], see [`collect_units`]
+    fn from_units(units: &[(u8, u8)]) -> Self {
+        let pieces = units.iter().map(|(piece, _)| *piece).collect::<Vec<u8>>();
+        Self::from_pieces(&pieces)
+    }
+
+    /// Gives back the name of the table, E.g. `KQvK`
+    fn get_name(&self) -> String {
+        let side_name = |counts: &[u8; 6]| {
+            (0..6)
+                .rev()
+                .flat_map(|kind| std::iter::repeat_n(UNIT_LETTERS[kind], counts[kind] as usize))
+                .collect::<String>()
+        };
+
+        format!(
+            "{}v{}",
+            side_name(&self.counts[0]),
+            side_name(&self.counts[1])
+        )
+    }
+
+    /// Gives back the [`Material`] with the sides switched
+    fn flip(&self) -> Self {
+        Self {
+            counts: [self.counts[1], self.counts[0]],
+        }
+    }
+
+    /// Returns true when the first side has the stronger units, the more Queens, then Rooks and so on
+    fn is_stronger(&self) -> bool {
+        self.counts[0].iter().rev().ge(self.counts[1].iter().rev())
+    }
+
+    /// Gives back the number of units of both sides
+    fn get_unit_count(&self) -> usize {
+        self.counts
+            .iter()
+            .flatten()
+            .map(|count| *count as usize)
+            .sum()
+    }
+
+    /// Returns true when any side has a Pawn
+    fn has_pawns(&self) -> bool {
+        self.counts[0][0] + self.counts[1][0] > 0
+    }
+
+    /// Returns true when both sides have the same units
+    fn is_symmetric(&self) -> bool {
+        self.counts[0] == self.counts[1]
+    }
+
+    /// Gives back the number of Pawns of the leading side and the other side
+    ///
+    /// The side with fewer Pawns leads, the first side when they have the same number
+    fn get_pawn_counts(&self) -> [u8; 2] {
+        let (first, second) = (self.counts[0][0], self.counts[1][0]);
+
+        match second == 0 || (first > 0 && second >= first) {
+            true => [first, second],
+            false => [second, first],
+        }
+    }
+
+    /// Returns true when a side has a unit which is the only one of its kind, the Kings are not counted
+    fn has_unique_pieces(&self) -> bool {
+        self.counts.iter().any(|counts| counts[..5].contains(&1))
+    }
+}
+
+//==================================================
+//=== Tablebase: Pairs Data
+//==================================================
+
+/// The compressed values of a table part
+///
+/// The values are packed into symbols, a symbol is a value or a pair of symbols,
+/// the symbols are Huffman coded in blocks of the same size
+///
+/// * `flags` flags of the table part, see [`SINGLE_VALUE_FLAG`]
+/// * `block_size` bytes of a block
+/// * `span` number of values between the entries of the sparse index
+/// * `sparse_index` offset of the sparse index, each entry holds the block and offset of a value
+/// * `sparse_index_size` number of entries in the sparse index
+/// * `block_length` offset of the number of values - 1 of each block
+/// * `block_length_size` number of blocks, including the padding
+/// * `blocks` number of blocks
+/// * `data` offset of the first block
+/// * `min_sym_len` shortest Huffman code, the value of a single value table part
+/// * `lowest_sym` offset of the lowest symbol of each code length
+/// * `base64` lowest code of each code length, left aligned
+/// * `symlen` number of values - 1 of each symbol
+/// * `btree` offset of the pairs of each symbol, 12 bits each, a value is a symbol without a pair
+/// * `map_idx` offset of the DTZ map of each result, see [`WDL_MAPS`]
+#[derive(Clone, Debug, Default)]
+struct PairsData {
+    flags: u8,
+    block_size: usize,
+    span: u64,
+    sparse_index: usize,
+    sparse_index_size: usize,
+    block_length: usize,
+    block_length_size: usize,
+    blocks: usize,
+    data: usize,
+    min_sym_len: u8,
+    lowest_sym: usize,
+    base64: Vec<u64>,
+    symlen: Vec<u32>,
+    btree: usize,
+    map_idx: [usize; 4],
+}
+
+impl PairsData {
+    /// Reads the sizes of the table part with `size` positions at `pos` and moves `pos` after them
+    fn parse(data: &[u8], pos: &mut usize, size: u64) -> Option<Self> {
+        let flags = *data.get(*pos)?;
+
+        if flags & SINGLE_VALUE_FLAG != 0 {
+            let value = *data.get(*pos + 1)?;
+            *pos += 2;
+
+            return Some(Self {
+                flags,
+                min_sym_len: value,
+                ..Default::default()
+            });
+        }
+
+        let block_size = 1usize.checked_shl(*data.get(*pos + 1)? as u32)?;
+        let span = 1u64.checked_shl(*data.get(*pos + 2)? as u32)?;
+        let padding = *data.get(*pos + 3)? as usize;
+        let blocks = read_u32(data, *pos + 4)? as usize;
+        let max_sym_len = *data.get(*pos + 8)?;
+        let min_sym_len = *data.get(*pos + 9)?;
+        if min_sym_len == 0 || max_sym_len < min_sym_len || max_sym_len > 32 {
+            return None;
+        }
+        let lowest_sym = *pos + 10;
+
+        // Canonical Huffman codes, the longer codes have the lower symbols
+        let lengths = (max_sym_len - min_sym_len + 1) as usize;
+        let mut base64 = vec![0u64; lengths];
+        for idx in (0..lengths - 1).rev() {
+            let lowest = read_u16(data, lowest_sym + 2 * idx)? as u64;
+            let next_lowest = read_u16(data, lowest_sym + 2 * idx + 2)? as u64;
+            base64[idx] = base64[idx + 1]
+                .wrapping_add(lowest)
+                .wrapping_sub(next_lowest)
+                / 2;
+        }
+        for (idx, base) in base64.iter_mut().enumerate() {
+            *base <<= 64 - idx - min_sym_len as usize;
+        }
+
+        let symbols_pos = lowest_sym + 2 * lengths;
+        let symbols = read_u16(data, symbols_pos)? as usize;
+        let btree = symbols_pos + 2;
+        data.get(btree..btree + 3 * symbols)?;
+
+        let mut pairs = Self {
+            flags,
+            block_size,
+            span,
+            sparse_index_size: size.div_ceil(span) as usize,
+            block_length_size: blocks + padding,
+            blocks,
+            min_sym_len,
+            lowest_sym,
+            base64,
+            symlen: vec![0; symbols],
+            btree,
+            ..Default::default()
+        };
+
+        let mut visited = vec![false; symbols];
+        for symbol in 0..symbols {
+            if !visited[symbol] {
+                pairs.symlen[symbol] = pairs.set_symlen(data, symbol, &mut visited);
+            }
+        }
+
+        *pos = btree + 3 * symbols + (symbols & 1);
+        Some(pairs)
+    }
+
+    /// Calculates the number of values - 1 of the `symbol` and the symbols of its pair
+    fn set_symlen(&mut self, data: &[u8], symbol: usize, visited: &mut [bool]) -> u32 {
+        visited[symbol] = true;
+
+        let (left, right) = read_pair(data, self.btree, symbol);
+        if right == 0xFFF || left >= visited.len() || right >= visited.len() {
+            return 0;
+        }
+
+        for child in [left, right] {
+            if !visited[child] {
+                self.symlen[child] = self.set_symlen(data, child, visited);
+            }
+        }
+
+        self.symlen[left] + self.symlen[right] + 1
+    }
+
+    /// Gives back the value at `index`, returns `None` when the data is out of the file
+    fn decompress(&self, data: &[u8], index: u64) -> Option<u16> {
+        if self.flags & SINGLE_VALUE_FLAG != 0 {
+            return Some(self.min_sym_len as u16);
+        }
+
+        // The sparse index points to the value in the middle of each span
+        let entry = self.sparse_index + 6 * (index / self.span) as usize;
+        let mut block = read_u32(data, entry)? as usize;
+        let mut offset = read_u16(data, entry + 4)? as i64;
+        offset += (index % self.span) as i64 - (self.span / 2) as i64;
+
+        let block_length = |block: usize| match block < self.block_length_size {
+            true => read_u16(data, self.block_length + 2 * block).map(|length| length as i64),
+            false => None,
+        };
+        while offset < 0 {
+            block = block.checked_sub(1)?;
+            offset += block_length(block)? + 1;
+        }
+        while offset > block_length(block)? {
+            offset -= block_length(block)? + 1;
+            block += 1;
+        }
+        if block >= self.blocks {
+            return None;
+        }
+
+        // The Huffman codes of the block, until the symbol holding the value
+        let mut pos = self.data + block * self.block_size;
+        data.get(pos)?;
+        let mut buffer = read_be(data, pos, 8);
+        let mut buffer_size = 64;
+        pos += 8;
+
+        let min_sym_len = self.min_sym_len as usize;
+        let mut symbol;
+        loop {
+            let mut len = 0;
+            while buffer < self.base64[len] {
+                len += 1;
+                if len == self.base64.len() {
+                    return None;
+                }
+            }
+
+            symbol = ((buffer - self.base64[len]) >> (64 - len - min_sym_len)) as usize;
+            symbol += read_u16(data, self.lowest_sym + 2 * len)? as usize;
+            let symlen = *self.symlen.get(symbol)? as i64;
+
+            if offset <= symlen {
+                break;
+            }
+
+            offset -= symlen + 1;
+            buffer <<= len + min_sym_len;
+            buffer_size -= len + min_sym_len;
+
+            if buffer_size <= 32 {
+                buffer_size += 32;
+                buffer |= read_be(data, pos, 4) << (64 - buffer_size);
+                pos += 4;
+            }
+        }
+
+        // The pairs of the symbol, until the value
+        while self.symlen[symbol] != 0 {
+            let (left, right) = read_pair(data, self.btree, symbol);
+            let left_len = *self.symlen.get(left)? as i64;
+
+            if offset <= left_len {
+                symbol = left;
+            } else {
+                offset -= left_len + 1;
+                symbol = right;
+            }
+            self.symlen.get(symbol)?;
+        }
+
+        Some(read_pair(data, self.btree, symbol).0 as u16)
+    }
+}
+
+/// Gives back the pair of the `symbol`, the value and 0xFFF when it is not a pair
+fn read_pair(data: &[u8], btree: usize, symbol: usize) -> (usize, usize) {
+    let pos = btree + 3 * symbol;
+    let byte = |idx: usize| *data.get(pos + idx).unwrap_or(&0xFF) as usize;
+
+    ((byte(1) & 0xF) << 8 | byte(0), byte(2) << 4 | byte(1) >> 4)
+}
+
+/// Reads a little-endian 16-bit number at `pos`
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+/// Reads a little-endian 32-bit number at `pos`
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+/// Reads a big-endian number of `len` bytes at `pos`, the bytes after the end of the file count as 0
+fn read_be(data: &[u8], pos: usize, len: usize) -> u64 {
+    (pos..pos + len).fold(0, |number, idx| {
+        number << 8 | *data.get(idx).unwrap_or(&0) as u64
+    })
+}
+
+//==================================================
+//=== Tablebase: Indices
+//==================================================
+
+/// Squares go from A1 = 0 to H8 = 63, `8 * rank + column`
+///
+/// * `binomial` number of ways to choose `k` squares of `n`, indexed by `[k][n]`
+/// * `map_pawns` index of the squares A2-H7, the leading Pawn has the highest, see [`Layout::encode`]
+/// * `lead_pawn_idx` first index of the leading Pawns, indexed by their number and the square of the leading one
+/// * `lead_pawns_size` number of positions of the leading Pawns, indexed by their number and column
+/// * `map_b1h1h7` index of the squares below the A1-H8 diagonal
+/// * `map_a1d1d4` index of the squares of the A1-D1-D4 triangle, the diagonal last
+/// * `map_kk` index of the 462 positions of two Kings, the first one in the A1-D1-D4 triangle
+#[derive(Debug)]
+struct Indices {
+    binomial: [[u64; 64]; MAX_UNITS],
+    map_pawns: [usize; 64],
+    lead_pawn_idx: [[u64; 64]; MAX_UNITS - 1],
+    lead_pawns_size: [[u64; 4]; MAX_UNITS - 1],
+    map_b1h1h7: [u64; 64],
+    map_a1d1d4: [usize; 64],
+    map_kk: [[u64; 64]; 10],
+}
+
+impl Indices {
+    /// Calculates the tables of the indices
+    fn new() -> Self {
+        let mut indices = Self {
+            binomial: [[0; 64]; MAX_UNITS],
+            map_pawns: [0; 64],
+            lead_pawn_idx: [[0; 64]; MAX_UNITS - 1],
+            lead_pawns_size: [[0; 4]; MAX_UNITS - 1],
+            map_b1h1h7: [0; 64],
+            map_a1d1d4: [0; 64],
+            map_kk: [[0; 64]; 10],
+        };
+
+        for n in 0..64 {
+            for k in 0..MAX_UNITS {
+                indices.binomial[k][n] = match (k, n) {
+                    (0, _) => 1,
+                    (_, 0) => 0,
+                    _ => indices.binomial[k - 1][n - 1] + indices.binomial[k][n - 1],
+                };
+            }
+        }
+
+        for (code, square) in (0..64)
+            .filter(|square| off_diagonal(*square) < 0)
+            .enumerate()
+        {
+            indices.map_b1h1h7[square] = code as u64;
+        }
+
+        // The squares on the diagonal come last
+        let mut code = 0;
+        let mut diagonal = Vec::new();
+        for square in (0..=27).filter(|square| square & 7 <= 3) {
+            match off_diagonal(square).cmp(&0) {
+                Ordering::Less => {
+                    indices.map_a1d1d4[square] = code;
+                    code += 1;
+                }
+                Ordering::Equal => diagonal.push(square),
+                Ordering::Greater => {}
+            }
+        }
+        for square in diagonal {
+            indices.map_a1d1d4[square] = code;
+            code += 1;
+        }
+
+        // When the first King is on the diagonal, the second one can't be above it,
+        // the positions with both Kings on the diagonal come last
+        let mut code = 0;
+        let mut diagonal = Vec::new();
+        for idx in 0..10 {
+            for first in (0..=27)
+                .filter(|square| indices.map_a1d1d4[*square] == idx && (idx != 0 || *square == 1))
+            {
+                for second in 0..64 {
+                    let distance = ((first & 7) as i32 - (second & 7) as i32)
+                        .abs()
+                        .max(((first >> 3) as i32 - (second >> 3) as i32).abs());
+
+                    if distance <= 1 || (off_diagonal(first) == 0 && off_diagonal(second) > 0) {
+                        continue;
+                    }
+
+                    if off_diagonal(first) == 0 && off_diagonal(second) == 0 {
+                        diagonal.push((idx, second));
+                    } else {
+                        indices.map_kk[idx][second] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+        for (idx, second) in diagonal {
+            indices.map_kk[idx][second] = code;
+            code += 1;
+        }
+
+        // The squares closer to the edge and to the first row come last
+        let mut available = 47;
+        for count in 1..MAX_UNITS - 1 {
+            for file in 0..4 {
+                let mut idx = 0;
+
+                for rank in 1..7 {
+                    let square = 8 * rank + file;
+                    if count == 1 {
+                        indices.map_pawns[square] = available;
+                        indices.map_pawns[square ^ 7] = available.saturating_sub(1);
+                        available = available.saturating_sub(2);
+                    }
+
+                    indices.lead_pawn_idx[count][square] = idx;
+                    idx += indices.binomial[count - 1][indices.map_pawns[square]];
+                }
+
+                indices.lead_pawns_size[count][file] = idx;
+            }
+        }
+
+        indices
+    }
+
+    /// Gives back the index of the leading group of 3 units, one of them is the only one of its kind
+    fn encode_unique(&self, squares: &[usize]) -> u64 {
+        let (first, second, third) = (squares[0], squares[1], squares[2]);
+        let adjust1 = (second > first) as u64;
+        let adjust2 = (third > first) as u64 + (third > second) as u64;
+        let rank = |square: usize| (square >> 3) as u64;
+
+        if off_diagonal(first) != 0 {
+            (self.map_a1d1d4[first] as u64 * 63 + second as u64 - adjust1) * 62 + third as u64
+                - adjust2
+        } else if off_diagonal(second) != 0 {
+            (6 * 63 + rank(first) * 28 + self.map_b1h1h7[second]) * 62 + third as u64 - adjust2
+        } else if off_diagonal(third) != 0 {
+            6 * 63 * 62
+                + 4 * 28 * 62
+                + rank(first) * 7 * 28
+                + (rank(second) - adjust1) * 28
+                + self.map_b1h1h7[third]
+        } else {
+            6 * 63 * 62
+                + 4 * 28 * 62
+                + 4 * 7 * 28
+                + rank(first) * 7 * 6
+                + (rank(second) - adjust1) * 6
+                + (rank(third) - adjust2)
+        }
+    }
+}
+
+/// Gives back how far the `square` is above the A1-H8 diagonal, negative below it
+fn off_diagonal(square: usize) -> i32 {
+    (square >> 3) as i32 - (square & 7) as i32
+}
+
+/// Mirrors the `square` on the A1-H8 diagonal
+fn transpose(square: usize) -> usize {
+    ((square >> 3) | (square << 3)) & 63
+}
+
+/// Gives back the distance of the `file` from the closest edge
+fn edge_distance(file: usize) -> usize {
+    file.min(7 - file)
+}
+
+//==================================================
+//=== Tablebase: Error
+//==================================================
+
+/// Errors produced while opening or probing a [`Tablebase`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TablebaseError {
+    Io(String),
+    InvalidTable(String),
+    MissingTable(String),
+    TooManyUnits(usize),
+    Castling,
+    Variant,
+}
+
+impl fmt::Display for TablebaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "Can't access the tablebase: {}", message),
+            Self::InvalidTable(name) => write!(f, "Invalid table: {}", name),
+            Self::MissingTable(name) => write!(f, "Missing table: {}", name),
+            Self::TooManyUnits(count) => write!(f, "No table holds {} units", count),
+            Self::Castling => write!(f, "The tables have no castling rights"),
+            Self::Variant => write!(f, "The tables only hold standard chess"),
+        }
+    }
+}
+
+impl error::Error for TablebaseError {}
+
+#[cfg(test)]
+mod tests_tablebase {
+    use super::*;
+    use crate::fen::START_FEN;
+    use crate::pos::Pos;
+    use crate::zobrist;
+    use std::cmp::Reverse;
+    use std::collections::{BTreeSet, BinaryHeap};
+
+    /// Directory of the fixture tables, written by [write_fixtures]
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/syzygy");
+
+    /// Fixture tables in the order they are written, a table probes the ones before it for captures and promotions
+    ///
+    /// White is the stronger side, the units are in the order of the index, the tables without wins have no DTZ table
+    const FIXTURE_TABLES: [(&str, &[u8], bool); 6] = [
+        ("KNvK", &[6, 2, 14], false),
+        ("KBvK", &[6, 3, 14], false),
+        ("KRvK", &[6, 4, 14], true),
+        ("KQvK", &[6, 5, 14], true),
+        ("KPvK", &[1, 6, 14], true),
+        ("KNNvK", &[6, 14, 2, 2], true),
+    ];
+
+    /// Bytes of a block of the fixture tables, as a power of 2
+    const BLOCK_LOG: u8 = 10;
+
+    /// Values between the entries of the sparse index of the fixture tables, as a power of 2
+    const SPAN_LOG: u8 = 12;
+
+    /// Most values of a block of the fixture tables, the sparse index offsets have to fit into 16 bits
+    const BLOCK_VALUES: usize = 60000;
+
+    fn open() -> Tablebase {
+        Tablebase::open(FIXTURES).unwrap()
+    }
+
+    fn board(fen: &str) -> Board {
+        Board::from_fen(fen).expect(fen)
+    }
+
+    /// Produces the [`Unit`] of a Syzygy code, see [`unit_code`]
+    fn code_unit(code: u8, square: u8) -> Unit {
+        let side = match code & 8 {
+            0 => Side::White,
+            _ => Side::Black,
+        };
+
+        match code & 7 {
+            1 => Unit::Pawn(side, square >> 3 != if side == Side::White { 1 } else { 6 }),
+            2 => Unit::Knight(side),
+            3 => Unit::Bishop(side),
+            4 => Unit::Rook(side, true),
+            5 => Unit::Queen(side),
+            _ => Unit::King(side, true),
+        }
+    }
+
+    /// Produces a [`Board`] of the units, returns `None` when the side not in the current turn is in check
+    fn placement_board(pieces: &[u8], squares: &[u8], side: Side) -> Option<Board> {
+        let mut board = Board::empty();
+        for (piece, square) in pieces.iter().zip(squares) {
+            let pos = Pos::new((square & 7) as i8, 7 - (square >> 3) as i8);
+            board.set_unit(code_unit(*piece, *square), pos);
+        }
+        board.set_current_turn(side);
+
+        match board.test_checked_status(&side.oppose()) {
+            true => None,
+            false => Some(board),
+        }
+    }
+
+    /// Gives back the number of King steps between the squares
+    fn distance(first: u8, second: u8) -> u8 {
+        let columns = (first & 7).abs_diff(second & 7);
+        let rows = (first >> 3).abs_diff(second >> 3);
+        columns.max(rows)
+    }
+
+    /// Returns true when the units can stand on the squares:
+    /// different squares, the Pawns off the first and last rows and the Kings apart
+    fn test_placement(pieces: &[u8], squares: &[u8]) -> bool {
+        let last = squares.len() - 1;
+        let (piece, square) = (pieces[last], squares[last]);
+
+        !squares[..last].contains(&square)
+            && (piece & 7 != 1 || (8..56).contains(&square))
+            && (piece & 7 != 6
+                || squares[..last]
+                    .iter()
+                    .zip(pieces)
+                    .all(|(other, other_piece)| {
+                        other_piece & 7 != 6 || distance(square, *other) > 1
+                    }))
+    }
+
+    /// Calls `f` with every placement of the units, the same units in ascending order, see [test_placement]
+    fn for_each_placement(pieces: &[u8], f: &mut impl FnMut(&[u8])) {
+        fn place(pieces: &[u8], squares: &mut Vec<u8>, f: &mut impl FnMut(&[u8])) {
+            let idx = squares.len();
+            if idx == pieces.len() {
+                return f(squares);
+            }
+
+            let start = match idx > 0 && pieces[idx] == pieces[idx - 1] {
+                true => squares[idx - 1] + 1,
+                false => 0,
+            };
+            for square in start..64 {
+                squares.push(square);
+                if test_placement(pieces, squares) {
+                    place(pieces, squares, f);
+                }
+                squares.pop();
+            }
+        }
+
+        place(pieces, &mut Vec::new(), f);
+    }
+
+    /// Calls `f` with `count` random legal positions of the units, for both sides in the current turn
+    fn for_each_sample(pieces: &[u8], count: usize, mut f: impl FnMut(&Board)) {
+        let mut samples = 0;
+        let mut seed = 0;
+
+        while samples < count {
+            seed += 1;
+            let random = zobrist::splitmix64(seed);
+            let mut squares = Vec::new();
+            for idx in 0..pieces.len() {
+                squares.push((random >> (8 * idx)) as u8 & 63);
+                if !test_placement(pieces, &squares) {
+                    break;
+                }
+            }
+            if squares.len() < pieces.len() || !test_placement(pieces, &squares) {
+                continue;
+            }
+
+            for side in [Side::White, Side::Black] {
+                if let Some(board) = placement_board(pieces, &squares, side) {
+                    f(&board);
+                    samples += 1;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_indices() {
+        let indices = Indices::new();
+
+        assert_eq!(indices.binomial[2][5], 10);
+        assert_eq!(indices.map_kk.iter().flatten().max(), Some(&461));
+        assert_eq!(indices.map_a1d1d4[0], 6);
+        assert_eq!(indices.map_b1h1h7[63], 0);
+        assert_eq!(indices.map_pawns[8], 47);
+        assert_eq!(indices.map_pawns[15], 46);
+        assert_eq!(indices.lead_pawns_size[1], [6; 4]);
+    }
+
+    #[test]
+    fn test_material() {
+        let material = Material::parse("KQvK").unwrap();
+        assert_eq!(material.get_name(), "KQvK");
+        assert_eq!(material.flip().get_name(), "KvKQ");
+        assert_eq!(material.get_unit_count(), 3);
+        assert!(material.has_unique_pieces());
+        assert!(!material.is_symmetric());
+
+        let material = Material::parse("KNNvK").unwrap();
+        assert!(!material.has_unique_pieces());
+        assert!(Material::parse("KPvKP").unwrap().is_symmetric());
+        assert!(Material::parse("KRvKN").unwrap().is_stronger());
+        assert!(!Material::parse("KNvKR").unwrap().is_stronger());
+
+        assert_eq!(Material::parse("QvK"), None);
+        assert_eq!(Material::parse("KQK"), None);
+        assert_eq!(Material::parse("KKvK"), None);
+        assert_eq!(Material::parse("KQQQvKRRR"), None);
+    }
+
+    #[test]
+    fn test_open() {
+        let tablebase = open();
+        assert_eq!(tablebase.get_max_units(), 4);
+        assert_eq!(tablebase.wdl_tables.len(), 6);
+        assert_eq!(tablebase.dtz_tables.len(), 4);
+
+        assert!(matches!(
+            Tablebase::open(Path::new(FIXTURES).join("missing")),
+            Err(TablebaseError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_table() {
+        let indices = Indices::new();
+        let material = Material::parse("KQvK").unwrap();
+
+        let header = table_header(&material, &[6, 5, 14], TableKind::Wdl);
+        assert!(Layout::parse(&header, &indices, material, TableKind::Wdl).is_some());
+        assert!(Layout::parse(&header, &indices, material, TableKind::Dtz).is_none());
+        assert!(Layout::parse(&header[..6], &indices, material, TableKind::Wdl).is_none());
+
+        let header = table_header(&material, &[6, 4, 14], TableKind::Wdl);
+        assert!(Layout::parse(&header, &indices, material, TableKind::Wdl).is_none());
+    }
+
+    #[test]
+    fn test_probe_wdl() {
+        let tablebase = open();
+        let probe = |fen: &str| tablebase.probe_wdl(&board(fen)).unwrap();
+
+        // KvK, KNvK
+        assert_eq!(probe("8/8/4k3/8/8/4K3/8/8 w - - 0 1"), Wdl::Draw);
+        assert_eq!(probe("8/8/4k3/8/8/4K3/4N3/8 w - - 0 1"), Wdl::Draw);
+
+        // KQvK, mated, stalemate, the Queen taken
+        assert_eq!(probe("7k/8/6K1/8/8/8/Q7/8 w - - 0 1"), Wdl::Win);
+        assert_eq!(probe("Q6k/8/6K1/8/8/8/8/8 b - - 0 1"), Wdl::Loss);
+        assert_eq!(probe("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"), Wdl::Draw);
+        assert_eq!(probe("7k/6Q1/8/8/8/8/8/K7 b - - 0 1"), Wdl::Draw);
+        assert_eq!(probe("8/q7/8/8/8/6k1/8/7K b - - 0 1"), Wdl::Win);
+
+        // KRvK
+        assert_eq!(probe("8/8/8/4k3/8/8/8/R3K3 w - - 0 1"), Wdl::Win);
+        assert_eq!(probe("8/8/8/4k3/8/8/8/R3K3 b - - 0 1"), Wdl::Loss);
+
+        // KPvK
+        assert_eq!(probe("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1"), Wdl::Win);
+        assert_eq!(probe("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1"), Wdl::Loss);
+        assert_eq!(probe("4k3/4P3/4K3/8/8/8/8/8 w - - 0 1"), Wdl::Win);
+        assert_eq!(probe("4k3/4P3/4K3/8/8/8/8/8 b - - 0 1"), Wdl::Draw);
+        assert_eq!(probe("8/8/8/8/8/4k3/4p3/4K3 w - - 0 1"), Wdl::Draw);
+
+        // KNNvK
+        assert_eq!(probe("k7/2NN4/1K6/8/8/8/8/8 b - - 0 1"), Wdl::Loss);
+        assert_eq!(probe("k7/3N4/1K6/3N4/8/8/8/8 w - - 0 1"), Wdl::Win);
+        assert_eq!(probe("8/8/8/4k3/8/8/2NN4/4K3 w - - 0 1"), Wdl::Draw);
+    }
+
+    #[test]
+    fn test_probe_dtz() {
+        let tablebase = open();
+        let probe = |fen: &str| tablebase.probe_dtz(&board(fen)).unwrap();
+
+        assert_eq!(probe("7k/8/6K1/8/8/8/Q7/8 w - - 0 1"), 1);
+        assert_eq!(probe("Q6k/8/6K1/8/8/8/8/8 b - - 0 1"), -1);
+        assert_eq!(probe("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"), 0);
+        assert_eq!(probe("8/q7/8/8/8/6k1/8/7K b - - 0 1"), 1);
+
+        // The King makes way for the Pawn step, a checkmate counts as a capture
+        assert_eq!(probe("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1"), 3);
+        assert_eq!(probe("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1"), -4);
+        assert_eq!(probe("k7/3N4/1K6/3N4/8/8/8/8 w - - 0 1"), 1);
+
+        // Each Move of the winner brings the next capture or mate a ply closer
+        let mut board = board("8/8/8/4k3/8/8/8/R3K3 w - - 0 1");
+        let mut dtz = tablebase.probe_dtz(&board).unwrap();
+        assert!(dtz > 1);
+        while dtz > 1 {
+            let best = tablebase.get_best_move(&board).unwrap().unwrap();
+            assert_eq!(best.dtz, dtz);
+            board.make_move(&best.chess_move);
+
+            assert_eq!(tablebase.probe_dtz(&board).unwrap(), 1 - dtz);
+            let reply = tablebase.get_best_move(&board).unwrap().unwrap();
+            board.make_move(&reply.chess_move);
+
+            let next = tablebase.probe_dtz(&board).unwrap();
+            assert!(next <= dtz - 2);
+            dtz = next;
+        }
+    }
+
+    #[test]
+    fn test_best_move() {
+        let tablebase = open();
+        let best = |fen: &str| {
+            let board = board(fen);
+            let best = tablebase.get_best_move(&board).unwrap().unwrap();
+            (best.chess_move.to_san(&board), best.wdl, best.dtz)
+        };
+
+        assert_eq!(
+            best("7k/8/6K1/8/8/8/Q7/8 w - - 0 1"),
+            (String::from("Qa8#"), Wdl::Win, 1)
+        );
+        assert_eq!(
+            best("k7/3N4/1K6/3N4/8/8/8/8 w - - 0 1"),
+            (String::from("Nc7#"), Wdl::Win, 1)
+        );
+        assert_eq!(
+            best("7k/6Q1/8/8/8/8/8/K7 b - - 0 1"),
+            (String::from("Kxg7"), Wdl::Draw, 0)
+        );
+
+        let (san, wdl, dtz) = best("4k3/4P3/4K3/8/8/8/8/8 w - - 0 1");
+        assert!(san == "Kd6" || san == "Kf6");
+        assert_eq!((wdl, dtz), (Wdl::Win, 5));
+
+        let (san, wdl, dtz) = best("8/4P3/8/8/8/8/k7/4K3 w - - 0 1");
+        assert!(san == "e8=Q" || san == "e8=R");
+        assert_eq!((wdl, dtz), (Wdl::Win, 1));
+
+        let board = board("Q6k/8/6K1/8/8/8/8/8 b - - 0 1");
+        assert_eq!(tablebase.get_best_move(&board), Ok(None));
+        assert!(tablebase.get_moves(&board).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_consistency() {
+        let tablebase = open();
+
+        // The stored result and DTZ of a position match the best Move
+        for (_, pieces, _) in FIXTURE_TABLES {
+            for_each_sample(pieces, 80, |board| {
+                let wdl = tablebase.probe_wdl(board).unwrap();
+                let dtz = tablebase.probe_dtz(board).unwrap();
+                let side = board.get_current_turn();
+
+                match tablebase.get_best_move(board).unwrap() {
+                    Some(best) => {
+                        assert_eq!(wdl, best.wdl, "{}", board.to_fen());
+                        match wdl {
+                            Wdl::Draw => assert_eq!(dtz, 0, "{}", board.to_fen()),
+                            _ => assert_eq!(dtz, best.dtz, "{}", board.to_fen()),
+                        }
+                    }
+                    None if board.test_checked_status(&side) => {
+                        assert_eq!((wdl, dtz), (Wdl::Loss, -1))
+                    }
+                    None => assert_eq!((wdl, dtz), (Wdl::Draw, 0)),
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let tablebase = open();
+
+        assert_eq!(
+            tablebase.probe_wdl(&board(START_FEN)),
+            Err(TablebaseError::TooManyUnits(32))
+        );
+        assert_eq!(
+            tablebase.probe_dtz(&board("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1")),
+            Err(TablebaseError::Castling)
+        );
+        assert_eq!(
+            tablebase.get_moves(&board("4k3/q7/8/8/8/8/Q7/4K3 w - - 0 1")),
+            Err(TablebaseError::MissingTable(String::from("KQvKQ.rtbw")))
+        );
+
+        let mut crazyhouse = board("7k/8/6K1/8/8/8/Q7/8 w - - 0 1");
+        crazyhouse.set_crazyhouse(true);
+        assert_eq!(
+            tablebase.probe_wdl(&crazyhouse),
+            Err(TablebaseError::Variant)
+        );
+    }
+
+    //==================================================
+    //=== Fixture Generator
+    //==================================================
+
+    /// A legal [`Move`] of a position of a fixture table
+    #[derive(Clone, Copy)]
+    enum Child {
+        /// The position after the [`Move`] is in the same table, at the slot, and whether the [`Move`] is a capture or Pawn step
+        Slot(u32, bool),
+        /// The [`Move`] leads into another table, the result for the other side
+        Result(Wdl),
+    }
+
+    /// A position of a fixture table
+    ///
+    /// * `checked` the side in the current turn is in check
+    /// * `children` the legal [`Move`]s
+    struct Node {
+        checked: bool,
+        children: Vec<Child>,
+    }
+
+    /// Writes the fixture tables, run with `cargo test --release -- --ignored write_fixtures`
+    ///
+    /// The tables are solved by retrograde analysis, so the fixtures don't depend on other tablebases
+    #[test]
+    #[ignore]
+    fn write_fixtures() {
+        fs::create_dir_all(FIXTURES).unwrap();
+        let indices = Indices::new();
+
+        for (name, pieces, dtz) in FIXTURE_TABLES {
+            write_fixture(&indices, name, pieces, dtz);
+        }
+    }
+
+    /// Solves and writes the WDL table of the units, and the DTZ table with `dtz`
+    fn write_fixture(indices: &Indices, name: &str, pieces: &[u8], dtz: bool) {
+        let material = Material::parse(name).unwrap();
+        let header = table_header(&material, pieces, TableKind::Wdl);
+        let (layout, _) = Layout::parse(&header, indices, material, TableKind::Wdl).unwrap();
+        let tablebase = open();
+
+        // Every table part, by column of the leading Pawn and side in the current turn
+        let mut offsets = Vec::new();
+        let mut slots = 0;
+        for groups in &layout.groups {
+            for group in groups {
+                offsets.push(slots);
+                slots += group.get_size() as usize;
+            }
+        }
+        let encode = |units: &[(u8, u8)], side: Side| {
+            let (file, side, index) = layout.encode(indices, units, side == Side::Black, false);
+            assert!(index < layout.groups[file][side].get_size());
+            offsets[2 * file + side] + index as usize
+        };
+
+        // A position for each slot, the others on the same slot are the same mirrored
+        let mut placements = vec![None; slots];
+        for_each_placement(pieces, &mut |squares| {
+            let units = pieces
+                .iter()
+                .copied()
+                .zip(squares.iter().copied())
+                .collect::<Vec<_>>();
+            for side in [Side::White, Side::Black] {
+                let slot = encode(&units, side);
+                if placements[slot].is_none() {
+                    let mut placement = [0; MAX_UNITS];
+                    placement[..squares.len()].copy_from_slice(squares);
+                    placements[slot] = Some((placement, side));
+                }
+            }
+        });
+
+        let nodes = placements
+            .iter()
+            .map(|placement| {
+                let (squares, side) = (*placement)?;
+                let board = placement_board(pieces, &squares[..pieces.len()], side)?;
+                let children = board
+                    .legal_moves(&side)
+                    .iter()
+                    .map(|chess_move| {
+                        let mut next = board.clone();
+                        next.apply_move(chess_move);
+
+                        let units = collect_units(&next);
+                        match Material::from_units(&units) == material {
+                            true => Child::Slot(
+                                encode(&units, side.oppose()) as u32,
+                                chess_move.is_capture()
+                                    || eq_unit_type(&chess_move.unit, &Unit::PAWN),
+                            ),
+                            false => Child::Result(tablebase.probe_wdl(&next).unwrap()),
+                        }
+                    })
+                    .collect();
+
+                Some(Node {
+                    checked: board.test_checked_status(&side),
+                    children,
+                })
+            })
+            .collect::<Vec<Option<Node>>>();
+
+        let wdl = solve_wdl(&nodes);
+        let parts = |values: &dyn Fn(usize) -> Option<u8>, sides: usize| {
+            let mut parts = Vec::new();
+            for (file, groups) in layout.groups.iter().enumerate() {
+                for (side, group) in groups.iter().enumerate().take(sides) {
+                    let offset = offsets[2 * file + side];
+                    let size = group.get_size() as usize;
+                    parts.push((offset..offset + size).map(values).collect::<Vec<_>>());
+                }
+            }
+            parts
+        };
+
+        let values = |slot: usize| wdl[slot].map(|wdl| (wdl.get_value() + 2) as u8);
+        let path = Path::new(FIXTURES).join(TableKind::Wdl.file_name(name));
+        write_table(&path, &header, &parts(&values, 2), 0, TableKind::Wdl);
+
+        if dtz {
+            let plies = solve_dtz(&nodes, &wdl);
+            let values = |slot: usize| match plies[slot] {
+                0 => None,
+                plies => Some(plies - 1),
+            };
+
+            let header = table_header(&material, pieces, TableKind::Dtz);
+            let path = Path::new(FIXTURES).join(TableKind::Dtz.file_name(name));
+            let flags = WIN_PLIES_FLAG | LOSS_PLIES_FLAG;
+            write_table(&path, &header, &parts(&values, 1), flags, TableKind::Dtz);
+        }
+    }
+
+    /// Gives back the result of each legal position, positions which can't force a result are draws
+    fn solve_wdl(nodes: &[Option<Node>]) -> Vec<Option<Wdl>> {
+        let mut wdl = nodes
+            .iter()
+            .map(|node| match node {
+                Some(node) if node.children.is_empty() => match node.checked {
+                    true => Some(Wdl::Loss),
+                    false => Some(Wdl::Draw),
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for (slot, node) in nodes.iter().enumerate() {
+                let Some(node) = node else {
+                    continue;
+                };
+                if wdl[slot].is_some() {
+                    continue;
+                }
+
+                let mut best = Wdl::Loss;
+                let mut known = true;
+                for child in &node.children {
+                    let value = match child {
+                        Child::Slot(child, _) => wdl[*child as usize].map(|wdl| -wdl),
+                        Child::Result(wdl) => Some(-*wdl),
+                    };
+
+                    match value {
+                        Some(value) => best = best.max(value),
+                        None => known = false,
+                    }
+                }
+
+                if known || best == Wdl::Win {
+                    wdl[slot] = Some(best);
+                    changed = true;
+                }
+            }
+        }
+
+        nodes
+            .iter()
+            .zip(wdl)
+            .map(|(node, wdl)| node.as_ref().map(|_| wdl.unwrap_or(Wdl::Draw)))
+            .collect()
+    }
+
+    /// Gives back the DTZ of each won or lost position, 0 for the others
+    ///
+    /// A capture, Pawn step or checkmate takes 1 ply, the winner takes the shortest way, the loser the longest
+    fn solve_dtz(nodes: &[Option<Node>], wdl: &[Option<Wdl>]) -> Vec<u8> {
+        let mut plies = vec![0u8; nodes.len()];
+
+        // The wins and losses alternate, a ply without a change doesn't end the search
+        for ply in 1..=100 {
+            for (slot, node) in nodes.iter().enumerate() {
+                let Some(node) = node else {
+                    continue;
+                };
+                if plies[slot] != 0 {
+                    continue;
+                }
+
+                match wdl[slot] {
+                    Some(Wdl::Win) => {
+                        let found = node.children.iter().any(|child| match *child {
+                            Child::Slot(child, zeroing) => {
+                                let child = child as usize;
+                                let mated = nodes[child].as_ref().unwrap().children.is_empty();
+
+                                wdl[child] == Some(Wdl::Loss)
+                                    && match ply {
+                                        1 => zeroing || mated,
+                                        _ => !zeroing && plies[child] == ply - 1,
+                                    }
+                            }
+                            Child::Result(wdl) => ply == 1 && wdl == Wdl::Loss,
+                        });
+
+                        if found {
+                            plies[slot] = ply;
+                        }
+                    }
+                    Some(Wdl::Loss) => {
+                        let mut longest = 1;
+                        let mut known = true;
+                        for child in &node.children {
+                            if let Child::Slot(child, false) = child {
+                                match plies[*child as usize] {
+                                    0 => known = false,
+                                    plies => longest = longest.max(plies + 1),
+                                }
+                            }
+                        }
+
+                        if known {
+                            plies[slot] = longest;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for (slot, wdl) in wdl.iter().enumerate() {
+            if matches!(wdl, Some(Wdl::Win) | Some(Wdl::Loss)) {
+                assert!((1..=100).contains(&plies[slot]), "DTZ out of range");
+            }
+        }
+
+        plies
+    }
+
+    /// Gives back the header of a table, the leading group comes first in the index
+    fn table_header(material: &Material, pieces: &[u8], kind: TableKind) -> Vec<u8> {
+        assert!(!material.has_pawns() || material.get_pawn_counts()[1] == 0);
+
+        let mut header = match kind {
+            TableKind::Wdl => WDL_MAGIC.to_vec(),
+            TableKind::Dtz => DTZ_MAGIC.to_vec(),
+        };
+
+        let mut flags = 0;
+        if !material.is_symmetric() {
+            flags |= SPLIT_FLAG;
+        }
+        if material.has_pawns() {
+            flags |= PAWNS_FLAG;
+        }
+        header.push(flags);
+
+        let files = if material.has_pawns() { 4 } else { 1 };
+        for _ in 0..files {
+            header.push(0);
+            header.extend(pieces.iter().map(|piece| piece | piece << 4));
+        }
+        if header.len() & 1 == 1 {
+            header.push(0);
+        }
+
+        header
+    }
+
+    /// A table part compressed as read by [`PairsData`]
+    ///
+    /// * `sizes` the flags, the sizes and the symbols
+    /// * `sparse_index` block and offset of the value in the middle of each span
+    /// * `block_lengths` number of values - 1 of each block
+    /// * `blocks` the Huffman codes of the symbols
+    struct Compressed {
+        sizes: Vec<u8>,
+        sparse_index: Vec<u8>,
+        block_lengths: Vec<u8>,
+        blocks: Vec<u8>,
+    }
+
+    /// Writes the table parts after the `header`, `None` values are never probed
+    fn write_table(
+        path: &Path,
+        header: &[u8],
+        parts: &[Vec<Option<u8>>],
+        flags: u8,
+        kind: TableKind,
+    ) {
+        let parts = parts
+            .iter()
+            .map(|values| compress(values, flags))
+            .collect::<Vec<_>>();
+
+        let mut data = header.to_vec();
+        for part in &parts {
+            data.extend(&part.sizes);
+        }
+        if kind == TableKind::Dtz && data.len() & 1 == 1 {
+            data.push(0);
+        }
+        for part in &parts {
+            data.extend(&part.sparse_index);
+        }
+        for part in &parts {
+            data.extend(&part.block_lengths);
+        }
+        for part in &parts {
+            data.resize((data.len() + 0x3F) & !0x3F, 0);
+            data.extend(&part.blocks);
+        }
+
+        fs::write(path, data).unwrap();
+    }
+
+    /// Compresses the values of a table part, the values which are never probed get the value before them
+    fn compress(values: &[Option<u8>], flags: u8) -> Compressed {
+        let mut previous = values.iter().flatten().next().copied().unwrap_or(0);
+        let values = values
+            .iter()
+            .map(|value| {
+                previous = value.unwrap_or(previous);
+                previous
+            })
+            .collect::<Vec<u8>>();
+
+        if values.iter().all(|value| *value == values[0]) {
+            return Compressed {
+                sizes: vec![flags | SINGLE_VALUE_FLAG, values[0]],
+                sparse_index: Vec::new(),
+                block_lengths: Vec::new(),
+                blocks: Vec::new(),
+            };
+        }
+
+        // The runs of a value, split into symbols of 1 to 256 values, E.g. (value, 3) holds 8 values
+        let mut tokens = Vec::new();
+        for run in values.chunk_by(|first, second| first == second) {
+            let mut len = run.len();
+            while len > 0 {
+                let size = usize::BITS - 1 - len.min(256).leading_zeros();
+                tokens.push((run[0], size as u8));
+                len -= 1 << size;
+            }
+        }
+
+        let mut counts = HashMap::new();
+        for token in &tokens {
+            *counts.entry(*token).or_insert(0u64) += 1;
+        }
+        let used = counts.keys().copied().collect::<BTreeSet<_>>();
+        let lengths = huffman_lengths(&used.iter().map(|token| counts[token]).collect::<Vec<_>>());
+        let lengths = used.iter().copied().zip(lengths).collect::<HashMap<_, _>>();
+
+        // The symbols only used in pairs come first, then the longer codes
+        let mut symbols = used
+            .iter()
+            .flat_map(|(value, size)| (0..*size).map(move |size| (*value, size)))
+            .filter(|token| !used.contains(token))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let unused = symbols.len();
+        let mut used = used.into_iter().collect::<Vec<_>>();
+        used.sort_by_key(|token| Reverse(lengths[token]));
+        symbols.extend(used);
+        assert!(symbols.len() < 0xFFF);
+        let ids = symbols
+            .iter()
+            .enumerate()
+            .map(|(id, token)| (*token, id))
+            .collect::<HashMap<_, _>>();
+
+        let min_len = *lengths.values().min().unwrap();
+        let max_len = *lengths.values().max().unwrap();
+        assert!(max_len <= 32);
+
+        // Canonical codes from the longest, see PairsData::parse
+        let mut lowest = vec![0; (max_len - min_len + 1) as usize];
+        let mut first_codes = vec![0u64; lowest.len()];
+        let mut next_lowest = unused;
+        let mut code = 0u64;
+        for len in (min_len..=max_len).rev() {
+            let count = lengths.values().filter(|other| **other == len).count();
+            let idx = (len - min_len) as usize;
+
+            lowest[idx] = next_lowest;
+            first_codes[idx] = code;
+            next_lowest += count;
+
+            if len > min_len {
+                assert_eq!((code + count as u64) & 1, 0);
+                code = (code + count as u64) / 2;
+            }
+        }
+
+        let mut sizes = vec![flags, BLOCK_LOG, SPAN_LOG, 0];
+        let mut blocks_data = Vec::new();
+        let mut block_lengths = Vec::new();
+        let mut block = BitWriter::default();
+        let mut block_values = 0;
+
+        for token in &tokens {
+            let len = lengths[token];
+            let idx = (len - min_len) as usize;
+            let code = first_codes[idx] + (ids[token] - lowest[idx]) as u64;
+            let count = 1 << token.1;
+
+            if block.bits + len as usize > 8 << BLOCK_LOG || block_values + count > BLOCK_VALUES {
+                block_lengths.push(block_values);
+                blocks_data.push(block.bytes);
+                block = BitWriter::default();
+                block_values = 0;
+            }
+
+            block.push(code, len);
+            block_values += count;
+        }
+        block_lengths.push(block_values);
+        blocks_data.push(block.bytes);
+
+        sizes.extend((blocks_data.len() as u32).to_le_bytes());
+        sizes.extend([max_len as u8, min_len as u8]);
+        for lowest in &lowest {
+            sizes.extend((*lowest as u16).to_le_bytes());
+        }
+        sizes.extend((symbols.len() as u16).to_le_bytes());
+        for (value, size) in &symbols {
+            let (left, right) = match size {
+                0 => (*value as usize, 0xFFF),
+                _ => (ids[&(*value, size - 1)], ids[&(*value, size - 1)]),
+            };
+            sizes.extend([
+                left as u8,
+                (left >> 8 | (right & 0xF) << 4) as u8,
+                (right >> 4) as u8,
+            ]);
+        }
+        if symbols.len() & 1 == 1 {
+            sizes.push(0);
+        }
+
+        // The sparse index points past the last block for the spans after the end
+        let mut starts = vec![0];
+        for length in &block_lengths {
+            starts.push(starts.last().unwrap() + length);
+        }
+        let span = 1 << SPAN_LOG;
+        let mut sparse_index = Vec::new();
+        for entry in 0..values.len().div_ceil(span) {
+            let index = entry * span + span / 2;
+            let block = starts[1..]
+                .iter()
+                .position(|start| index < *start)
+                .unwrap_or(block_lengths.len() - 1);
+            let offset = index - starts[block];
+            assert!(offset <= 0xFFFF);
+
+            sparse_index.extend((block as u32).to_le_bytes());
+            sparse_index.extend((offset as u16).to_le_bytes());
+        }
+
+        let mut blocks = Vec::new();
+        for mut data in blocks_data {
+            data.resize(1 << BLOCK_LOG, 0);
+            blocks.extend(data);
+        }
+
+        Compressed {
+            sizes,
+            sparse_index,
+            block_lengths: block_lengths
+                .iter()
+                .flat_map(|length| ((length - 1) as u16).to_le_bytes())
+                .collect(),
+            blocks,
+        }
+    }
+
+    /// Gives back the lengths of the Huffman codes of symbols with the `counts`
+    fn huffman_lengths(counts: &[u64]) -> Vec<u32> {
+        if counts.len() == 1 {
+            return vec![1];
+        }
+
+        let mut parents = vec![0; counts.len()];
+        let mut heap = counts
+            .iter()
+            .enumerate()
+            .map(|(node, count)| Reverse((*count, node)))
+            .collect::<BinaryHeap<_>>();
+
+        while heap.len() > 1 {
+            let Reverse((first, first_node)) = heap.pop().unwrap();
+            let Reverse((second, second_node)) = heap.pop().unwrap();
+
+            let node = parents.len();
+            parents.push(node);
+            parents[first_node] = node;
+            parents[second_node] = node;
+            heap.push(Reverse((first + second, node)));
+        }
+
+        (0..counts.len())
+            .map(|mut node| {
+                let mut len = 0;
+                while parents[node] != node {
+                    node = parents[node];
+                    len += 1;
+                }
+                len
+            })
+            .collect()
+    }
+
+    /// Writes codes from the highest bit
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        bits: usize,
+    }
+
+    impl BitWriter {
+        fn push(&mut self, code: u64, len: u32) {
+            for bit in (0..len).rev() {
+                if self.bits.is_multiple_of(8) {
+                    self.bytes.push(0);
+                }
+                if code >> bit & 1 == 1 {
+                    *self.bytes.last_mut().unwrap() |= 0x80 >> (self.bits % 8);
+                }
+                self.bits += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,522 @@
+// Standard Crate
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Chess Crate
+use super::chess::Board;
+use super::engine::{Engine, Limit, SearchResult, DEFAULT_HASH_SIZE};
+use super::eval::EvalParams;
+use super::fen::START_FEN;
+use super::game::Game;
+use super::moves::Move;
+use super::unit::Side;
+
+/// Largest transposition table in megabytes
+const MAX_HASH_SIZE: usize = 1024;
+
+//==================================================
+//=== UCI
+//==================================================
+
+/// Speaks the Universal Chess Interface with a GUI, the answers go to `out`
+///
+/// Commands come one line at a time through [execute](#method.execute), the search runs on its own thread,
+/// so `stop` and `isready` are answered while it thinks
+///
+/// * `out` where the answers are written, E.g. stdout
+/// * `engine` the [`Engine`], locked by the running search
+/// * `stop` stops the running search, see [`Engine::get_stop_handle`]
+/// * `start_board` position of the last `position` command
+/// * `moves` [`Move`]s played from `start_board`
+/// * `search` thread of the running search
+/// * `infinite` the running search has no limit, it sends the best [`Move`] after `stop`
+/// * `chess960` the `UCI_Chess960` option, the King takes its own Rook on a Castle, E.g. "e1h1"
+pub struct Uci<W: Write + Send + 'static> {
+    out: Arc<Mutex<W>>,
+    engine: Arc<Mutex<Engine>>,
+    stop: Arc<AtomicBool>,
+    start_board: Board,
+    moves: Vec<Move>,
+    search: Option<JoinHandle<()>>,
+    infinite: bool,
+    chess960: bool,
+}
+
+impl<W: Write + Send + 'static> Uci<W> {
+    /// Creates a new [`Uci`] in the start position, writing to `out`
+    pub fn new(out: W) -> Self {
+        let engine = Engine::new();
+
+        Self {
+            out: Arc::new(Mutex::new(out)),
+            stop: engine.get_stop_handle(),
+            engine: Arc::new(Mutex::new(engine)),
+            start_board: Board::new(),
+            moves: Vec::new(),
+            search: None,
+            infinite: false,
+            chess960: false,
+        }
+    }
+
+    /// Executes a command of the GUI, E.g. `position startpos moves e2e4` or `go movetime 1000`
+    ///
+    /// Unknown commands are ignored, returns false after `quit`
+    pub fn execute(&mut self, line: &str) -> bool {
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("uci") => {
+                send(
+                    &self.out,
+                    &format!("id name chess-rs {}", env!("CARGO_PKG_VERSION")),
+                );
+                send(&self.out, "id author hokkonsz");
+                send(
+                    &self.out,
+                    &format!(
+                        "option name Hash type spin default {} min 1 max {}",
+                        DEFAULT_HASH_SIZE, MAX_HASH_SIZE
+                    ),
+                );
+                send(
+                    &self.out,
+                    "option name EvalFile type string default <empty>",
+                );
+                send(
+                    &self.out,
+                    "option name UCI_Chess960 type check default false",
+                );
+                send(&self.out, "uciok");
+            }
+            Some("isready") => send(&self.out, "readyok"),
+            Some("ucinewgame") => {
+                self.stop();
+                self.lock_engine().clear();
+            }
+            Some("setoption") => {
+                self.stop();
+                self.set_option(&tokens.collect::<Vec<&str>>());
+            }
+            Some("position") => {
+                self.stop();
+                if let Err(error) = self.set_position(&tokens.collect::<Vec<&str>>()) {
+                    send(&self.out, &format!("info string {}", error));
+                }
+            }
+            Some("go") => {
+                self.stop();
+                self.go(&tokens.collect::<Vec<&str>>());
+            }
+            Some("stop") => self.stop(),
+            Some("quit") => {
+                self.stop();
+                return false;
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    /// Stops the running search and waits until it sends the best [`Move`]
+    ///
+    /// The search keeps the best [`Move`] of its last complete iteration
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.wait();
+    }
+
+    /// Waits until the running search sends the best [`Move`], E.g. at the end of the input
+    ///
+    /// A search without a limit is stopped first
+    pub fn wait(&mut self) {
+        if self.infinite {
+            self.stop.store(true, Ordering::Relaxed);
+        }
+
+        if let Some(search) = self.search.take() {
+            search.join().ok();
+        }
+    }
+
+    /// Sets the `Hash`, the `EvalFile` or the `UCI_Chess960` option, E.g. `name Hash value 64`
+    fn set_option(&mut self, tokens: &[&str]) {
+        let value_idx = tokens.iter().position(|token| *token == "value");
+        let name = tokens[..value_idx.unwrap_or(tokens.len())]
+            .iter()
+            .skip_while(|token| **token == "name")
+            .copied()
+            .collect::<Vec<&str>>()
+            .join(" ");
+        let value = value_idx
+            .map(|idx| tokens[idx + 1..].join(" "))
+            .unwrap_or_default();
+
+        match name.to_lowercase().as_str() {
+            "hash" => match value.parse::<usize>() {
+                Ok(megabytes) => self
+                    .lock_engine()
+                    .set_hash_size(megabytes.clamp(1, MAX_HASH_SIZE)),
+                Err(_) => send(&self.out, &format!("info string Invalid Hash: '{}'", value)),
+            },
+            "evalfile" => {
+                let params = if value.is_empty() || value == "<empty>" {
+                    Ok(EvalParams::default())
+                } else {
+                    EvalParams::load(&value)
+                };
+
+                match params {
+                    Ok(params) => self.lock_engine().set_params(params),
+                    Err(error) => send(&self.out, &format!("info string {}", error)),
+                }
+            }
+            "uci_chess960" => match value.as_str() {
+                "true" => self.chess960 = true,
+                "false" => self.chess960 = false,
+                _ => send(
+                    &self.out,
+                    &format!("info string Invalid UCI_Chess960: '{}'", value),
+                ),
+            },
+            _ => send(
+                &self.out,
+                &format!("info string Unknown option: '{}'", name),
+            ),
+        }
+    }
+
+    /// Sets the position, E.g. `startpos moves e2e4 e7e5` or `fen <fen> moves e2e4`
+    ///
+    /// The position stays unchanged on an error
+    fn set_position(&mut self, tokens: &[&str]) -> Result<(), String> {
+        let moves_idx = tokens
+            .iter()
+            .position(|token| *token == "moves")
+            .unwrap_or(tokens.len());
+
+        let fen = match tokens.first() {
+            Some(&"startpos") => START_FEN.to_string(),
+            Some(&"fen") => tokens[1..moves_idx].join(" "),
+            _ => return Err(format!("Invalid position: '{}'", tokens.join(" "))),
+        };
+
+        let mut start_board = Board::from_fen(&fen).map_err(|error| error.to_string())?;
+        if self.chess960 {
+            start_board.set_chess960(true);
+        }
+        let mut game = Game::from_board(start_board.clone());
+        game.set_adjudicating(false);
+        let mut moves = Vec::new();
+
+        for lan in tokens.iter().skip(moves_idx + 1) {
+            let chess_move = game
+                .board_state
+                .parse_lan(lan)
+                .map_err(|error| error.to_string())?;
+
+            if !game.play_move(&chess_move) {
+                return Err(format!("Illegal move: '{}'", lan));
+            }
+            moves.push(chess_move);
+        }
+
+        self.start_board = start_board;
+        self.moves = moves;
+        Ok(())
+    }
+
+    /// Starts the search on its own thread, E.g. `depth 6`, `nodes 10000`, `movetime 1000` or `wtime 60000 btime 60000 winc 1000`
+    ///
+    /// Without a limit the search runs until `stop`
+    fn go(&mut self, tokens: &[&str]) {
+        let value = |name: &str| {
+            tokens
+                .iter()
+                .position(|token| *token == name)
+                .and_then(|idx| tokens.get(idx + 1))
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+
+        let side = match self.moves.len() % 2 {
+            0 => self.start_board.get_current_turn(),
+            _ => self.start_board.get_current_turn().oppose(),
+        };
+        let (time, inc) = match side {
+            Side::White => (value("wtime"), value("winc")),
+            Side::Black => (value("btime"), value("binc")),
+        };
+
+        let mut limit = if let Some(movetime) = value("movetime") {
+            Limit::time(Duration::from_millis(movetime))
+        } else if let Some(time) = time {
+            Limit::clock(
+                Duration::from_millis(time),
+                Duration::from_millis(inc.unwrap_or(0)),
+                value("movestogo").map(|moves_to_go| moves_to_go as u32),
+            )
+        } else {
+            Limit::depth(u32::MAX)
+        };
+
+        if let Some(depth) = value("depth") {
+            limit.depth = depth as u32;
+        }
+        limit.nodes = value("nodes");
+
+        // Without a limit the best move waits for `stop`
+        self.infinite = tokens.contains(&"infinite")
+            || (limit.time.is_none() && value("depth").is_none() && value("nodes").is_none());
+
+        let infinite = self.infinite;
+        let start_board = self.start_board.clone();
+        let moves = self.moves.clone();
+        let out = Arc::clone(&self.out);
+        let engine = Arc::clone(&self.engine);
+        let stop = Arc::clone(&self.stop);
+        stop.store(false, Ordering::Relaxed);
+
+        self.search = Some(thread::spawn(move || {
+            let start = Instant::now();
+
+            // A Game can't leave its thread, it is played again here
+            let mut game = Game::from_board(start_board);
+            game.set_adjudicating(false);
+            for chess_move in &moves {
+                game.play_move(chess_move);
+            }
+
+            let mut engine = engine.lock().unwrap_or_else(|error| error.into_inner());
+            let result = engine.search_with(&game, limit, |result| {
+                send(&out, &info(result, start.elapsed()));
+            });
+
+            if infinite {
+                while !stop.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(5));
+                }
+            }
+
+            match result {
+                Some(result) => send(&out, &format!("bestmove {}", result.best_move.to_lan())),
+                None => send(&out, "bestmove 0000"),
+            }
+        }));
+    }
+
+    /// Gives back the [`Engine`], even when a search thread panicked with it
+    fn lock_engine(&self) -> MutexGuard<'_, Engine> {
+        self.engine
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl<W: Write + Send + 'static> Drop for Uci<W> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Writes a line to `out`, right away
+fn send<W: Write>(out: &Mutex<W>, line: &str) {
+    let mut out = out.lock().unwrap_or_else(|error| error.into_inner());
+    writeln!(out, "{}", line).ok();
+    out.flush().ok();
+}
+
+/// Gives back the `info` line of a complete iteration, E.g. `info depth 5 score cp 25 nodes 9000 nps 450000 time 20 pv e2e4 e7e5`
+fn info(result: &SearchResult, elapsed: Duration) -> String {
+    let score = match result.get_mate() {
+        Some(mate) => format!("mate {}", mate),
+        None => format!("cp {}", result.score),
+    };
+    let millis = elapsed.as_millis() as u64;
+    let nps = result.nodes * 1000 / millis.max(1);
+    let pv = result
+        .pv
+        .iter()
+        .map(|chess_move| chess_move.to_lan())
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    format!(
+        "info depth {} score {} nodes {} nps {} time {} pv {}",
+        result.depth, score, result.nodes, nps, millis, pv
+    )
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_uci {
+    use super::*;
+
+    /// Shared buffer, the answers stay readable after the [`Uci`] took the writer
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Buffer {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(String::from)
+                .collect()
+        }
+    }
+
+    fn run(commands: &[&str]) -> Vec<String> {
+        let buffer = Buffer::default();
+        let mut uci = Uci::new(buffer.clone());
+        for command in commands {
+            uci.execute(command);
+        }
+        uci.wait();
+
+        buffer.lines()
+    }
+
+    #[test]
+    fn test_handshake() {
+        let lines = run(&["uci", "isready"]);
+
+        assert!(lines[0].starts_with("id name chess-rs"));
+        assert!(lines.contains(&String::from(
+            "option name Hash type spin default 4 min 1 max 1024"
+        )));
+        assert_eq!(lines[lines.len() - 2], "uciok");
+        assert_eq!(lines[lines.len() - 1], "readyok");
+    }
+
+    #[test]
+    fn test_go_depth() {
+        let lines = run(&["position startpos moves e2e4 e7e5", "go depth 3"]);
+
+        assert!(lines[0].starts_with("info depth 1 score cp "));
+        assert!(lines[2].starts_with("info depth 3 "));
+        assert!(lines[2].contains(" pv "));
+        assert!(lines[3].starts_with("bestmove "));
+
+        let board = Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2")
+            .unwrap();
+        assert!(board.parse_lan(&lines[3][9..]).is_ok());
+    }
+
+    #[test]
+    fn test_mate() {
+        let lines = run(&[
+            "position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+            "go depth 3",
+        ]);
+
+        assert!(lines[0].contains("score mate 1"));
+        assert_eq!(lines.last().unwrap(), "bestmove a1a8");
+    }
+
+    #[test]
+    fn test_position_moves() {
+        // Black to move after 1. e4, the Knight answers
+        let lines = run(&[
+            "position fen 7k/8/8/8/8/8/4P3/4K1n1 w - - 0 1 moves e2e4",
+            "go depth 2",
+        ]);
+        let bestmove = lines.last().unwrap();
+        assert!(bestmove.starts_with("bestmove g1") || bestmove.starts_with("bestmove h8"));
+
+        let lines = run(&["position startpos moves e2e5", "isready"]);
+        assert_eq!(lines, ["info string Illegal move: 'e2e5'", "readyok"]);
+    }
+
+    #[test]
+    fn test_draw_position() {
+        // The GUI adjudicates the draws, the engine still plays a move
+        let lines = run(&["position fen 8/8/8/8/8/8/8/K1k5 w - - 0 1", "go depth 2"]);
+        assert_eq!(lines.last().unwrap(), "bestmove a1a2");
+
+        let lines = run(&[
+            "position fen 8/8/8/8/8/8/8/K1k5 w - - 0 1 moves a1a2 c1c2",
+            "go depth 2",
+        ]);
+        assert!(lines.last().unwrap().starts_with("bestmove a2"));
+
+        // No move is played after the checkmate
+        let lines = run(&[
+            "position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1 moves a1a8 g8h8",
+            "isready",
+        ]);
+        assert_eq!(lines, ["info string Illegal move: 'g8h8'", "readyok"]);
+    }
+
+    #[test]
+    fn test_chess960() {
+        let position = "position fen 6k1/8/8/8/8/8/8/4K2R w K - 0 1 moves e1h1";
+
+        let lines = run(&[position, "isready"]);
+        assert_eq!(lines, ["info string Illegal move: 'e1h1'", "readyok"]);
+
+        let lines = run(&[
+            "setoption name UCI_Chess960 value true",
+            position,
+            "isready",
+        ]);
+        assert_eq!(lines, ["readyok"]);
+    }
+
+    #[test]
+    fn test_infinite() {
+        let buffer = Buffer::default();
+        let mut uci = Uci::new(buffer.clone());
+        uci.execute("position startpos");
+        uci.execute("go infinite");
+        thread::sleep(Duration::from_millis(50));
+        assert!(!buffer
+            .lines()
+            .iter()
+            .any(|line| line.starts_with("bestmove")));
+
+        uci.execute("isready");
+        uci.execute("stop");
+        let lines = buffer.lines();
+        assert!(lines.contains(&String::from("readyok")));
+        assert!(lines.last().unwrap().starts_with("bestmove "));
+    }
+
+    #[test]
+    fn test_movetime() {
+        let start = Instant::now();
+        let lines = run(&["position startpos", "go wtime 1000 btime 1000"]);
+
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert!(lines.last().unwrap().starts_with("bestmove "));
+    }
+
+    #[test]
+    fn test_go_nodes() {
+        let lines = run(&["position startpos", "go nodes 5000"]);
+        assert!(lines.last().unwrap().starts_with("bestmove "));
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut uci = Uci::new(Buffer::default());
+        assert!(uci.execute("setoption name Hash value 1"));
+        assert!(!uci.execute("quit"));
+    }
+}
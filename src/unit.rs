@@ -29,6 +29,9 @@ impl Unit {
     pub const QUEEN: Unit = Unit::Queen(Side::Black);
     pub const KING: Unit = Unit::King(Side::Black, false);
 
+    /// Unit Types a Pawn can promote to
+    pub const PROMOTIONS: [Unit; 4] = [Unit::QUEEN, Unit::ROOK, Unit::BISHOP, Unit::KNIGHT];
+
     /// Gives back the name of the [`Unit`]
     pub fn get_name(self) -> String {
         match self {
@@ -83,6 +86,46 @@ impl Unit {
         }
     }
 
+    /// Gives back the FEN character of the [`Unit`], uppercase for White and lowercase for Black
+    ///
+    /// See also [from_fen_char](#method.from_fen_char)
+    pub fn get_fen_char(&self) -> char {
+        let c = match self {
+            Self::Pawn(..) => 'p',
+            Self::Bishop(..) => 'b',
+            Self::Knight(..) => 'n',
+            Self::Rook(..) => 'r',
+            Self::Queen(..) => 'q',
+            Self::King(..) => 'k',
+        };
+
+        match self.get_side() {
+            Side::Black => c,
+            Side::White => c.to_ascii_uppercase(),
+        }
+    }
+
+    /// Produces a [`Unit`] from its FEN character, `Moved` is always false
+    ///
+    /// See also [get_fen_char](#method.get_fen_char)
+    pub fn from_fen_char(c: char) -> Option<Self> {
+        let side = if c.is_ascii_uppercase() {
+            Side::White
+        } else {
+            Side::Black
+        };
+
+        match c.to_ascii_lowercase() {
+            'p' => Some(Self::Pawn(side, false)),
+            'b' => Some(Self::Bishop(side)),
+            'n' => Some(Self::Knight(side)),
+            'r' => Some(Self::Rook(side, false)),
+            'q' => Some(Self::Queen(side)),
+            'k' => Some(Self::King(side, false)),
+            _ => None,
+        }
+    }
+
     /// Gives back the [Side] of the [`Unit`]
     pub fn get_side(&self) -> Side {
         match self {
@@ -123,6 +166,18 @@ impl Unit {
         }
     }
 
+    /// Gives back the [`Unit`] with the same type and `Moved` status for the given `side`
+    pub fn set_side(&self, side: Side) -> Self {
+        match self {
+            Unit::Pawn(_, moved) => Unit::Pawn(side, *moved),
+            Unit::Bishop(_) => Unit::Bishop(side),
+            Unit::Knight(_) => Unit::Knight(side),
+            Unit::Rook(_, moved) => Unit::Rook(side, *moved),
+            Unit::Queen(_) => Unit::Queen(side),
+            Unit::King(_, moved) => Unit::King(side, *moved),
+        }
+    }
+
     /// Change the current type of [`Unit`] to the type of `other_unit`
     pub fn change_type(&self, other_unit: &Unit) -> Self {
         match other_unit {
@@ -138,15 +193,15 @@ impl Unit {
 
 /// Checks if the two [`Unit`]s are the same type
 pub fn eq_unit_type(unit1: &Unit, unit2: &Unit) -> bool {
-    match (unit1, unit2) {
-        (Unit::Pawn(..), Unit::Pawn(..)) => true,
-        (Unit::Bishop(..), Unit::Bishop(..)) => true,
-        (Unit::Knight(..), Unit::Knight(..)) => true,
-        (Unit::Rook(..), Unit::Rook(..)) => true,
-        (Unit::Queen(..), Unit::Queen(..)) => true,
-        (Unit::King(..), Unit::King(..)) => true,
-        _ => false,
-    }
+    matches!(
+        (unit1, unit2),
+        (Unit::Pawn(..), Unit::Pawn(..))
+            | (Unit::Bishop(..), Unit::Bishop(..))
+            | (Unit::Knight(..), Unit::Knight(..))
+            | (Unit::Rook(..), Unit::Rook(..))
+            | (Unit::Queen(..), Unit::Queen(..))
+            | (Unit::King(..), Unit::King(..))
+    )
 }
 
 impl fmt::Display for Unit {
@@ -239,6 +294,28 @@ mod tests_unit {
     }
 
     #[test]
+    fn test_side() {
+        assert_eq!(
+            Unit::Rook(Side::Black, true).set_side(Side::White),
+            Unit::Rook(Side::White, true)
+        );
+        assert_eq!(Unit::QUEEN.set_side(Side::White), Unit::Queen(Side::White));
+    }
+
+    #[test]
+    fn test_fen_char() {
+        assert_eq!(Unit::Knight(Side::White).get_fen_char(), 'N');
+        assert_eq!(Unit::Pawn(Side::Black, true).get_fen_char(), 'p');
+        assert_eq!(Unit::from_fen_char('q'), Some(Unit::Queen(Side::Black)));
+        assert_eq!(
+            Unit::from_fen_char('K'),
+            Some(Unit::King(Side::White, false))
+        );
+        assert_eq!(Unit::from_fen_char('x'), None);
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_unit_type() {
         let unit1 = Unit::Pawn(Side::Black, true);
         let mut unit2 = Unit::Pawn(Side::Black, true);
@@ -0,0 +1,415 @@
+// Standard Crate
+use std::fmt;
+
+// Chess Crate
+use super::chess::{Board, MoveImage};
+use super::game::{EndReason, Outcome};
+use super::moves::Move;
+use super::pos::Pos;
+use super::unit::{Side, Unit};
+
+/// Squares in the middle of the board, the King reaching one of them wins King of the Hill
+const HILL: [Pos; 4] = [
+    Pos::new(3, 3),
+    Pos::new(4, 3),
+    Pos::new(3, 4),
+    Pos::new(4, 4),
+];
+
+/// Number of checks a side has to give to win Three-check
+const CHECKS_TO_WIN: u8 = 3;
+
+/// Start position of Racing Kings, both sides start on the first two rows without Pawns
+const RACING_KINGS_FEN: &str = "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1";
+
+//==================================================
+//=== Variant
+//==================================================
+
+/// Rules of a chess variant, played by a [`Game`](crate::game::Game), see [`Game::with_variant`](crate::game::Game::with_variant)
+///
+/// The [`Board`] keeps the standard move rules, the variant can take back some of the legal [`Move`]s
+/// and ends the game by its own win conditions, every method falls back to the standard rules
+pub trait Variant: fmt::Debug + Send + Sync {
+    /// Gives back the name of the variant, E.g. "King of the Hill"
+    fn get_name(&self) -> &'static str;
+
+    /// Gives back the [`Board`] a new game starts from
+    fn get_start_board(&self) -> Board {
+        let mut board = Board::default();
+        self.set_up_board(&mut board);
+
+        board
+    }
+
+    /// Switches on the rules the [`Board`] keeps for the variant, E.g. the Crazyhouse pockets,
+    /// called on the [`Board`] every [`Game`](crate::game::Game) of the variant starts from
+    fn set_up_board(&self, _board: &mut Board) {}
+
+    /// Returns true when the legal [`Move`] on the `board` is allowed by the variant too
+    fn test_move(&self, _board: &Board, _chess_move: &Move) -> bool {
+        true
+    }
+
+    /// Gives back the [`Outcome`] when the variant ends the game on the `board`,
+    /// the `history` holds the [`MoveImage`] of every [`Move`] leading to it
+    ///
+    /// Checked before the standard endings, E.g. CheckMate or StaleMate
+    fn test_ending(&self, _board: &Board, _history: &[MoveImage]) -> Option<Outcome> {
+        None
+    }
+
+    /// Returns true when neither side can win anymore, see [`Board::test_insufficient_material`]
+    fn test_insufficient_material(&self, board: &Board) -> bool {
+        board.test_insufficient_material()
+    }
+}
+
+//==================================================
+//=== Variant: Standard
+//==================================================
+
+/// The standard rules of chess
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Standard;
+
+impl Variant for Standard {
+    fn get_name(&self) -> &'static str {
+        "Standard"
+    }
+}
+
+//==================================================
+//=== Variant: King of the Hill
+//==================================================
+
+/// The side whose King reaches one of the middle squares (D4, E4, D5, E5) wins
+///
+/// A lone King can still walk up the hill, so the material never runs out
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct KingOfTheHill;
+
+impl Variant for KingOfTheHill {
+    fn get_name(&self) -> &'static str {
+        "King of the Hill"
+    }
+
+    fn test_ending(&self, board: &Board, _history: &[MoveImage]) -> Option<Outcome> {
+        let side = board.get_current_turn().oppose();
+
+        board
+            .get_king_pos(&side)
+            .filter(|king_pos| HILL.contains(king_pos))
+            .map(|_| Outcome {
+                winner: Some(side),
+                reason: EndReason::Variant(self.get_name()),
+            })
+    }
+
+    fn test_insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+}
+
+//==================================================
+//=== Variant: Three-check
+//==================================================
+
+/// The side which puts the enemy King in check for the third time wins
+///
+/// The checks are counted by the [`Board`], see [`Board::set_three_check`],
+/// only a board with two lone Kings runs out of material
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ThreeCheck;
+
+impl Variant for ThreeCheck {
+    fn get_name(&self) -> &'static str {
+        "Three-check"
+    }
+
+    fn set_up_board(&self, board: &mut Board) {
+        board.set_three_check(true);
+    }
+
+    fn test_ending(&self, board: &Board, _history: &[MoveImage]) -> Option<Outcome> {
+        let side = board.get_current_turn().oppose();
+
+        (board.get_checks(&side) >= CHECKS_TO_WIN).then_some(Outcome {
+            winner: Some(side),
+            reason: EndReason::Variant(self.get_name()),
+        })
+    }
+
+    fn test_insufficient_material(&self, board: &Board) -> bool {
+        board
+            .get_squares()
+            .iter()
+            .flatten()
+            .flatten()
+            .all(|unit| matches!(unit, Unit::King(..)))
+    }
+}
+
+//==================================================
+//=== Variant: Racing Kings
+//==================================================
+
+/// The side whose King reaches the last row (8) first wins, no [`Move`] can put a King in check
+///
+/// When White reaches the last row, Black gets one more [`Move`] to reach it too, which ends in a draw
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RacingKings;
+
+impl RacingKings {
+    /// Returns true when the King of `side` stands on the last row
+    fn test_finished(board: &Board, side: &Side) -> bool {
+        board
+            .get_king_pos(side)
+            .is_some_and(|king_pos| king_pos.y == 0)
+    }
+}
+
+impl Variant for RacingKings {
+    fn get_name(&self) -> &'static str {
+        "Racing Kings"
+    }
+
+    fn get_start_board(&self) -> Board {
+        Board::from_fen(RACING_KINGS_FEN).unwrap_or_default()
+    }
+
+    fn test_move(&self, board: &Board, chess_move: &Move) -> bool {
+        !board.test_gives_check(chess_move)
+    }
+
+    fn test_ending(&self, board: &Board, _history: &[MoveImage]) -> Option<Outcome> {
+        let reason = EndReason::Variant(self.get_name());
+        let white = Self::test_finished(board, &Side::White);
+        let black = Self::test_finished(board, &Side::Black);
+
+        let winner = match (white, black) {
+            (true, true) => None,
+            (false, true) => Some(Side::Black),
+            (true, false) => {
+                // Black can still catch up with a King step to the last row
+                let catch_up = board.get_current_turn() == Side::Black
+                    && board.legal_moves(&Side::Black).iter().any(|chess_move| {
+                        matches!(chess_move.unit, Unit::King(..))
+                            && chess_move.target_pos.y == 0
+                            && self.test_move(board, chess_move)
+                    });
+                if catch_up {
+                    return None;
+                }
+
+                Some(Side::White)
+            }
+            (false, false) => return None,
+        };
+
+        Some(Outcome { winner, reason })
+    }
+
+    fn test_insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+}
+
+//==================================================
+//=== Variant: Crazyhouse
+//==================================================
+
+/// Captured [`Unit`]s change side and can be dropped back onto the board instead of a [`Move`]
+///
+/// The rules are kept by the [`Board`], see [`Board::set_crazyhouse`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Crazyhouse;
+
+impl Variant for Crazyhouse {
+    fn get_name(&self) -> &'static str {
+        "Crazyhouse"
+    }
+
+    fn set_up_board(&self, board: &mut Board) {
+        board.set_crazyhouse(true);
+    }
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_variant {
+    use super::*;
+    use crate::engine::{Engine, Limit};
+    use crate::game::{Game, GameState};
+
+    /// Plays the moves written in Standard Algebraic Notation
+    fn play(game: &mut Game, moves: &[&str]) {
+        for san in moves {
+            game.play_san(san).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_standard() {
+        let game = Game::with_variant(Standard);
+
+        assert_eq!(game.get_variant().get_name(), "Standard");
+        assert_eq!(game.board_state, Board::default());
+        assert_eq!(game.legal_moves().len(), 20);
+    }
+
+    #[test]
+    fn test_king_of_the_hill() {
+        let mut game = Game::with_variant(KingOfTheHill);
+        play(&mut game, &["e3", "e6", "Ke2", "Ke7", "Kd3", "Kd6"]);
+        assert_eq!(game.get_game_state(), GameState::Playing);
+
+        play(&mut game, &["Kd4"]);
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+        assert_eq!(
+            game.get_outcome().unwrap().to_string(),
+            "White won by King of the Hill!"
+        );
+    }
+
+    #[test]
+    fn test_king_of_the_hill_material() {
+        let board = Board::from_fen("8/8/8/8/8/8/k7/7K w - - 0 1").unwrap();
+
+        assert!(board.test_insufficient_material());
+        assert!(!KingOfTheHill.test_insufficient_material(&board));
+    }
+
+    #[test]
+    fn test_three_check() {
+        let mut game = Game::with_variant(ThreeCheck);
+        play(&mut game, &["e4", "e5", "Bb5", "c6", "Bxc6", "dxc6"]);
+        assert_eq!(game.board_state.get_checks(&Side::White), 0);
+
+        play(&mut game, &["Qh5", "Nf6", "Qxf7+", "Kxf7"]);
+        assert_eq!(game.board_state.get_checks(&Side::White), 1);
+
+        play(&mut game, &["d4", "Bb4+", "c3", "Bxc3+", "Nxc3", "Qxd4"]);
+        assert_eq!(game.board_state.get_checks(&Side::Black), 2);
+        assert_eq!(game.get_game_state(), GameState::Playing);
+        assert!(game.to_fen().ends_with(" +1+2"));
+
+        play(&mut game, &["Nf3", "Qxf2+"]);
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::Black)));
+        assert_eq!(
+            game.get_end_reason(),
+            Some(EndReason::Variant("Three-check"))
+        );
+
+        // Taking back the third check lets the game go on
+        assert!(game.undo());
+        assert_eq!(game.get_game_state(), GameState::Playing);
+        assert_eq!(game.board_state.get_checks(&Side::Black), 2);
+    }
+
+    #[test]
+    fn test_three_check_fen() {
+        // The checks of the FEN count without any history
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0").unwrap();
+        let mut game = Game::from_board_variant(board, ThreeCheck);
+        assert_eq!(game.get_game_state(), GameState::Playing);
+
+        play(&mut game, &["Ra8+"]);
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+
+        // A board without the checks starts counting from zero
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        let mut game = Game::from_board_variant(board, ThreeCheck);
+        play(&mut game, &["Ra8+"]);
+        assert_eq!(game.to_fen(), "R3k3/8/8/8/8/8/8/4K3 b - - 1 1 +1+0");
+    }
+
+    #[test]
+    fn test_three_check_material() {
+        let board = Board::from_fen("8/8/8/8/8/8/kn6/7K w - - 0 1").unwrap();
+
+        assert!(board.test_insufficient_material());
+        assert!(!ThreeCheck.test_insufficient_material(&board));
+    }
+
+    #[test]
+    fn test_racing_kings_start() {
+        let game = Game::with_variant(RacingKings);
+
+        assert_eq!(game.to_fen(), RACING_KINGS_FEN);
+        assert_eq!(game.get_game_state(), GameState::Playing);
+        assert!(game
+            .legal_moves()
+            .iter()
+            .all(|chess_move| !game.board_state.test_gives_check(chess_move)));
+        assert!(game.legal_moves().len() < game.board_state.legal_moves(&Side::White).len());
+    }
+
+    #[test]
+    fn test_racing_kings_no_check() {
+        let mut game = Game::from_board_variant(
+            Board::from_fen("8/8/8/8/8/8/k7/6RK w - - 0 1").unwrap(),
+            RacingKings,
+        );
+
+        // The Rook can't give check on the A column or the second row
+        assert!(game.play_san("Ra1").is_err());
+        assert!(game.play_san("Rg2").is_err());
+        assert!(game.play_san("Rb1").is_ok());
+    }
+
+    #[test]
+    fn test_racing_kings_win() {
+        let mut game = Game::from_board_variant(
+            Board::from_fen("8/6K1/8/8/8/8/k7/8 w - - 0 1").unwrap(),
+            RacingKings,
+        );
+        play(&mut game, &["Kg8"]);
+
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::White)));
+    }
+
+    #[test]
+    fn test_racing_kings_draw() {
+        // Black answers on the last row
+        let mut game = Game::from_board_variant(
+            Board::from_fen("8/k5K1/8/8/8/8/8/8 w - - 0 1").unwrap(),
+            RacingKings,
+        );
+        play(&mut game, &["Kg8"]);
+        assert_eq!(game.get_game_state(), GameState::Playing);
+
+        play(&mut game, &["Ka8"]);
+        assert_eq!(game.get_game_state(), GameState::Ending(None));
+
+        // Black reaches the last row first
+        let mut game = Game::from_board_variant(
+            Board::from_fen("8/k5K1/8/8/8/8/8/8 b - - 0 1").unwrap(),
+            RacingKings,
+        );
+        play(&mut game, &["Kb8"]);
+        assert_eq!(game.get_game_state(), GameState::Ending(Some(Side::Black)));
+    }
+
+    #[test]
+    fn test_engine_follows_variant() {
+        // The King walks up the hill instead of taking the Knight
+        let game = Game::from_board_variant(
+            Board::from_fen("7k/8/8/8/8/2K5/1n6/8 w - - 0 1").unwrap(),
+            KingOfTheHill,
+        );
+        let result = Engine::new().search(&game, Limit::depth(2)).unwrap();
+        assert_eq!(result.best_move.get_unit_target(), Pos::from_notation("D4"));
+
+        // The Rook can't win the race by giving check
+        let game = Game::from_board_variant(
+            Board::from_fen("8/8/8/8/8/8/k7/6RK w - - 0 1").unwrap(),
+            RacingKings,
+        );
+        let result = Engine::new().search(&game, Limit::depth(3)).unwrap();
+        assert!(!game.board_state.test_gives_check(&result.best_move));
+    }
+}
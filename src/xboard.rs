@@ -0,0 +1,481 @@
+// Standard Crate
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+// Chess Crate
+use super::chess::Board;
+use super::chess960::CHESS960_STANDARD;
+use super::engine::{Engine, Limit, SearchResult};
+use super::game::{Game, GameState};
+use super::moves::{Move, MoveKind};
+use super::unit::Side;
+
+/// Moves of a time control until the clock gets its time again, before the GUI sends `level`
+const DEFAULT_MOVES_PER_SESSION: u32 = 40;
+
+/// Time of a time control, before the GUI sends `level`
+const DEFAULT_BASE_TIME: Duration = Duration::from_secs(5 * 60);
+
+//==================================================
+//=== XBoard
+//==================================================
+
+/// Speaks the Chess Engine Communication Protocol (XBoard/WinBoard) with a GUI, the answers go to `out`
+///
+/// Commands come one line at a time through [execute](#method.execute),
+/// the [`Engine`] thinks right away when it is its turn and answers with its move
+///
+/// * `out` where the answers are written, E.g. stdout
+/// * `engine` searches the moves
+/// * `game` current [`Game`], its history serves `undo` and `remove`
+/// * `engine_side` side the [`Engine`] plays, `None` in force mode
+/// * `post` sends the thinking output of each complete iteration
+/// * `moves_per_session` moves of a time control, 0 when the whole game is one session
+/// * `increment` time added to the clock after each move
+/// * `time_left` time left on the clock of the [`Engine`]
+/// * `move_time` fixed time of each move, set by `st`
+/// * `depth` maximum depth, set by `sd`
+/// * `chess960` plays Fischer Random Chess after `variant fischerandom`, the Castles are written as `O-O` and `O-O-O`
+pub struct Xboard<W: Write> {
+    out: W,
+    engine: Engine,
+    game: Game,
+    engine_side: Option<Side>,
+    post: bool,
+    moves_per_session: u32,
+    increment: Duration,
+    time_left: Duration,
+    move_time: Option<Duration>,
+    depth: Option<u32>,
+    chess960: bool,
+}
+
+impl<W: Write> Xboard<W> {
+    /// Creates a new [`Xboard`] in the start position, the [`Engine`] plays Black
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            engine: Engine::new(),
+            game: Game::new(),
+            engine_side: Some(Side::Black),
+            post: false,
+            moves_per_session: DEFAULT_MOVES_PER_SESSION,
+            increment: Duration::ZERO,
+            time_left: DEFAULT_BASE_TIME,
+            move_time: None,
+            depth: None,
+            chess960: false,
+        }
+    }
+
+    /// Gives back the current [`Game`]
+    pub fn get_game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Executes a command of the GUI, E.g. `usermove e2e4` or `level 40 5 0`
+    ///
+    /// Returns false after `quit`
+    pub fn execute(&mut self, line: &str) -> bool {
+        let (command, args) = match line.trim().split_once(' ') {
+            Some((command, args)) => (command, args.trim()),
+            None => (line.trim(), ""),
+        };
+
+        match command {
+            "protover" => {
+                self.send(&format!(
+                    "feature myname=\"chess-rs {}\" ping=1 setboard=1 usermove=1 playother=1 \
+                     san=0 colors=0 sigint=0 sigterm=0 analyze=0 reuse=1 \
+                     variants=\"normal,fischerandom\" done=1",
+                    env!("CARGO_PKG_VERSION")
+                ));
+            }
+            "new" => {
+                self.game = Game::new();
+                self.engine.clear();
+                self.engine_side = Some(Side::Black);
+                self.depth = None;
+                self.chess960 = false;
+            }
+            "variant" => match args {
+                "normal" => {
+                    self.game = Game::new();
+                    self.chess960 = false;
+                }
+                "fischerandom" => {
+                    self.game = Game::new_chess960(CHESS960_STANDARD).unwrap_or_default();
+                    self.chess960 = true;
+                }
+                _ => self.send(&format!("Error (unsupported variant): {}", args)),
+            },
+            "force" | "result" => self.engine_side = None,
+            "go" => {
+                self.engine_side = Some(self.game.get_current_turn());
+                self.think();
+            }
+            "playother" => self.engine_side = Some(self.game.get_current_turn().oppose()),
+            "usermove" => self.user_move(args),
+            "setboard" => match Board::from_fen(args) {
+                Ok(mut board) => {
+                    if self.chess960 {
+                        board.set_chess960(true);
+                    }
+                    self.game = Game::from_board(board);
+                }
+                Err(error) => self.send(&format!("tellusererror Illegal position: {}", error)),
+            },
+            "undo" => {
+                self.game.undo();
+            }
+            "remove" => {
+                self.game.undo();
+                self.game.undo();
+            }
+            "level" => self.set_level(args),
+            "st" => match args.parse::<f64>() {
+                Ok(seconds) if seconds > 0.0 => {
+                    self.move_time = Some(Duration::from_secs_f64(seconds))
+                }
+                _ => self.send(&format!("Error (invalid time): {}", line)),
+            },
+            "sd" => match args.parse::<u32>() {
+                Ok(depth) => self.depth = Some(depth),
+                Err(_) => self.send(&format!("Error (invalid depth): {}", line)),
+            },
+            "time" => match args.parse::<u64>() {
+                Ok(centiseconds) => self.time_left = Duration::from_millis(centiseconds * 10),
+                Err(_) => self.send(&format!("Error (invalid time): {}", line)),
+            },
+            "ping" => self.send(&format!("pong {}", args)),
+            "post" => self.post = true,
+            "nopost" => self.post = false,
+            "quit" => return false,
+            // Commands which change nothing here
+            "" | "xboard" | "accepted" | "rejected" | "otim" | "hard" | "easy" | "random"
+            | "computer" | "name" | "rating" | "draw" | "?" => {}
+            _ => self.send(&format!("Error (unknown command): {}", command)),
+        }
+
+        true
+    }
+
+    /// Plays the move of the opponent, E.g. `e2e4`, `e7e8q` or `O-O`, then the [`Engine`] answers
+    fn user_move(&mut self, lan: &str) {
+        if self.game.get_game_state() != GameState::Playing {
+            self.send(&format!("Illegal move (game over): {}", lan));
+            return;
+        }
+
+        let chess_move = match lan.starts_with(['O', '0']) {
+            true => self.game.board_state.parse_san(lan),
+            false => self.game.board_state.parse_lan(lan),
+        };
+
+        match chess_move {
+            Ok(chess_move) => {
+                self.game.play_move(&chess_move);
+            }
+            Err(_) => {
+                self.send(&format!("Illegal move: {}", lan));
+                return;
+            }
+        }
+
+        if !self.send_result() {
+            self.think();
+        }
+    }
+
+    /// Sets a conventional or an incremental time control, E.g. `40 5 0` or `0 0:30 2`
+    fn set_level(&mut self, args: &str) {
+        let fields = args.split_whitespace().collect::<Vec<&str>>();
+
+        let base = fields.get(1).and_then(|base| match base.split_once(':') {
+            Some((minutes, seconds)) => {
+                Some(minutes.parse::<u64>().ok()? * 60 + seconds.parse::<u64>().ok()?)
+            }
+            None => Some(base.parse::<u64>().ok()? * 60),
+        });
+
+        let increment = fields
+            .get(2)
+            .and_then(|increment| increment.parse::<f64>().ok())
+            .filter(|increment| *increment >= 0.0);
+
+        match (
+            fields.first().map(|mps| mps.parse::<u32>()),
+            base,
+            increment,
+        ) {
+            (Some(Ok(moves_per_session)), Some(base), Some(increment)) if fields.len() == 3 => {
+                self.moves_per_session = moves_per_session;
+                self.increment = Duration::from_secs_f64(increment);
+                self.time_left = Duration::from_secs(base);
+                self.move_time = None;
+            }
+            _ => self.send(&format!("Error (invalid level): level {}", args)),
+        }
+    }
+
+    /// Gives back the [`Limit`] of the next search, from `st`, `sd` and the clock
+    fn get_limit(&self) -> Limit {
+        let mut limit = match self.move_time {
+            Some(move_time) => Limit::time(move_time),
+            None => {
+                let moves_to_go = match self.moves_per_session {
+                    0 => None,
+                    moves => {
+                        let played = self.game.board_state.get_fullmove_number() - 1;
+                        Some(moves - played % moves)
+                    }
+                };
+
+                Limit::clock(self.time_left, self.increment, moves_to_go)
+            }
+        };
+
+        if let Some(depth) = self.depth {
+            limit.depth = limit.depth.min(depth);
+        }
+
+        limit
+    }
+
+    /// Searches and plays the move of the [`Engine`], when it is its turn
+    fn think(&mut self) {
+        if self.engine_side != Some(self.game.get_current_turn()) {
+            return;
+        }
+
+        let limit = self.get_limit();
+        let post = self.post;
+        let out = &mut self.out;
+        let start = Instant::now();
+
+        let result = self.engine.search_with(&self.game, limit, |result| {
+            if post {
+                writeln!(out, "{}", thinking(result, start.elapsed())).ok();
+                out.flush().ok();
+            }
+        });
+
+        if let Some(result) = result {
+            self.game.play_move(&result.best_move);
+            let notation = self.get_notation(&result.best_move);
+            self.send(&format!("move {}", notation));
+
+            // The repetition and the fifty-move rule end the game at once, the GUI does not claim for it
+            if self.game.get_claimable_draw().is_some() {
+                self.game.claim_draw();
+            }
+        }
+
+        self.send_result();
+    }
+
+    /// Gives back the notation of the `chess_move` sent to the GUI, E.g. `e2e4`, or `O-O` for a Chess960 Castle
+    fn get_notation(&self, chess_move: &Move) -> String {
+        match chess_move.kind {
+            MoveKind::CastleLeft if self.chess960 => String::from("O-O-O"),
+            MoveKind::CastleRight if self.chess960 => String::from("O-O"),
+            _ => chess_move.to_lan(),
+        }
+    }
+
+    /// Sends the result when the game is over, E.g. `1-0 {White won by CheckMate!}`
+    ///
+    /// Returns true when the game is over
+    fn send_result(&mut self) -> bool {
+        let Some(outcome) = self.game.get_outcome() else {
+            return false;
+        };
+
+        let result = match outcome.winner {
+            Some(Side::White) => "1-0",
+            Some(Side::Black) => "0-1",
+            None => "1/2-1/2",
+        };
+        self.send(&format!("{} {{{}}}", result, outcome));
+
+        true
+    }
+
+    /// Writes a line to `out`, right away
+    fn send(&mut self, line: &str) {
+        writeln!(self.out, "{}", line).ok();
+        self.out.flush().ok();
+    }
+}
+
+/// Gives back the thinking output of a complete iteration, E.g. `5 25 20 9000 e2e4 e7e5`
+///
+/// The fields are the depth, the score in centipawns, the time in centiseconds, the nodes and the principal variation
+fn thinking(result: &SearchResult, elapsed: Duration) -> String {
+    let pv = result
+        .pv
+        .iter()
+        .map(|chess_move| chess_move.to_lan())
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    format!(
+        "{} {} {} {} {}",
+        result.depth,
+        result.score,
+        elapsed.as_millis() / 10,
+        result.nodes,
+        pv
+    )
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_xboard {
+    use super::*;
+
+    fn run(commands: &[&str]) -> (Vec<String>, Xboard<Vec<u8>>) {
+        let mut xboard = Xboard::new(Vec::new());
+        for command in commands {
+            xboard.execute(command);
+        }
+
+        let lines = String::from_utf8(xboard.out.clone())
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+
+        (lines, xboard)
+    }
+
+    #[test]
+    fn test_protover() {
+        let (lines, _) = run(&["xboard", "protover 2", "ping 7"]);
+
+        assert!(lines[0].starts_with("feature myname=\"chess-rs "));
+        assert!(lines[0].ends_with(" done=1"));
+        assert_eq!(lines[1], "pong 7");
+    }
+
+    #[test]
+    fn test_usermove() {
+        let (lines, xboard) = run(&["new", "sd 2", "usermove e2e4"]);
+
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("move "));
+        assert_eq!(xboard.get_game().get_ply(), 2);
+        assert_eq!(xboard.get_game().get_current_turn(), Side::White);
+    }
+
+    #[test]
+    fn test_force() {
+        let (lines, xboard) = run(&["new", "force", "usermove e2e4", "usermove e7e5"]);
+
+        assert!(lines.is_empty());
+        assert_eq!(xboard.get_game().get_ply(), 2);
+
+        let (lines, xboard) = run(&["new", "force", "usermove e2e4", "sd 2", "go"]);
+        assert!(lines[0].starts_with("move "));
+        assert_eq!(xboard.get_game().get_ply(), 2);
+    }
+
+    #[test]
+    fn test_illegal_move() {
+        let (lines, xboard) = run(&["new", "usermove e2e5", "usermove e2"]);
+
+        assert_eq!(lines, ["Illegal move: e2e5", "Illegal move: e2"]);
+        assert_eq!(xboard.get_game().get_ply(), 0);
+    }
+
+    #[test]
+    fn test_undo_remove() {
+        let (_, xboard) = run(&[
+            "new",
+            "force",
+            "usermove e2e4",
+            "usermove e7e5",
+            "usermove g1f3",
+            "undo",
+        ]);
+        assert_eq!(xboard.get_game().get_ply(), 2);
+
+        let (_, xboard) = run(&["new", "sd 2", "usermove e2e4", "remove"]);
+        assert_eq!(xboard.get_game().get_ply(), 0);
+        assert_eq!(xboard.get_game().board_state, crate::chess::Board::new());
+    }
+
+    #[test]
+    fn test_setboard_mate() {
+        let (lines, _) = run(&[
+            "new",
+            "force",
+            "setboard 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+            "post",
+            "sd 3",
+            "go",
+        ]);
+
+        assert!(lines[0].starts_with("1 "));
+        assert_eq!(lines[lines.len() - 2], "move a1a8");
+        assert_eq!(lines[lines.len() - 1], "1-0 {White won by CheckMate!}");
+    }
+
+    #[test]
+    fn test_fischerandom() {
+        let (lines, xboard) = run(&[
+            "new",
+            "variant fischerandom",
+            "force",
+            "setboard 4k3/8/8/8/8/8/8/5K1R w H - 0 1",
+            "usermove O-O",
+        ]);
+        assert!(lines.is_empty());
+        assert_eq!(xboard.get_game().to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+
+        let (lines, _) = run(&[
+            "new",
+            "variant fischerandom",
+            "force",
+            "setboard 4rkr1/4p1p1/8/8/8/8/8/5K1R w H - 0 1",
+            "sd 2",
+            "go",
+        ]);
+        assert_eq!(lines, ["move O-O", "1-0 {White won by CheckMate!}"]);
+
+        let (lines, _) = run(&["variant crazyhouse"]);
+        assert_eq!(lines, ["Error (unsupported variant): crazyhouse"]);
+    }
+
+    #[test]
+    fn test_level() {
+        let (_, mut xboard) = run(&["level 40 0:30 0", "time 3000"]);
+        assert_eq!(xboard.get_limit().time, Some(Duration::from_millis(750)));
+
+        xboard.execute("level 0 2 12");
+        assert_eq!(xboard.get_limit().time, Some(Duration::from_secs(13)));
+
+        xboard.execute("st 2");
+        xboard.execute("sd 4");
+        assert_eq!(
+            xboard.get_limit(),
+            Limit {
+                depth: 4,
+                ..Limit::time(Duration::from_secs(2))
+            }
+        );
+
+        let (lines, _) = run(&["level 40", "sd x", "foo"]);
+        assert_eq!(
+            lines,
+            [
+                "Error (invalid level): level 40",
+                "Error (invalid depth): sd x",
+                "Error (unknown command): foo"
+            ]
+        );
+    }
+}
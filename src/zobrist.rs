@@ -0,0 +1,424 @@
+// Chess Crate
+use super::bitboard::{self, Bitboard};
+use super::chess::Board;
+use super::crazyhouse::{Pocket, POCKET_LIMIT};
+use super::pos::Pos;
+use super::unit::{Side, Unit};
+
+/// Seed of the random keys, changing it changes every hash
+const SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// Random keys of each [`Unit`] type and [`Side`] on each square, indexed by [`Unit::get_id`] and `y * 8 + x`
+const UNIT_KEYS: [[u64; 64]; Unit::UNIT_COUNT] = unit_keys();
+
+/// Random key of Black in the current turn
+pub(crate) const SIDE_KEY: u64 = splitmix64(SEED ^ 1);
+
+/// Random keys of the castling rights, in the same order as the FEN: `K`, `Q`, `k`, `q`
+const CASTLING_KEYS: [u64; 4] = [
+    splitmix64(SEED ^ 2),
+    splitmix64(SEED ^ 3),
+    splitmix64(SEED ^ 4),
+    splitmix64(SEED ^ 5),
+];
+
+/// Random keys of the En Passant column
+const EN_PASSANT_KEYS: [u64; 8] = en_passant_keys();
+
+/// Random keys of the number of [`Unit`]s in the Crazyhouse pockets, indexed by [`Unit::get_id`] and the count
+const POCKET_KEYS: [[u64; POCKET_LIMIT as usize + 1]; Unit::UNIT_COUNT] = pocket_keys();
+
+/// Random keys of the promoted [`Unit`]s on each square, indexed by `y * 8 + x`
+const PROMOTED_KEYS: [u64; 64] = promoted_keys();
+
+/// Highest number of Three-check checks with its own key, more checks share it
+const CHECK_LIMIT: usize = 3;
+
+/// Random keys of the number of checks given on a Three-check [`Board`], indexed by [`Side`] and the count
+const CHECK_KEYS: [[u64; CHECK_LIMIT + 1]; 2] = check_keys();
+
+//==================================================
+//=== Zobrist
+//==================================================
+
+impl Board {
+    /// Calculates the Zobrist hash of the position from scratch, see [get_hash](#method.get_hash)
+    ///
+    /// Gives back the same hash as [get_hash](#method.get_hash), which is kept up to date by each change
+    pub fn compute_hash(&self) -> u64 {
+        let mut hash = match self.get_current_turn() {
+            Side::Black => SIDE_KEY,
+            Side::White => 0,
+        };
+
+        for (y, row) in self.get_squares().iter().enumerate() {
+            for (x, unit) in row.iter().enumerate() {
+                if let Some(unit) = unit {
+                    hash ^= unit_key(unit, &Pos::new(x as i8, y as i8));
+                }
+            }
+        }
+
+        hash ^ self.get_castling_key()
+            ^ self.get_en_passant_key()
+            ^ self.get_crazyhouse_key()
+            ^ self.get_three_check_key()
+    }
+
+    /// Gives back the keys which depend on the squares of the [`Unit`]s, the castling rights and the En Passant column
+    pub(crate) fn get_square_key(&self) -> u64 {
+        self.get_castling_key() ^ self.get_en_passant_key()
+    }
+
+    /// Gives back the keys of the castling rights
+    pub(crate) fn get_castling_key(&self) -> u64 {
+        self.get_castling_rights()
+            .iter()
+            .zip(CASTLING_KEYS)
+            .filter(|(right, _)| **right)
+            .fold(0, |key, (_, castling_key)| key ^ castling_key)
+    }
+
+    /// Gives back the castling rights in the same order as the FEN: `K`, `Q`, `k`, `q`
+    ///
+    /// The King and the Rook both have to be unmoved
+    pub(crate) fn get_castling_rights(&self) -> [bool; 4] {
+        self.get_castling_rooks().map(|rook_pos| rook_pos.is_some())
+    }
+
+    /// Gives back the [`Pos`] of the Rook of each castling right in the same order as the FEN: `K`, `Q`, `k`, `q`
+    ///
+    /// * Standard: the unmoved King on E1 / E8 and the unmoved Rook in the corner
+    /// * Chess960: the unmoved King on its back row and the outermost unmoved Rook on each side of it
+    pub(crate) fn get_castling_rooks(&self) -> [Option<Pos>; 4] {
+        let mut rooks = [None; 4];
+
+        for (idx, side) in [Side::White, Side::Black].into_iter().enumerate() {
+            let king_pos = match self.get_king_pos(&side) {
+                Some(pos) if pos.y == 7 * (1 - idx as i8) => pos,
+                _ => continue,
+            };
+            if self.get_unit(&king_pos) != Some(Unit::King(side, false)) {
+                continue;
+            }
+
+            let is_rook =
+                |x: &i8| self.get_unit(&Pos::new(*x, king_pos.y)) == Some(Unit::Rook(side, false));
+            let (right, left) = if self.is_chess960() {
+                (
+                    (king_pos.x + 1..8).rev().find(is_rook),
+                    (0..king_pos.x).find(is_rook),
+                )
+            } else if king_pos.x == 4 {
+                (Some(7).filter(is_rook), Some(0).filter(is_rook))
+            } else {
+                continue;
+            };
+
+            rooks[idx * 2] = right.map(|x| Pos::new(x, king_pos.y));
+            rooks[idx * 2 + 1] = left.map(|x| Pos::new(x, king_pos.y));
+        }
+
+        rooks
+    }
+
+    /// Gives back the keys of the pockets and the promoted [`Unit`]s, only on a Crazyhouse [`Board`]
+    pub(crate) fn get_crazyhouse_key(&self) -> u64 {
+        if !self.is_crazyhouse() {
+            return 0;
+        }
+
+        let mut key = 0;
+        for side in [Side::Black, Side::White] {
+            key ^= pocket_key(&side, self.get_pocket(&side));
+        }
+
+        for pos in self.get_promoted() {
+            key ^= promoted_key(&pos);
+        }
+
+        key
+    }
+
+    /// Gives back the keys of the number of checks, only on a Three-check [`Board`]
+    pub(crate) fn get_three_check_key(&self) -> u64 {
+        if !self.is_three_check() {
+            return 0;
+        }
+
+        [Side::Black, Side::White]
+            .iter()
+            .fold(0, |key, side| key ^ check_key(side, self.get_checks(side)))
+    }
+
+    /// Gives back the key of the En Passant column
+    ///
+    /// Only counts when a Pawn of the side in the current turn stands next to the Pawn which stepped 2 squares
+    pub(crate) fn get_en_passant_key(&self) -> u64 {
+        let en_passant_pos = match self.get_en_passant_pos() {
+            Some(pos) if pos.is_onboard() => pos,
+            _ => return 0,
+        };
+
+        let side = self.get_current_turn();
+        let attackers = bitboard::attacks(
+            &Unit::Pawn(side.oppose(), false),
+            &en_passant_pos,
+            Bitboard::EMPTY,
+        );
+
+        if (attackers & self.get_bitboards().get_units(&Unit::PAWN, &side)).is_empty() {
+            0
+        } else {
+            EN_PASSANT_KEYS[en_passant_pos.x as usize]
+        }
+    }
+}
+
+/// Gives back the key of the `unit` standing at `pos`
+pub(crate) fn unit_key(unit: &Unit, pos: &Pos) -> u64 {
+    UNIT_KEYS[unit.get_id() as usize][(pos.y * 8 + pos.x) as usize]
+}
+
+/// Gives back the keys of the number of each [`Unit`] in the [`Pocket`] of `side`
+pub(crate) fn pocket_key(side: &Side, pocket: &Pocket) -> u64 {
+    Pocket::UNITS.iter().fold(0, |key, unit| {
+        let count = pocket.get_count(unit) as usize;
+        key ^ POCKET_KEYS[unit.set_side(*side).get_id() as usize][count]
+    })
+}
+
+/// Gives back the key of the promoted [`Unit`] standing at `pos`
+pub(crate) fn promoted_key(pos: &Pos) -> u64 {
+    PROMOTED_KEYS[(pos.y * 8 + pos.x) as usize]
+}
+
+/// Gives back the key of the number of checks given by `side`
+pub(crate) fn check_key(side: &Side, checks: u8) -> u64 {
+    CHECK_KEYS[*side as usize][(checks as usize).min(CHECK_LIMIT)]
+}
+
+//==================================================
+//=== Keys
+//==================================================
+
+/// Mixes the bits of `value`, gives back well spread 64-bit numbers for following values
+pub(crate) const fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const fn unit_keys() -> [[u64; 64]; Unit::UNIT_COUNT] {
+    let mut keys = [[0; 64]; Unit::UNIT_COUNT];
+
+    let mut unit = 0;
+    while unit < Unit::UNIT_COUNT {
+        let mut square = 0;
+        while square < 64 {
+            keys[unit][square] = splitmix64(SEED ^ (0x100 + (unit * 64 + square) as u64));
+            square += 1;
+        }
+        unit += 1;
+    }
+
+    keys
+}
+
+const fn en_passant_keys() -> [u64; 8] {
+    let mut keys = [0; 8];
+
+    let mut x = 0;
+    while x < 8 {
+        keys[x] = splitmix64(SEED ^ (0x10 + x as u64));
+        x += 1;
+    }
+
+    keys
+}
+
+const fn pocket_keys() -> [[u64; POCKET_LIMIT as usize + 1]; Unit::UNIT_COUNT] {
+    let mut keys = [[0; POCKET_LIMIT as usize + 1]; Unit::UNIT_COUNT];
+
+    // An empty pocket has no key
+    let mut unit = 0;
+    while unit < Unit::UNIT_COUNT {
+        let mut count = 1;
+        while count <= POCKET_LIMIT as usize {
+            keys[unit][count] = splitmix64(SEED ^ (0x1000 + (unit * 32 + count) as u64));
+            count += 1;
+        }
+        unit += 1;
+    }
+
+    keys
+}
+
+const fn promoted_keys() -> [u64; 64] {
+    let mut keys = [0; 64];
+
+    let mut square = 0;
+    while square < 64 {
+        keys[square] = splitmix64(SEED ^ (0x2000 + square as u64));
+        square += 1;
+    }
+
+    keys
+}
+
+const fn check_keys() -> [[u64; CHECK_LIMIT + 1]; 2] {
+    let mut keys = [[0; CHECK_LIMIT + 1]; 2];
+
+    // No check has no key
+    let mut side = 0;
+    while side < 2 {
+        let mut count = 1;
+        while count <= CHECK_LIMIT {
+            keys[side][count] = splitmix64(SEED ^ (0x3000 + (side * 8 + count) as u64));
+            count += 1;
+        }
+        side += 1;
+    }
+
+    keys
+}
+
+//==================================================
+//=== Unit Testing
+//==================================================
+
+#[cfg(test)]
+mod tests_zobrist {
+    use crate::chess::Board;
+    use crate::game::Game;
+    use crate::pos::Pos;
+    use crate::unit::Side;
+
+    fn play(moves: &[&str]) -> Board {
+        let mut game = Game::new();
+        for san in moves {
+            game.play_san(san).unwrap();
+        }
+
+        game.board_state
+    }
+
+    #[test]
+    fn test_transposition() {
+        let board1 = play(&["Nf3", "Nf6", "g3", "g6"]);
+        let board2 = play(&["g3", "g6", "Nf3", "Nf6"]);
+
+        assert_eq!(board1.get_hash(), board2.get_hash());
+        assert_ne!(board1.get_hash(), Board::new().get_hash());
+    }
+
+    #[test]
+    fn test_incremental() {
+        let board = play(&["e4", "d5", "exd5", "Qxd5", "Nc3", "Qa5", "d4", "Nf6"]);
+        assert_eq!(board.get_hash(), board.compute_hash());
+
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        for chess_move in board.legal_moves(&board.get_current_turn()) {
+            let mut board_state = board.clone();
+            let move_image = board_state.make_move_image(&chess_move).unwrap();
+            assert_eq!(board_state.get_hash(), board_state.compute_hash());
+
+            board_state.unmake_move(&move_image);
+            assert_eq!(board_state.get_hash(), board.get_hash());
+        }
+    }
+
+    #[test]
+    fn test_incremental_status() {
+        let sequences: [&[&str]; 3] = [
+            // Castle on both sides
+            &[
+                "e4", "e5", "Nf3", "Nc6", "Bc4", "Bc5", "O-O", "d6", "d3", "Be6", "Nc3", "Qd7",
+                "a3", "O-O-O",
+            ],
+            // The castling rights are lost by a King step, a Rook step and a captured Rook
+            &[
+                "g3", "b6", "Bg2", "Nf6", "Nh3", "e6", "Rg1", "Ke7", "Bxa8", "Bb7",
+            ],
+            // En Passant captures on both sides and a Pawn 2 Step which can't be captured
+            &[
+                "e4", "a6", "e5", "d5", "exd6", "cxd6", "h4", "b5", "h5", "b4", "c4", "bxc3", "h6",
+                "Nf6", "hxg7", "h5",
+            ],
+        ];
+
+        for moves in sequences {
+            let mut game = Game::new();
+            for san in moves {
+                let board = game.board_state.clone();
+                game.play_san(san).unwrap();
+                assert_eq!(
+                    game.board_state.get_hash(),
+                    game.board_state.compute_hash(),
+                    "{}",
+                    san
+                );
+
+                let chess_move = game.history().last().unwrap().chess_move;
+                let mut board_state = board.clone();
+                let move_image = board_state.make_move_image(&chess_move).unwrap();
+                board_state.unmake_move(&move_image);
+                assert_eq!(board_state.get_hash(), board.get_hash(), "{}", san);
+            }
+        }
+    }
+
+    #[test]
+    fn test_step() {
+        let mut board = Board::new();
+        let hash = board.get_hash();
+
+        assert!(board.test_step(&Pos::from_notation("G1"), &Pos::from_notation("F3")));
+        assert_eq!(board.get_hash(), board.compute_hash());
+        assert!(board.test_step(&Pos::from_notation("G8"), &Pos::from_notation("F6")));
+        assert!(board.test_step(&Pos::from_notation("F3"), &Pos::from_notation("G1")));
+        assert!(board.test_step(&Pos::from_notation("F6"), &Pos::from_notation("G8")));
+        assert_eq!(board.get_hash(), hash);
+    }
+
+    #[test]
+    fn test_side() {
+        let mut board = Board::new();
+        let hash = board.get_hash();
+
+        board.set_current_turn(Side::Black);
+        assert_ne!(board.get_hash(), hash);
+        assert_eq!(board.get_hash(), board.compute_hash());
+    }
+
+    #[test]
+    fn test_castling_rights() {
+        // The King steps away and back, the castling rights are lost
+        let board1 = play(&["e4", "e5", "Ke2", "Ke7", "Ke1", "Ke8"]);
+        let board2 = play(&["e4", "e5"]);
+        assert_ne!(board1.get_hash(), board2.get_hash());
+
+        // The Rooks stepping away and back after that do not change the rights
+        let board3 = play(&[
+            "e4", "e5", "Ke2", "Ke7", "Ke1", "Ke8", "Nf3", "Nf6", "Rg1", "Rg8", "Rh1", "Rh8",
+            "Ng1", "Ng8",
+        ]);
+        assert_eq!(board1.get_hash(), board3.get_hash());
+    }
+
+    #[test]
+    fn test_en_passant() {
+        // No Black Pawn can capture, the En Passant square does not count
+        let board1 = play(&["e4"]);
+        let board2 =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert_eq!(board1.get_hash(), board2.get_hash());
+
+        let board1 = play(&["e4", "Nf6", "e5", "d5"]);
+        let board2 = play(&["e4", "d5", "e5", "Nf6"]);
+        assert_ne!(board1.get_hash(), board2.get_hash());
+    }
+}